@@ -3,12 +3,19 @@
 //! Provides a rate-limited HTTP client that respects server limits
 //! and implements exponential backoff for transient errors.
 
-use std::sync::Arc;
+use regex::Regex;
+use reqwest::cookie::{CookieStore, Jar};
+use scraper::{Html, Selector};
+use std::sync::{Arc, LazyLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::{Mutex, Semaphore};
 use tokio::time::sleep;
 
 use crate::error::{PrehrajtoError, Result};
+use crate::parser::direct_url::is_cdn_url;
+use crate::text::fold_text;
 
 /// Configuration for the HTTP client
 #[derive(Debug, Clone)]
@@ -19,6 +26,102 @@ pub struct ClientConfig {
     pub timeout_secs: u64,
     /// Maximum retry attempts for transient errors (default: 3)
     pub max_retries: u32,
+    /// Base URL of the site (default: "https://prehraj.to")
+    ///
+    /// Mainly useful for pointing the client at a mirror or a local mock server.
+    pub base_url: String,
+    /// Strip tracking query params from returned CDN URLs (default: false)
+    ///
+    /// See [`crate::url::strip_cdn_tracking`] for which params are kept.
+    pub clean_cdn_urls: bool,
+    /// Refetch a page once if it parses to nothing and looks truncated (default: false)
+    ///
+    /// Applies to `search`, `get_direct_url` and `get_video_sources`. A body
+    /// is considered truncated when it has no closing `</html>` tag.
+    pub refetch_on_empty_parse: bool,
+    /// Delay between the two steps of the original-file download flow (default: 0)
+    ///
+    /// Some backends need a moment for the session cookie set by the video
+    /// page to become active before `?do=download` accepts it.
+    pub download_flow_delay_ms: u64,
+    /// Maximum number of requests issued concurrently by batch operations (default: 4)
+    ///
+    /// Requests are still spaced by the rate limiter; this only caps how many
+    /// can be in flight at once (e.g., HEAD probes for source sizes).
+    pub max_concurrent_requests: usize,
+    /// Header added to every request to the site, e.g. `("X-Request-Id", "…")` (default: none)
+    ///
+    /// Useful for correlating requests in proxy logs. CDN hosts never get
+    /// it. Can be overridden per logical operation with [`with_correlation`].
+    pub correlation_header: Option<(String, String)>,
+    /// Send `Accept-Encoding: identity` on byte-exact requests (default: true)
+    ///
+    /// Applies to the download page, HEAD size probes and
+    /// [`PrehrajtoClient::fetch_bytes`], so `Content-Length` and ranges
+    /// describe the actual bytes rather than a compressed transfer.
+    pub identity_encoding_for_downloads: bool,
+    /// Maximum bytes [`PrehrajtoClient::fetch_bytes`] and
+    /// [`PrehrajtoClient::download_to`] may download per session (default: none)
+    ///
+    /// Once exhausted, downloads fail with `BudgetExceeded` until
+    /// [`PrehrajtoClient::reset_session_bytes`] is called. A failed
+    /// `fetch_bytes` attempt counts nothing, so retries aren't double-counted.
+    pub session_byte_budget: Option<u64>,
+    /// Reject pages that don't look like prehraj.to (default: false)
+    ///
+    /// Guards against captive portals, ISP block pages and a wrong
+    /// `base_url` yielding empty or garbage results. Scraper methods then
+    /// fail with `ParseError` when [`crate::is_prehrajto_page`] finds no
+    /// site markers. Leave off for mirrors or mock servers without them.
+    pub verify_site_pages: bool,
+    /// Keep cookies between requests (default: true)
+    ///
+    /// Required by the download flow, which relies on the session cookies set
+    /// by the video page: with this off, [`crate::PrehrajtoScraper::get_original_url`]
+    /// and [`crate::PrehrajtoScraper::get_download_options`] fail with
+    /// `CookiesDisabled`. Turn off to keep the client stateless.
+    pub cookie_store: bool,
+    /// Random delay range `(min, max)` in ms slept before each page fetch (default: none)
+    ///
+    /// Makes request timing look less mechanical. Applies on top of the rate
+    /// limiter to site pages and the download page, not to CDN requests.
+    pub human_delay_ms: Option<(u64, u64)>,
+    /// Maximum number of [`PrehrajtoClient::download_to`] streams running at once (default: 2)
+    ///
+    /// Separate from `max_concurrent_requests`: further downloads wait for a
+    /// free slot before sending their request, so many parallel downloads
+    /// can't saturate the link or trip the CDN. Values below 1 are treated as 1.
+    pub max_concurrent_downloads: usize,
+    /// Deadline for multi-step scraper operations as a whole, in ms (default: none)
+    ///
+    /// Applies to flows that make several requests:
+    /// [`crate::PrehrajtoScraper::get_download_options`] (and so
+    /// `get_original_url`), `get_full_video_info` and `play_first`. When it
+    /// passes they fail with `Timeout`. Independent of `timeout_secs`, which
+    /// bounds each request.
+    pub operation_timeout_ms: Option<u64>,
+    /// Fail fast after repeated identical failures (default: none)
+    ///
+    /// Once `failures` requests in a row fail with the same error, page
+    /// fetches, download pages and HEAD probes return that error without
+    /// touching the network until the cooldown passes (network errors come
+    /// back as `CircuitOpen` with the original status). 404s and parse errors
+    /// never count, as they don't mean the site is down. Stops a batch from
+    /// hammering the site after it starts rate limiting or blocking.
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+}
+
+/// Settings for [`ClientConfig::circuit_breaker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive identical failures that open the breaker (values below 1 are treated as 1)
+    pub failures: u32,
+    /// How long the breaker stays open, in milliseconds
+    ///
+    /// After it passes exactly one request is let through as a probe, while
+    /// the others keep failing fast. If the probe fails the same way the
+    /// breaker opens again straight away, any other outcome resets it.
+    pub cooldown_ms: u64,
 }
 
 impl Default for ClientConfig {
@@ -27,28 +130,91 @@ impl Default for ClientConfig {
             requests_per_second: 2.0,
             timeout_secs: 30,
             max_retries: 3,
+            base_url: BASE_URL.to_string(),
+            clean_cdn_urls: false,
+            refetch_on_empty_parse: false,
+            download_flow_delay_ms: 0,
+            max_concurrent_requests: 4,
+            correlation_header: None,
+            identity_encoding_for_downloads: true,
+            session_byte_budget: None,
+            verify_site_pages: false,
+            cookie_store: true,
+            human_delay_ms: None,
+            max_concurrent_downloads: 2,
+            operation_timeout_ms: None,
+            circuit_breaker: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Preset for normal interactive use
+    ///
+    /// One request per second with a few retries, which keeps well clear of
+    /// the site's rate limiting.
+    pub fn polite() -> Self {
+        Self {
+            requests_per_second: 1.0,
+            timeout_secs: 30,
+            max_retries: 3,
+            ..Default::default()
+        }
+    }
+
+    /// Preset for shared IPs (VPNs, CGNAT, servers running several clients)
+    ///
+    /// Other users on the same address also count against the limit, so
+    /// requests are spaced further apart and retried more patiently.
+    pub fn conservative() -> Self {
+        Self {
+            requests_per_second: 0.5,
+            timeout_secs: 60,
+            max_retries: 5,
+            ..Default::default()
+        }
+    }
+
+    /// Preset for local mock servers and tests
+    ///
+    /// Fast requests, short timeouts and no retries. Not meant for the live
+    /// site, which will start answering with rate limit pages.
+    pub fn aggressive() -> Self {
+        Self {
+            requests_per_second: 50.0,
+            timeout_secs: 5,
+            max_retries: 0,
+            ..Default::default()
         }
     }
 }
 
 /// Rate limiter to control request frequency
 ///
-/// Ensures requests are spaced at least `min_interval` apart.
+/// Ensures requests are spaced at least `min_interval` apart. The interval
+/// can be changed at runtime with [`RateLimiter::set_rate`].
 pub struct RateLimiter {
-    min_interval: Duration,
+    /// Minimum interval in nanoseconds, atomic so it can change on the fly
+    min_interval_nanos: AtomicU64,
     last_request: Arc<Mutex<Instant>>,
 }
 
 impl RateLimiter {
     /// Create a new rate limiter with the specified requests per second
     ///
+    /// Zero, negative and NaN rates are clamped to the slowest rate, one
+    /// request per hour; infinity disables the spacing.
+    ///
     /// # Arguments
     /// * `requests_per_second` - Maximum number of requests allowed per second
     pub fn new(requests_per_second: f64) -> Self {
-        let min_interval = Duration::from_secs_f64(1.0 / requests_per_second);
+        let min_interval = rate_interval(requests_per_second);
+        let last_request = Instant::now()
+            .checked_sub(min_interval)
+            .unwrap_or_else(Instant::now);
         Self {
-            min_interval,
-            last_request: Arc::new(Mutex::new(Instant::now() - min_interval)),
+            min_interval_nanos: AtomicU64::new(min_interval.as_nanos() as u64),
+            last_request: Arc::new(Mutex::new(last_request)),
         }
     }
 
@@ -56,24 +222,55 @@ impl RateLimiter {
     ///
     /// If called before the minimum interval has passed since the last request,
     /// this method will sleep until the interval has elapsed.
+    ///
+    /// The interval is read once the caller holds the limiter, so callers
+    /// still queued when [`Self::set_rate`] runs use the new interval; a
+    /// caller already sleeping finishes its current wait.
     pub async fn acquire(&self) {
         let mut last = self.last_request.lock().await;
         let elapsed = last.elapsed();
+        let min_interval = self.min_interval();
 
-        if elapsed < self.min_interval {
-            let wait_time = self.min_interval - elapsed;
+        if elapsed < min_interval {
+            let wait_time = min_interval - elapsed;
             sleep(wait_time).await;
         }
 
         *last = Instant::now();
     }
 
+    /// Change the allowed request rate
+    ///
+    /// Takes effect for the next [`Self::acquire`] without rebuilding the
+    /// client, so cookies and connections are kept. Invalid rates are
+    /// clamped as in [`Self::new`].
+    ///
+    /// # Arguments
+    /// * `requests_per_second` - New maximum number of requests per second
+    pub fn set_rate(&self, requests_per_second: f64) {
+        let min_interval = rate_interval(requests_per_second);
+        self.min_interval_nanos
+            .store(min_interval.as_nanos() as u64, Ordering::Relaxed);
+    }
+
     /// Get the minimum interval between requests
     pub fn min_interval(&self) -> Duration {
-        self.min_interval
+        Duration::from_nanos(self.min_interval_nanos.load(Ordering::Relaxed))
     }
 }
 
+/// Longest interval a [`RateLimiter`] waits between requests
+const MAX_RATE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Interval between requests for a rate, clamped to `0..=MAX_RATE_INTERVAL`
+fn rate_interval(requests_per_second: f64) -> Duration {
+    if requests_per_second.is_nan() || requests_per_second <= 0.0 {
+        return MAX_RATE_INTERVAL;
+    }
+    Duration::try_from_secs_f64(1.0 / requests_per_second)
+        .unwrap_or(MAX_RATE_INTERVAL)
+        .min(MAX_RATE_INTERVAL)
+}
 
 const BASE_URL: &str = "https://prehraj.to";
 const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
@@ -88,6 +285,32 @@ pub struct PrehrajtoClient {
     client: reqwest::Client,
     rate_limiter: RateLimiter,
     max_retries: u32,
+    base_url: String,
+    cookies: Arc<Jar>,
+    correlation_header: Option<(String, String)>,
+    identity_encoding_for_downloads: bool,
+    session_byte_budget: Option<u64>,
+    /// Bytes downloaded by `fetch_bytes` and `download_to` since creation or
+    /// the last reset
+    session_bytes: AtomicU64,
+    human_delay_ms: Option<(u64, u64)>,
+    /// Slots limiting concurrent `download_to` streams
+    download_slots: Semaphore,
+    circuit_breaker: Option<CircuitBreakerConfig>,
+    breaker_state: std::sync::Mutex<BreakerState>,
+    /// CDN hosts discovered at runtime, on top of the static list
+    cdn_hosts: std::sync::RwLock<Vec<String>>,
+}
+
+/// Consecutive identical failures seen by the circuit breaker
+#[derive(Default)]
+struct BreakerState {
+    /// Key of the last failure, see [`breaker_key`]
+    key: Option<String>,
+    /// The last failure, replayed while the breaker is open
+    error: Option<PrehrajtoError>,
+    count: u32,
+    open_until: Option<Instant>,
 }
 
 impl PrehrajtoClient {
@@ -98,10 +321,14 @@ impl PrehrajtoClient {
 
     /// Create a new client with custom configuration
     pub fn with_config(config: ClientConfig) -> Result<Self> {
-        let client = reqwest::Client::builder()
+        let cookies = Arc::new(Jar::default());
+        let mut builder = reqwest::Client::builder();
+        if config.cookie_store {
+            builder = builder.cookie_provider(Arc::clone(&cookies));
+        }
+        let client = builder
             .timeout(Duration::from_secs(config.timeout_secs))
             .user_agent(USER_AGENT)
-            .cookie_store(true)
             .redirect(reqwest::redirect::Policy::none())
             .default_headers({
                 let mut headers = reqwest::header::HeaderMap::new();
@@ -118,6 +345,17 @@ impl PrehrajtoClient {
             client,
             rate_limiter: RateLimiter::new(config.requests_per_second),
             max_retries: config.max_retries,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            cookies,
+            correlation_header: config.correlation_header,
+            identity_encoding_for_downloads: config.identity_encoding_for_downloads,
+            session_byte_budget: config.session_byte_budget,
+            session_bytes: AtomicU64::new(0),
+            human_delay_ms: config.human_delay_ms,
+            download_slots: Semaphore::new(config.max_concurrent_downloads.max(1)),
+            circuit_breaker: config.circuit_breaker,
+            breaker_state: std::sync::Mutex::new(BreakerState::default()),
+            cdn_hosts: std::sync::RwLock::new(Vec::new()),
         })
     }
 
@@ -126,21 +364,198 @@ impl PrehrajtoClient {
     /// Automatically follows redirects for non-CDN URLs (normal page navigation).
     ///
     /// # Arguments
-    /// * `path` - The path to fetch (e.g., "/search?q=test")
+    /// * `path` - The path to fetch (e.g., "/search?q=test"); normalized to
+    ///   exactly one leading slash with duplicate slashes collapsed
     ///
     /// # Returns
     /// The HTML content as a string, or an error if the request fails
     ///
     /// # Errors
     /// - `HttpError` - Network or HTTP errors
+    /// - `ConnectionFailed` - DNS resolution or connection failed after all retries
     /// - `RateLimited` - Server returned 429 after all retries exhausted
     pub async fn fetch(&self, path: &str) -> Result<String> {
-        let url = format!("{}{}", BASE_URL, path);
-        self.fetch_with_retry(&url).await
+        let url = format!("{}{}", self.base_url, normalize_path(path));
+        self.fetch_with_retry(&url, false).await
+    }
+
+    /// Fetch a page as an XHR request
+    ///
+    /// Same as [`Self::fetch`], but sends `X-Requested-With: XMLHttpRequest`
+    /// so the site answers with its AJAX payload (snippet JSON or an HTML
+    /// fragment) instead of the full page.
+    pub async fn fetch_xhr(&self, path: &str) -> Result<String> {
+        let url = format!("{}{}", self.base_url, normalize_path(path));
+        self.fetch_with_retry(&url, true).await
+    }
+
+    /// Fetch a page as a connectivity canary
+    ///
+    /// Same as [`Self::fetch`], but a 403 fails with `HttpError` instead of
+    /// returning the block page, so callers can tell a blocked IP apart from
+    /// a page that merely lacks the expected content.
+    pub(crate) async fn fetch_canary(&self, path: &str) -> Result<String> {
+        let url = format!("{}{}", self.base_url, normalize_path(path));
+        self.human_delay().await;
+        self.with_retry(|| self.do_fetch(&url, false, true)).await
+    }
+
+    /// Fetch raw bytes, for binary content such as thumbnails
+    ///
+    /// Goes through the same rate limiting and retries as [`Self::fetch`],
+    /// but returns the body untouched instead of decoding it as text.
+    /// Redirects are not followed.
+    ///
+    /// # Arguments
+    /// * `url` - Absolute URL (e.g. a thumbnail or CDN link), or a path
+    ///   relative to the base URL
+    ///
+    /// # Returns
+    /// The exact response body bytes
+    ///
+    /// # Errors
+    /// - `HttpError` - Network errors or a non-success status
+    /// - `ConnectionFailed` - DNS resolution or connection failed after all retries
+    /// - `NotFound` - Server returned 404
+    /// - `RateLimited` - Server returned 429 after all retries exhausted
+    /// - `BudgetExceeded` - The download would exceed
+    ///   [`ClientConfig::session_byte_budget`]
+    pub async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let url = self.absolute_url(url);
+        self.with_retry(|| self.do_fetch_bytes(&url)).await
+    }
+
+    /// Stream a file into `writer`, optionally resuming from `offset`
+    ///
+    /// Sends a `Range: bytes={offset}-` header when `offset` is non-zero; if
+    /// the server ignores it and sends the whole file, the first `offset`
+    /// bytes are skipped. Bytes count towards the session byte budget as
+    /// they arrive. The request is rate limited but not retried, since a
+    /// partial write can't be undone. At most
+    /// [`ClientConfig::max_concurrent_downloads`] downloads stream at once;
+    /// others wait for a slot first.
+    ///
+    /// Expired CDN links may answer 200 with an HTML error page; such a body
+    /// (by `Content-Type` or its first bytes) is rejected before anything is
+    /// written.
+    ///
+    /// # Arguments
+    /// * `url` - Absolute URL (e.g. a CDN link), or a path relative to the base URL
+    /// * `writer` - Destination for the body
+    /// * `offset` - Byte position to start from (0 for the whole file)
+    ///
+    /// # Returns
+    /// Number of bytes written to `writer`
+    ///
+    /// # Errors
+    /// - `DownloadInterrupted` - The connection dropped mid-body or `writer`
+    ///   failed; resume with `offset + bytes_written`
+    /// - `HttpError` - Network errors or a non-success status
+    /// - `ConnectionFailed` - DNS resolution or connection failed
+    /// - `NotFound` - Server returned 404 or an HTML page instead of the file
+    /// - `RateLimited` - Server returned 429
+    /// - `BudgetExceeded` - The download exceeded
+    ///   [`ClientConfig::session_byte_budget`]
+    pub async fn download_to<W>(&self, url: &str, writer: &mut W, offset: u64) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let url = self.absolute_url(url);
+        // The semaphore is never closed, so acquiring can't fail
+        let _slot = self.download_slots.acquire().await;
+        self.rate_limiter.acquire().await;
+
+        let mut request = self.byte_exact_request(reqwest::Method::GET, &url);
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+        let response = request.send().await.map_err(map_send_error)?;
+
+        let mut response = match response.status() {
+            reqwest::StatusCode::TOO_MANY_REQUESTS => return Err(PrehrajtoError::RateLimited),
+            reqwest::StatusCode::NOT_FOUND => return Err(PrehrajtoError::NotFound(url)),
+            _ => response
+                .error_for_status()
+                .map_err(PrehrajtoError::HttpError)?,
+        };
+        if is_html_content_type(response.headers()) {
+            return Err(html_instead_of_file(&url));
+        }
+
+        let mut sniffed = false;
+        let mut to_skip = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+            0
+        } else {
+            offset
+        };
+        let mut bytes_written = 0u64;
+        loop {
+            let chunk = match response.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(_) => return Err(PrehrajtoError::DownloadInterrupted { bytes_written }),
+            };
+            if !sniffed && !chunk.is_empty() {
+                if looks_like_html(&chunk) {
+                    return Err(html_instead_of_file(&url));
+                }
+                sniffed = true;
+            }
+
+            let skipped = usize::try_from(to_skip).map_or(chunk.len(), |n| n.min(chunk.len()));
+            to_skip -= skipped as u64;
+            let data = &chunk[skipped..];
+            if data.is_empty() {
+                continue;
+            }
+
+            self.commit_session_bytes(data.len() as u64)?;
+            if writer.write_all(data).await.is_err() {
+                return Err(PrehrajtoError::DownloadInterrupted { bytes_written });
+            }
+            bytes_written += data.len() as u64;
+        }
+
+        Ok(bytes_written)
+    }
+
+    /// Resolve a path against the base URL, leaving absolute URLs untouched
+    fn absolute_url(&self, url: &str) -> String {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            url.to_string()
+        } else {
+            format!("{}{}", self.base_url, normalize_path(url))
+        }
     }
 
     /// Internal method to fetch with retry logic
-    async fn fetch_with_retry(&self, url: &str) -> Result<String> {
+    async fn fetch_with_retry(&self, url: &str, xhr: bool) -> Result<String> {
+        self.human_delay().await;
+        self.with_retry(|| self.do_fetch(url, xhr, false)).await
+    }
+
+    /// Sleep for a random time within `human_delay_ms`, if configured
+    async fn human_delay(&self) {
+        if let Some(delay) = human_delay(self.human_delay_ms) {
+            sleep(delay).await;
+        }
+    }
+
+    /// Run a request attempt with rate limiting and exponential backoff
+    async fn with_retry<T, F, Fut>(&self, attempt_fn: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        self.guarded(self.retry_attempts(attempt_fn)).await
+    }
+
+    /// Retry loop behind [`Self::with_retry`]
+    async fn retry_attempts<T, F, Fut>(&self, mut attempt_fn: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
         let mut last_error: Option<PrehrajtoError> = None;
         let mut attempt = 0;
 
@@ -148,7 +563,7 @@ impl PrehrajtoClient {
             // Wait for rate limiter
             self.rate_limiter.acquire().await;
 
-            match self.do_fetch(url).await {
+            match attempt_fn().await {
                 Ok(body) => return Ok(body),
                 Err(e) => {
                     if Self::is_retryable(&e) && attempt < self.max_retries {
@@ -167,21 +582,69 @@ impl PrehrajtoClient {
         Err(last_error.unwrap_or(PrehrajtoError::RateLimited))
     }
 
+    /// Run a request through the circuit breaker, if one is configured
+    ///
+    /// Fails with the last error while the breaker is open, otherwise runs
+    /// `request` and records its outcome. The first request after the
+    /// cooldown is the probe: it pushes `open_until` one cooldown further, so
+    /// concurrent requests keep failing fast until it finishes (or, if it is
+    /// dropped, until the next cooldown lets another probe through).
+    async fn guarded<T>(&self, request: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+        let Some(config) = self.circuit_breaker else {
+            return request.await;
+        };
+        let cooldown = Duration::from_millis(config.cooldown_ms);
+
+        {
+            let mut state = self.breaker_state.lock().unwrap_or_else(|e| e.into_inner());
+            if let (Some(until), Some(error)) = (state.open_until, &state.error) {
+                let now = Instant::now();
+                if now < until {
+                    return Err(replay_error(error));
+                }
+                state.open_until = Some(now + cooldown);
+            }
+        }
+
+        let result = request.await;
+
+        let mut state = self.breaker_state.lock().unwrap_or_else(|e| e.into_inner());
+        let failure = result.as_ref().err();
+        match failure.and_then(|e| breaker_key(e).map(|key| (e, key))) {
+            Some((error, key)) => {
+                if state.key.as_ref() == Some(&key) {
+                    state.count += 1;
+                } else {
+                    state.key = Some(key);
+                    state.count = 1;
+                    state.open_until = None;
+                }
+                state.error = Some(replay_error(error));
+                if state.count >= config.failures.max(1) {
+                    state.open_until = Some(Instant::now() + cooldown);
+                }
+            }
+            None => *state = BreakerState::default(),
+        }
+        result
+    }
+
     /// Perform a single fetch attempt with manual redirect following
     ///
     /// Follows redirects for same-site URLs but stops for CDN URLs
-    /// to prevent accidentally downloading large binary files.
-    async fn do_fetch(&self, url: &str) -> Result<String> {
+    /// to prevent accidentally downloading large binary files. A 403 body is
+    /// returned like any other page unless `forbidden_is_error` is set.
+    async fn do_fetch(&self, url: &str, xhr: bool, forbidden_is_error: bool) -> Result<String> {
         let mut current_url = url.to_string();
         let max_redirects = 5;
 
         for _ in 0..max_redirects {
-            let response = self
-                .client
-                .get(&current_url)
-                .send()
-                .await
-                .map_err(PrehrajtoError::HttpError)?;
+            let mut request = self.request(reqwest::Method::GET, &current_url);
+            if xhr {
+                request = request.header("X-Requested-With", "XMLHttpRequest");
+            }
+
+            let response = request.send().await.map_err(map_send_error)?;
 
             let status = response.status();
 
@@ -193,7 +656,9 @@ impl PrehrajtoClient {
                 return Err(PrehrajtoError::NotFound(current_url));
             }
 
-            if status.is_server_error() {
+            if status.is_server_error()
+                || forbidden_is_error && status == reqwest::StatusCode::FORBIDDEN
+            {
                 return Err(PrehrajtoError::HttpError(
                     response.error_for_status().unwrap_err(),
                 ));
@@ -205,17 +670,24 @@ impl PrehrajtoClient {
                     && let Ok(loc_str) = location.to_str()
                 {
                     // Don't follow redirects to CDN (would download binary files)
-                    if loc_str.contains("premiumcdn.net") {
-                        return response.text().await.map_err(PrehrajtoError::HttpError);
+                    if is_cdn_url(loc_str, &self.cdn_hosts()) {
+                        return Self::read_body(response).await;
                     }
-                    current_url = loc_str.to_string();
+                    // Relative locations (e.g. `/slug/id`) resolve against the current URL
+                    current_url = reqwest::Url::parse(&current_url)
+                        .and_then(|base| base.join(loc_str))
+                        .map_or_else(|_| loc_str.to_string(), String::from);
                     continue;
                 }
                 // No Location header or can't parse — return the body as-is
-                return response.text().await.map_err(PrehrajtoError::HttpError);
+                return Self::read_body(response).await;
             }
 
-            return response.text().await.map_err(PrehrajtoError::HttpError);
+            let body = Self::read_body(response).await?;
+            if is_soft_rate_limited(&body) {
+                return Err(PrehrajtoError::RateLimited);
+            }
+            return Ok(body);
         }
 
         Err(PrehrajtoError::ParseError(
@@ -223,30 +695,193 @@ impl PrehrajtoClient {
         ))
     }
 
+    /// Perform a single binary fetch attempt
+    async fn do_fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .byte_exact_request(reqwest::Method::GET, url)
+            .send()
+            .await
+            .map_err(map_send_error)?;
+
+        match response.status() {
+            reqwest::StatusCode::TOO_MANY_REQUESTS => Err(PrehrajtoError::RateLimited),
+            reqwest::StatusCode::NOT_FOUND => Err(PrehrajtoError::NotFound(url.to_string())),
+            _ => {
+                let mut response = response
+                    .error_for_status()
+                    .map_err(PrehrajtoError::HttpError)?;
+
+                // Refuse up front when the size is announced
+                if let Some(budget) = self.session_byte_budget {
+                    let used = self.session_bytes.load(Ordering::Relaxed);
+                    let announced = response.content_length().unwrap_or(0);
+                    if used.saturating_add(announced) > budget {
+                        return Err(PrehrajtoError::BudgetExceeded(budget));
+                    }
+                }
+
+                // Otherwise stop reading as soon as a chunk doesn't fit. A
+                // failed attempt delivers nothing, so what it counted is
+                // refunded before the error (and any retry) goes out.
+                let mut bytes = Vec::new();
+                loop {
+                    let chunk = match response.chunk().await {
+                        Ok(Some(chunk)) => chunk,
+                        Ok(None) => return Ok(bytes),
+                        Err(e) => {
+                            self.refund_session_bytes(bytes.len() as u64);
+                            return Err(PrehrajtoError::HttpError(e));
+                        }
+                    };
+                    if let Err(e) = self.commit_session_bytes(chunk.len() as u64) {
+                        self.refund_session_bytes(bytes.len() as u64);
+                        return Err(e);
+                    }
+                    bytes.extend_from_slice(&chunk);
+                }
+            }
+        }
+    }
+
+    /// Count `bytes` towards the session byte budget
+    ///
+    /// Only adds them if they fit, so refused bytes never count as used.
+    ///
+    /// # Errors
+    /// - `BudgetExceeded` - The bytes would exceed
+    ///   [`ClientConfig::session_byte_budget`]
+    fn commit_session_bytes(&self, bytes: u64) -> Result<()> {
+        let budget = self.session_byte_budget;
+        self.session_bytes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                let used = used.saturating_add(bytes);
+                budget.is_none_or(|budget| used <= budget).then_some(used)
+            })
+            .map(|_| ())
+            .map_err(|_| PrehrajtoError::BudgetExceeded(budget.unwrap_or_default()))
+    }
+
+    /// Take `bytes` counted by a failed download back off the session budget
+    fn refund_session_bytes(&self, bytes: u64) {
+        let _ = self
+            .session_bytes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                Some(used.saturating_sub(bytes))
+            });
+    }
+
     /// Fetch a download page without following redirects
     ///
     /// The `?do=download` page returns 302 with an HTML body containing
     /// the CDN link. This uses the main cookie-bearing client but does
     /// NOT follow any redirects — returns the response body as-is.
     pub async fn fetch_download_page(&self, path: &str) -> Result<String> {
-        let url = format!("{}{}", BASE_URL, path);
+        let url = format!("{}{}", self.base_url, normalize_path(path));
+
+        self.human_delay().await;
+        self.guarded(async {
+            self.rate_limiter.acquire().await;
+
+            let response = self
+                .byte_exact_request(reqwest::Method::GET, &url)
+                .send()
+                .await
+                .map_err(map_send_error)?;
+
+            Self::read_body(response).await
+        })
+        .await
+    }
+
+    /// Get the size of a remote file with a HEAD request
+    ///
+    /// Used for CDN URLs, so `url` is absolute and not relative to the base URL.
+    ///
+    /// # Returns
+    /// The `Content-Length` in bytes, or `None` if the header is missing
+    ///
+    /// # Errors
+    /// - `HttpError` / `ConnectionFailed` for network errors or non-success status
+    /// - `NotFound` if the server answers with an HTML page (e.g. an expired
+    ///   CDN link) instead of the file
+    pub async fn head_content_length(&self, url: &str) -> Result<Option<u64>> {
+        self.guarded(self.do_head_content_length(url)).await
+    }
 
+    /// Single HEAD request behind [`Self::head_content_length`]
+    async fn do_head_content_length(&self, url: &str) -> Result<Option<u64>> {
         self.rate_limiter.acquire().await;
 
         let response = self
-            .client
-            .get(&url)
+            .byte_exact_request(reqwest::Method::HEAD, url)
             .send()
             .await
+            .map_err(map_send_error)?
+            .error_for_status()
             .map_err(PrehrajtoError::HttpError)?;
+        if is_html_content_type(response.headers()) {
+            return Err(html_instead_of_file(url));
+        }
+
+        Ok(response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok()))
+    }
+
+    /// Start a request, adding the correlation header for the site's own URLs
+    ///
+    /// A header set by [`with_correlation`] wins over
+    /// [`ClientConfig::correlation_header`].
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        if !self.is_site_url(url) {
+            return request;
+        }
+        let scoped = CORRELATION_HEADER.try_with(Clone::clone).ok();
+        match scoped.or_else(|| self.correlation_header.clone()) {
+            Some((name, value)) => request.header(name, value),
+            None => request,
+        }
+    }
+
+    /// Checks whether `url` is on the configured base URL
+    fn is_site_url(&self, url: &str) -> bool {
+        url.strip_prefix(&self.base_url)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?']))
+    }
+
+    /// Start a request whose sizes must match the actual bytes
+    ///
+    /// Like [`Self::request`], plus `Accept-Encoding: identity` when
+    /// `identity_encoding_for_downloads` is enabled.
+    fn byte_exact_request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.request(method, url);
+        if self.identity_encoding_for_downloads {
+            request.header(reqwest::header::ACCEPT_ENCODING, "identity")
+        } else {
+            request
+        }
+    }
 
-        response.text().await.map_err(PrehrajtoError::HttpError)
+    /// Read a response body and decode it to a string
+    ///
+    /// See [`decode_body`] for the charset detection rules.
+    async fn read_body(response: reqwest::Response) -> Result<String> {
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let bytes = response.bytes().await.map_err(PrehrajtoError::HttpError)?;
+        Ok(decode_body(&bytes, content_type.as_deref()))
     }
 
     /// Check if an error is retryable
     fn is_retryable(error: &PrehrajtoError) -> bool {
         match error {
-            PrehrajtoError::RateLimited => true,
+            PrehrajtoError::RateLimited | PrehrajtoError::ConnectionFailed(_) => true,
             PrehrajtoError::HttpError(e) => {
                 // Retry on timeout, connection errors, or 5xx status codes
                 e.is_timeout()
@@ -259,53 +894,1477 @@ impl PrehrajtoClient {
         }
     }
 
+    /// Get the base URL requests are sent to, without a trailing slash
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// List the cookies currently held for the base URL
+    ///
+    /// A diagnostics aid for the download flow: shows whether the video page
+    /// set the session cookies before `?do=download` is requested.
+    ///
+    /// # Returns
+    /// `(name, value)` pairs in the order the cookie store sends them
+    pub fn debug_cookies(&self) -> Vec<(String, String)> {
+        let Ok(url) = reqwest::Url::parse(&format!("{}/", self.base_url)) else {
+            return Vec::new();
+        };
+        let Some(header) = self.cookies.cookies(&url) else {
+            return Vec::new();
+        };
+        let Ok(header) = header.to_str() else {
+            return Vec::new();
+        };
+
+        header
+            .split(';')
+            .filter_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect()
+    }
+
+    /// Get the bytes downloaded by [`Self::fetch_bytes`] and
+    /// [`Self::download_to`] this session
+    pub fn session_bytes_used(&self) -> u64 {
+        self.session_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Accept CDN hosts discovered at runtime, skipping ones already known
+    pub(crate) fn add_cdn_hosts(&self, hosts: &[String]) {
+        let mut known = self.cdn_hosts.write().unwrap_or_else(|e| e.into_inner());
+        for host in hosts {
+            if !known.contains(host) {
+                known.push(host.clone());
+            }
+        }
+    }
+
+    /// Snapshot of the CDN hosts added by [`Self::add_cdn_hosts`]
+    pub(crate) fn cdn_hosts(&self) -> Vec<String> {
+        self.cdn_hosts
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Reset the session byte counter, restoring the full budget
+    pub fn reset_session_bytes(&self) {
+        self.session_bytes.store(0, Ordering::Relaxed);
+    }
+
+    /// Change the request rate at runtime
+    ///
+    /// See [`RateLimiter::set_rate`].
+    pub fn set_rate(&self, requests_per_second: f64) {
+        self.rate_limiter.set_rate(requests_per_second);
+    }
+
     /// Get a reference to the rate limiter (for testing)
     pub fn rate_limiter(&self) -> &RateLimiter {
         &self.rate_limiter
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+tokio::task_local! {
+    /// Correlation header set by [`with_correlation`] for the running operation
+    static CORRELATION_HEADER: (String, String);
+}
 
-    #[test]
-    fn test_rate_limiter_creation() {
-        let limiter = RateLimiter::new(2.0);
-        assert_eq!(limiter.min_interval(), Duration::from_millis(500));
+/// Run an operation with a correlation header on its requests to the site
+///
+/// The header is scoped to `operation`, so concurrent operations on one
+/// client can each carry their own `X-Request-Id`. Inside the scope it
+/// overrides [`ClientConfig::correlation_header`]; requests to CDN hosts
+/// never carry it. Tasks spawned by `operation` don't inherit it.
+///
+/// # Arguments
+/// * `header` - `(name, value)` to send, e.g. `("X-Request-Id", "op-1")`
+/// * `operation` - Future whose requests are tagged
+///
+/// # Returns
+/// The output of `operation`
+pub async fn with_correlation<F: std::future::Future>(
+    header: (String, String),
+    operation: F,
+) -> F::Output {
+    CORRELATION_HEADER.scope(header, operation).await
+}
+
+/// Folded phrases the site uses on its "too many requests" block page
+const SOFT_RATE_LIMIT_MARKERS: &[&str] = &[
+    "prilis mnoho pozadavku",
+    "too many requests",
+    "docasne zablokovan",
+];
+
+/// Raw-HTML stems of [`SOFT_RATE_LIMIT_MARKERS`], unaffected by diacritics or entities
+static SOFT_RATE_LIMIT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)mnoho\s+po|too\s+many\s+requests|zablokov")
+        .expect("valid soft rate limit regex")
+});
+
+/// Check whether a page is the site's soft rate limit page
+///
+/// Besides HTTP 429, prehraj.to sometimes answers 200 with an HTML page
+/// saying requests are temporarily blocked. Only the `<title>` and `<h1>`
+/// of a page without content are inspected: search results, video pages
+/// and pages echoing a search query are never the notice, so searching for
+/// "Too Many Requests" doesn't trigger it. Pages not mentioning any of the
+/// phrases are rejected before parsing.
+///
+/// # Arguments
+/// * `html` - Page HTML
+///
+/// # Returns
+/// `true` if the page is the soft rate limit notice
+pub fn is_soft_rate_limited(html: &str) -> bool {
+    if !SOFT_RATE_LIMIT_RE.is_match(html) {
+        return false;
     }
 
-    #[test]
-    fn test_rate_limiter_interval_calculation() {
-        let limiter = RateLimiter::new(4.0);
-        assert_eq!(limiter.min_interval(), Duration::from_millis(250));
+    let document = Html::parse_document(html);
+    if has_page_content(&document) {
+        return false;
     }
+    let Ok(selector) = Selector::parse("title, h1") else {
+        return false;
+    };
 
-    #[test]
-    fn test_client_config_default() {
-        let config = ClientConfig::default();
-        assert_eq!(config.requests_per_second, 2.0);
-        assert_eq!(config.timeout_secs, 30);
-        assert_eq!(config.max_retries, 3);
+    document.select(&selector).any(|element| {
+        let text = fold_text(&element.text().collect::<String>());
+        SOFT_RATE_LIMIT_MARKERS
+            .iter()
+            .any(|marker| text.contains(marker))
+    })
+}
+
+/// Checks whether a page has video links, a player or a filled-in search box
+fn has_page_content(document: &Html) -> bool {
+    let Ok(selector) = Selector::parse("a[href], video, input[value]") else {
+        return false;
+    };
+
+    document.select(&selector).any(|element| {
+        let element = element.value();
+        match element.name() {
+            "a" => element
+                .attr("href")
+                .is_some_and(|href| crate::url::parse_video_link(href).is_some()),
+            "input" => element.attr("value").is_some_and(|value| {
+                !value.trim().is_empty() && element.attr("type") != Some("hidden")
+            }),
+            _ => true,
+        }
+    })
+}
+
+/// Pick a random delay within an inclusive `(min, max)` ms range
+///
+/// A reversed range is treated as `(max, min)`. Uses the standard library's
+/// randomly seeded hasher, which is plenty for timing jitter.
+fn human_delay(range: Option<(u64, u64)>) -> Option<Duration> {
+    use std::hash::{BuildHasher, Hasher};
+
+    let (a, b) = range?;
+    let (min, max) = (a.min(b), a.max(b));
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    let span = max - min;
+    let offset = if span == u64::MAX {
+        random
+    } else {
+        random % (span + 1)
+    };
+    Some(Duration::from_millis(min + offset))
+}
+
+/// Key identifying "the same" failure for the circuit breaker
+///
+/// Server errors compare by status code so the same outage on different
+/// pages counts as one run. 404s and parse errors are about a single page,
+/// not the site, and like local errors (budget, cookies, bad input) and
+/// interrupted downloads return `None` and never trip the breaker.
+fn breaker_key(error: &PrehrajtoError) -> Option<String> {
+    match error {
+        PrehrajtoError::HttpError(e) => match e.status() {
+            Some(reqwest::StatusCode::NOT_FOUND) => None,
+            Some(status) => Some(format!("http {}", status.as_u16())),
+            None if e.is_timeout() => Some("timeout".to_string()),
+            None => Some("http".to_string()),
+        },
+        PrehrajtoError::RateLimited | PrehrajtoError::ConnectionFailed(_) => {
+            Some(error.to_string())
+        }
+        _ => None,
     }
+}
 
-    #[test]
-    fn test_client_creation() {
-        let client = PrehrajtoClient::new();
-        assert!(client.is_ok());
+/// Copy an error for the circuit breaker to return again
+///
+/// `reqwest::Error` can't be cloned, so `HttpError` comes back as
+/// `CircuitOpen` carrying the original status and message.
+fn replay_error(error: &PrehrajtoError) -> PrehrajtoError {
+    match error {
+        PrehrajtoError::HttpError(e) => PrehrajtoError::CircuitOpen {
+            status: e.status().map(|status| status.as_u16()),
+            message: e.to_string(),
+        },
+        PrehrajtoError::ParseError(msg) => PrehrajtoError::ParseError(msg.clone()),
+        PrehrajtoError::ElementNotFound(msg) => PrehrajtoError::ElementNotFound(msg.clone()),
+        PrehrajtoError::InvalidUrl(msg) => PrehrajtoError::InvalidUrl(msg.clone()),
+        PrehrajtoError::ConnectionFailed(msg) => PrehrajtoError::ConnectionFailed(msg.clone()),
+        PrehrajtoError::RateLimited => PrehrajtoError::RateLimited,
+        PrehrajtoError::NotFound(msg) => PrehrajtoError::NotFound(msg.clone()),
+        PrehrajtoError::InvalidId(msg) => PrehrajtoError::InvalidId(msg.clone()),
+        PrehrajtoError::BudgetExceeded(budget) => PrehrajtoError::BudgetExceeded(*budget),
+        PrehrajtoError::CookiesDisabled => PrehrajtoError::CookiesDisabled,
+        PrehrajtoError::Timeout(ms) => PrehrajtoError::Timeout(*ms),
+        PrehrajtoError::DownloadInterrupted { bytes_written } => {
+            PrehrajtoError::DownloadInterrupted {
+                bytes_written: *bytes_written,
+            }
+        }
+        PrehrajtoError::CircuitOpen { status, message } => PrehrajtoError::CircuitOpen {
+            status: *status,
+            message: message.clone(),
+        },
     }
+}
 
-    #[test]
-    fn test_client_with_custom_config() {
-        let config = ClientConfig {
-            requests_per_second: 1.0,
+/// Normalize a site path to have exactly one leading slash
+///
+/// Collapses repeated slashes in the path part (`//a//b` → `/a/b`);
+/// the query string is left untouched.
+fn normalize_path(path: &str) -> String {
+    let (path_part, query) = match path.split_once('?') {
+        Some((p, q)) => (p, Some(q)),
+        None => (path, None),
+    };
+
+    let segments: Vec<&str> = path_part.split('/').filter(|s| !s.is_empty()).collect();
+    let mut normalized = format!("/{}", segments.join("/"));
+    if path_part.len() > 1 && path_part.ends_with('/') {
+        normalized.push('/');
+    }
+    if let Some(query) = query {
+        normalized.push('?');
+        normalized.push_str(query);
+    }
+    normalized
+}
+
+/// Map an error from sending a request to a [`PrehrajtoError`]
+///
+/// DNS and connection failures become `ConnectionFailed` with the innermost
+/// cause as the message (reqwest's own message only names the URL).
+fn map_send_error(error: reqwest::Error) -> PrehrajtoError {
+    if !error.is_connect() {
+        return PrehrajtoError::HttpError(error);
+    }
+
+    let mut cause: &dyn std::error::Error = &error;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    PrehrajtoError::ConnectionFailed(cause.to_string())
+}
+
+/// Decode a response body using the best available charset information
+///
+/// 1. Honors the `charset` parameter of the `Content-Type` header
+///    (invalid sequences become U+FFFD rather than failing)
+/// 2. Otherwise decodes as UTF-8 if the bytes are valid UTF-8
+/// 3. Otherwise, if the body is mostly UTF-8 with a few stray invalid bytes,
+///    decodes it as lossy UTF-8 so one bad byte doesn't garble the page
+/// 4. Otherwise falls back to windows-1250 (the common legacy Czech encoding)
+///
+/// A byte order mark, if present, always takes precedence and is never
+/// part of the returned text, so parsers see the document from its first tag.
+fn decode_body(bytes: &[u8], content_type: Option<&str>) -> String {
+    let declared = content_type
+        .and_then(charset_from_content_type)
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()));
+
+    if let Some(encoding) = declared {
+        let (text, _, _) = encoding.decode(bytes);
+        return text.into_owned();
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.strip_prefix('\u{FEFF}').unwrap_or(text).to_string();
+    }
+
+    if is_mostly_utf8(bytes) {
+        let text = String::from_utf8_lossy(bytes);
+        return text.strip_prefix('\u{FEFF}').unwrap_or(&text).to_string();
+    }
+
+    let (text, _, _) = encoding_rs::WINDOWS_1250.decode(bytes);
+    text.into_owned()
+}
+
+/// Check whether invalid UTF-8 bytes look like stray corruption in UTF-8 text
+///
+/// Legacy single-byte encodings produce an invalid sequence for nearly
+/// every accented letter and almost never a valid multi-byte one, so the
+/// body counts as UTF-8 when valid multi-byte characters outnumber the
+/// invalid sequences.
+fn is_mostly_utf8(bytes: &[u8]) -> bool {
+    let mut multibyte = 0usize;
+    let mut invalid = 0usize;
+
+    for chunk in bytes.utf8_chunks() {
+        multibyte += chunk.valid().chars().filter(|c| !c.is_ascii()).count();
+        if !chunk.invalid().is_empty() {
+            invalid += 1;
+        }
+    }
+
+    multibyte > invalid
+}
+
+/// Extract the `charset` parameter from a `Content-Type` header value
+fn charset_from_content_type(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if key.trim().eq_ignore_ascii_case("charset") {
+            Some(value.trim().trim_matches('"'))
+        } else {
+            None
+        }
+    })
+}
+
+/// Check whether response headers declare an HTML body
+fn is_html_content_type(headers: &reqwest::header::HeaderMap) -> bool {
+    headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|mime| {
+            let mime = mime.trim();
+            mime.eq_ignore_ascii_case("text/html")
+                || mime.eq_ignore_ascii_case("application/xhtml+xml")
+        })
+}
+
+/// Check whether the start of a body is an HTML document
+///
+/// Catches error pages served with a generic or missing `Content-Type`.
+fn looks_like_html(bytes: &[u8]) -> bool {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let start = bytes.trim_ascii_start();
+    [b"<!doctype html".as_slice(), b"<html"].iter().any(|tag| {
+        start
+            .get(..tag.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(tag))
+    })
+}
+
+/// Error for a file URL that answered with an HTML page
+fn html_instead_of_file(url: &str) -> PrehrajtoError {
+    PrehrajtoError::NotFound(format!("{} (got an HTML page, link may have expired)", url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_creation() {
+        let limiter = RateLimiter::new(2.0);
+        assert_eq!(limiter.min_interval(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_rate_limiter_interval_calculation() {
+        let limiter = RateLimiter::new(4.0);
+        assert_eq!(limiter.min_interval(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_rate_limiter_set_rate() {
+        let limiter = RateLimiter::new(2.0);
+        limiter.set_rate(10.0);
+        assert_eq!(limiter.min_interval(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_rate_limiter_clamps_invalid_rates() {
+        for rate in [0.0, -1.0, f64::NAN, f64::NEG_INFINITY, 1e-300] {
+            assert_eq!(RateLimiter::new(rate).min_interval(), MAX_RATE_INTERVAL);
+        }
+        let unlimited = RateLimiter::new(f64::INFINITY);
+        assert_eq!(unlimited.min_interval(), Duration::ZERO);
+
+        let limiter = RateLimiter::new(2.0);
+        limiter.set_rate(0.0);
+        assert_eq!(limiter.min_interval(), MAX_RATE_INTERVAL);
+        limiter.set_rate(f64::NAN);
+        assert_eq!(limiter.min_interval(), MAX_RATE_INTERVAL);
+        limiter.set_rate(f64::INFINITY);
+        assert_eq!(limiter.min_interval(), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_zero_rate_acquires() {
+        let limiter = RateLimiter::new(0.0);
+        limiter.acquire().await;
+        assert_eq!(limiter.min_interval(), MAX_RATE_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_slower_rate_mid_run() {
+        let limiter = RateLimiter::new(100.0); // 10ms interval
+        limiter.acquire().await;
+        limiter.acquire().await;
+
+        limiter.set_rate(5.0); // 200ms interval
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[test]
+    fn test_client_config_default() {
+        let config = ClientConfig::default();
+        assert_eq!(config.requests_per_second, 2.0);
+        assert_eq!(config.timeout_secs, 30);
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.base_url, "https://prehraj.to");
+        assert!(!config.clean_cdn_urls);
+        assert!(!config.refetch_on_empty_parse);
+        assert_eq!(config.download_flow_delay_ms, 0);
+        assert_eq!(config.max_concurrent_requests, 4);
+        assert!(config.cookie_store);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast_after_identical_errors() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            circuit_breaker: Some(CircuitBreakerConfig {
+                failures: 3,
+                cooldown_ms: 60_000,
+            }),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        for page in ["/a", "/b", "/c"] {
+            let result = client.fetch(page).await;
+            assert!(matches!(result, Err(PrehrajtoError::RateLimited)));
+        }
+
+        // The breaker is open: same error, no fourth request
+        let start = Instant::now();
+        let result = client.fetch("/d").await;
+        assert!(matches!(result, Err(PrehrajtoError::RateLimited)));
+        assert!(start.elapsed() < Duration::from_millis(50));
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_resets_on_other_outcomes() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ok"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            circuit_breaker: Some(CircuitBreakerConfig {
+                failures: 2,
+                cooldown_ms: 60_000,
+            }),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        assert!(client.fetch("/a").await.is_err());
+        assert!(client.fetch("/ok").await.is_ok());
+        assert!(client.fetch("/b").await.is_err());
+        assert!(client.fetch("/ok").await.is_ok());
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_ignores_not_found() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            circuit_breaker: Some(CircuitBreakerConfig {
+                failures: 2,
+                cooldown_ms: 60_000,
+            }),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        // A run of missing pages says nothing about the site, so all are sent
+        for page in ["/a", "/b", "/c", "/d"] {
+            let result = client.fetch(page).await;
+            assert!(matches!(result, Err(PrehrajtoError::NotFound(_))));
+        }
+        let url = format!("{}/file.mp4", server.uri());
+        for _ in 0..3 {
+            assert!(client.head_content_length(&url).await.is_err());
+        }
+        assert_eq!(server.received_requests().await.unwrap().len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_replays_status() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            circuit_breaker: Some(CircuitBreakerConfig {
+                failures: 2,
+                cooldown_ms: 60_000,
+            }),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        for page in ["/a", "/b"] {
+            let result = client.fetch(page).await;
+            assert!(matches!(result, Err(PrehrajtoError::HttpError(_))));
+        }
+
+        let result = client.fetch("/c").await;
+        assert!(matches!(
+            result,
+            Err(PrehrajtoError::CircuitOpen {
+                status: Some(503),
+                ..
+            })
+        ));
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_lets_one_probe_through() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).set_delay(Duration::from_millis(200)))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            circuit_breaker: Some(CircuitBreakerConfig {
+                failures: 1,
+                cooldown_ms: 50,
+            }),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        assert!(client.fetch("/a").await.is_err());
+        sleep(Duration::from_millis(80)).await;
+
+        // Cooldown over: only one of the waiting requests reaches the server
+        let requests = ["/b", "/c", "/d", "/e"].map(|page| client.fetch(page));
+        let results = futures::future::join_all(requests).await;
+        assert!(
+            results
+                .iter()
+                .all(|r| matches!(r, Err(PrehrajtoError::RateLimited)))
+        );
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_client_config_presets() {
+        let presets = [
+            ClientConfig::polite(),
+            ClientConfig::conservative(),
+            ClientConfig::aggressive(),
+        ];
+        for (i, config) in presets.iter().enumerate() {
+            assert!(config.requests_per_second > 0.0);
+            assert!(config.timeout_secs > 0);
+            assert!(PrehrajtoClient::with_config(config.clone()).is_ok());
+            for other in &presets[i + 1..] {
+                assert_ne!(config.requests_per_second, other.requests_per_second);
+            }
+        }
+
+        let conservative = ClientConfig::conservative();
+        assert!(conservative.requests_per_second < ClientConfig::polite().requests_per_second);
+        assert!(conservative.max_retries > ClientConfig::polite().max_retries);
+        assert_eq!(ClientConfig::aggressive().max_retries, 0);
+    }
+
+    #[test]
+    fn test_human_delay_within_range() {
+        assert_eq!(human_delay(None), None);
+        assert_eq!(human_delay(Some((50, 50))), Some(Duration::from_millis(50)));
+
+        for _ in 0..200 {
+            let delay = human_delay(Some((100, 300))).unwrap();
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(300));
+
+            let reversed = human_delay(Some((300, 100))).unwrap();
+            assert!(reversed >= Duration::from_millis(100));
+            assert!(reversed <= Duration::from_millis(300));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_human_delay_applied_before_fetch() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            human_delay_ms: Some((150, 250)),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let start = Instant::now();
+        client.fetch("/video/abc123").await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_client_creation() {
+        let client = PrehrajtoClient::new();
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_client_with_custom_config() {
+        let config = ClientConfig {
+            requests_per_second: 1.0,
             timeout_secs: 60,
             max_retries: 5,
+            ..Default::default()
         };
         let client = PrehrajtoClient::with_config(config);
         assert!(client.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_connection_refused_maps_to_connection_failed() {
+        // Nothing listens on port 1 — the connection is refused immediately
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: "http://127.0.0.1:1".to_string(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let result = client.fetch("/").await;
+        assert!(matches!(result, Err(PrehrajtoError::ConnectionFailed(_))));
+    }
+
+    const SOFT_LIMIT_HTML: &str = r#"<html><head><title>Příliš mnoho požadavků</title></head>
+        <body><h1>Příliš mnoho požadavků</h1><p>Zkuste to prosím později.</p></body></html>"#;
+
+    #[test]
+    fn test_is_soft_rate_limited() {
+        assert!(is_soft_rate_limited(SOFT_LIMIT_HTML));
+        assert!(is_soft_rate_limited(
+            "<html><head><title>Too Many Requests</title></head></html>"
+        ));
+        assert!(!is_soft_rate_limited(
+            "<html><head><title>Prehraj.to</title></head><body></body></html>"
+        ));
+    }
+
+    #[test]
+    fn test_is_soft_rate_limited_ignores_result_cards() {
+        let html = r#"<html><head><title>Hledání | prehraj.to</title></head><body>
+            <a class="video--link"><h3 class="video__title">Too Many Requests</h3></a>
+            </body></html>"#;
+        assert!(!is_soft_rate_limited(html));
+    }
+
+    #[test]
+    fn test_is_soft_rate_limited_ignores_searches_for_the_phrase() {
+        let results = r#"<html><head><title>too many requests | Prehraj.to</title></head><body>
+            <h1>Výsledky hledání: too many requests</h1>
+            <main><a href="/too-many-requests/63aba7f51f6cf"><h3>Too Many Requests</h3></a></main>
+            </body></html>"#;
+        assert!(!is_soft_rate_limited(results));
+
+        let no_results = r#"<html><head><title>too many requests | Prehraj.to</title></head><body>
+            <form><input type="text" name="q" value="too many requests"></form>
+            <h1>Výsledky hledání: too many requests</h1><p>Nic nenalezeno</p>
+            </body></html>"#;
+        assert!(!is_soft_rate_limited(no_results));
+
+        let video = r#"<html><head><title>Too Many Requests | Prehraj.to</title></head><body>
+            <h1>Too Many Requests</h1><video src="https://example.com/v.mp4"></video>
+            </body></html>"#;
+        assert!(!is_soft_rate_limited(video));
+    }
+
+    #[tokio::test]
+    async fn test_soft_rate_limit_maps_to_rate_limited() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SOFT_LIMIT_HTML))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let result = client.fetch("/hledej/test").await;
+        assert!(matches!(result, Err(PrehrajtoError::RateLimited)));
+    }
+
+    #[tokio::test]
+    async fn test_soft_rate_limit_is_retried() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SOFT_LIMIT_HTML))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>ok</html>"))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 1,
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let body = client.fetch("/hledej/test").await.unwrap();
+        assert_eq!(body, "<html>ok</html>");
+    }
+
+    #[tokio::test]
+    async fn test_cookie_store_disabled_keeps_no_cookies() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .append_header("Set-Cookie", "_nss=abc; Path=/")
+                    .set_body_string("<html></html>"),
+            )
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            cookie_store: false,
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        client.fetch("/video/abc123").await.unwrap();
+        assert!(client.debug_cookies().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_debug_cookies_lists_cookies_after_fetch() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .append_header("Set-Cookie", "_nss=abc; Path=/")
+                    .append_header("Set-Cookie", "u_uid=42; Path=/")
+                    .set_body_string("<html></html>"),
+            )
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+        assert!(client.debug_cookies().is_empty());
+
+        client.fetch("/video/abc123").await.unwrap();
+        let mut cookies = client.debug_cookies();
+        cookies.sort();
+        assert_eq!(
+            cookies,
+            vec![
+                ("_nss".to_string(), "abc".to_string()),
+                ("u_uid".to_string(), "42".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_returns_forbidden_body_but_canary_fails() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/video/abc123"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("<h1>Access denied</h1>"))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        assert_eq!(
+            client.fetch("/video/abc123").await.unwrap(),
+            "<h1>Access denied</h1>"
+        );
+        let canary = client.fetch_canary("/video/abc123").await;
+        assert!(
+            matches!(canary, Err(PrehrajtoError::HttpError(e)) if e.status() == Some(reqwest::StatusCode::FORBIDDEN))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_returns_exact_bytes() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let bytes: Vec<u8> = vec![0x89, b'P', b'N', b'G', 0x00, 0xFF, 0xFE, 0x0D, 0x0A];
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/thumbs/abc123.png"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Type", "image/png")
+                    .set_body_bytes(bytes.clone()),
+            )
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let absolute = format!("{}/thumbs/abc123.png", server.uri());
+        assert_eq!(client.fetch_bytes(&absolute).await.unwrap(), bytes);
+        let relative = client.fetch_bytes("/thumbs/abc123.png").await.unwrap();
+        assert_eq!(relative, bytes);
+
+        let missing = client.fetch_bytes("/thumbs/missing.png").await;
+        assert!(matches!(missing, Err(PrehrajtoError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_correlation_header_is_sent() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("X-Request-Id", "op-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("first"))
+            .expect(3)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(header("X-Request-Id", "op-2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("second"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            correlation_header: Some(("X-Request-Id".to_string(), "op-1".to_string())),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+        assert_eq!(client.fetch("/").await.unwrap(), "first");
+
+        let header = ("X-Request-Id".to_string(), "op-2".to_string());
+        let (second, first) = tokio::join!(
+            with_correlation(header, client.fetch("/")),
+            client.fetch("/")
+        );
+        assert_eq!(second.unwrap(), "second");
+        assert_eq!(first.unwrap(), "first");
+        assert_eq!(client.fetch("/").await.unwrap(), "first");
+    }
+
+    #[tokio::test]
+    async fn test_correlation_header_not_sent_to_other_hosts() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let site = MockServer::start().await;
+        let cdn = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 16]))
+            .mount(&cdn)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: site.uri(),
+            correlation_header: Some(("X-Request-Id".to_string(), "op-1".to_string())),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+        let header = ("X-Request-Id".to_string(), "op-2".to_string());
+        let url = format!("{}/video.mp4", cdn.uri());
+        with_correlation(header, client.head_content_length(&url))
+            .await
+            .unwrap();
+
+        let requests = cdn.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let has_header = requests[0]
+            .headers
+            .keys()
+            .any(|name| name.as_str().eq_ignore_ascii_case("x-request-id"));
+        assert!(!has_header);
+    }
+
+    #[tokio::test]
+    async fn test_head_sends_identity_encoding() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(header("Accept-Encoding", "identity"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 512]))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(header("Accept-Encoding", "identity"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("download"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let url = format!("{}/cdn/video.mp4", server.uri());
+        assert_eq!(client.head_content_length(&url).await.unwrap(), Some(512));
+        let page = client.fetch_download_page("/video/abc123").await.unwrap();
+        assert_eq!(page, "download");
+    }
+
+    #[tokio::test]
+    async fn test_identity_encoding_can_be_disabled() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(header("Accept-Encoding", "identity"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            identity_encoding_for_downloads: false,
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let url = format!("{}/cdn/video.mp4", server.uri());
+        assert!(client.head_content_length(&url).await.is_ok());
+    }
+
+    /// Serve one response announcing `announced` bytes but close after `body`
+    async fn truncating_server(announced: usize, body: &'static [u8]) -> String {
+        use tokio::io::AsyncReadExt;
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                announced
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(body).await.unwrap();
+            socket.flush().await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_download_to_reports_bytes_written_on_drop() {
+        let uri = truncating_server(100, &[7u8; 40]).await;
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: uri.clone(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let mut written = Vec::new();
+        let result = client
+            .download_to(&format!("{}/file.mp4", uri), &mut written, 0)
+            .await;
+        assert!(matches!(
+            result,
+            Err(PrehrajtoError::DownloadInterrupted { bytes_written: 40 })
+        ));
+        assert_eq!(written, vec![7u8; 40]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_failed_body_is_refunded() {
+        let uri = truncating_server(100, &[7u8; 40]).await;
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: uri.clone(),
+            session_byte_budget: Some(100),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        // The 40 bytes read before the connection dropped were never delivered
+        let result = client.fetch_bytes(&format!("{}/file.bin", uri)).await;
+        assert!(matches!(result, Err(PrehrajtoError::HttpError(_))));
+        assert_eq!(client.session_bytes_used(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_download_to_caps_concurrent_streams() {
+        use std::sync::atomic::AtomicUsize;
+        use tokio::io::AsyncReadExt;
+        use tokio::net::TcpListener;
+
+        const RESPONSE: &[u8] =
+            b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\ndata";
+
+        // Count connections that are being served at the same time
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        {
+            let (active, peak) = (Arc::clone(&active), Arc::clone(&peak));
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let (active, peak) = (Arc::clone(&active), Arc::clone(&peak));
+                    tokio::spawn(async move {
+                        let mut request = [0u8; 1024];
+                        let _ = socket.read(&mut request).await;
+                        let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        sleep(Duration::from_millis(50)).await;
+                        active.fetch_sub(1, Ordering::SeqCst);
+                        let _ = socket.write_all(RESPONSE).await;
+                    });
+                }
+            });
+        }
+
+        let config = ClientConfig {
+            requests_per_second: 1000.0,
+            max_retries: 0,
+            base_url: format!("http://{}", addr),
+            max_concurrent_downloads: 2,
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let downloads = (0..6).map(|i| {
+            let client = &client;
+            async move {
+                let mut written = Vec::new();
+                client
+                    .download_to(&format!("/file{}.mp4", i), &mut written, 0)
+                    .await
+            }
+        });
+        let results = futures::future::join_all(downloads).await;
+
+        assert!(results.iter().all(|r| matches!(r, Ok(4))));
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_download_to_resumes_with_range() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/file.mp4"))
+            .and(header("Range", "bytes=4-"))
+            .respond_with(ResponseTemplate::new(206).set_body_bytes(b"efgh".to_vec()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/whole.mp4"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"abcdefgh".to_vec()))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let mut written = Vec::new();
+        let count = client
+            .download_to("/file.mp4", &mut written, 4)
+            .await
+            .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(written, b"efgh");
+
+        // A server ignoring the range sends everything; the prefix is skipped
+        let mut written = Vec::new();
+        let count = client
+            .download_to("/whole.mp4", &mut written, 4)
+            .await
+            .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(written, b"efgh");
+    }
+
+    #[tokio::test]
+    async fn test_download_to_rejects_html_error_page() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let expired = "<!DOCTYPE html><html><body>Link expired</body></html>";
+        let html = ResponseTemplate::new(200).set_body_raw(expired, "text/html; charset=utf-8");
+        let mp4 = ResponseTemplate::new(200).set_body_bytes(b"\x00\x00\x00\x18ftypmp42".to_vec());
+        let server = MockServer::start().await;
+        Mock::given(path("/typed.mp4"))
+            .respond_with(html)
+            .mount(&server)
+            .await;
+        Mock::given(path("/untyped.mp4"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(format!("\n  {}", expired)))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/video.mp4"))
+            .respond_with(mp4)
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        for file in ["/typed.mp4", "/untyped.mp4"] {
+            let mut written = Vec::new();
+            let result = client.download_to(file, &mut written, 0).await;
+            assert!(matches!(result, Err(PrehrajtoError::NotFound(_))));
+            assert!(written.is_empty());
+        }
+
+        let typed = format!("{}/typed.mp4", server.uri());
+        let result = client.head_content_length(&typed).await;
+        assert!(matches!(result, Err(PrehrajtoError::NotFound(_))));
+
+        let mut written = Vec::new();
+        let count = client
+            .download_to("/video.mp4", &mut written, 0)
+            .await
+            .unwrap();
+        assert_eq!(count, 12);
+    }
+
+    #[test]
+    fn test_looks_like_html() {
+        assert!(looks_like_html(b"<!DOCTYPE html><html></html>"));
+        assert!(looks_like_html(b"\xEF\xBB\xBF  <HTML lang=\"cs\">"));
+        assert!(!looks_like_html(b"\x00\x00\x00\x18ftypmp42"));
+        assert!(!looks_like_html(b"<htm"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_respects_session_budget() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![7u8; 400]))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            session_byte_budget: Some(1000),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        client.fetch_bytes("/a.bin").await.unwrap();
+        client.fetch_bytes("/b.bin").await.unwrap();
+        assert_eq!(client.session_bytes_used(), 800);
+
+        let result = client.fetch_bytes("/c.bin").await;
+        assert!(matches!(result, Err(PrehrajtoError::BudgetExceeded(1000))));
+        assert_eq!(client.session_bytes_used(), 800);
+
+        client.reset_session_bytes();
+        assert_eq!(client.fetch_bytes("/c.bin").await.unwrap().len(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_download_to_refused_chunk_is_not_counted() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![7u8; 400]))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            session_byte_budget: Some(1000),
+            ..Default::default()
+        };
+        let client = PrehrajtoClient::with_config(config).unwrap();
+
+        let mut first = Vec::new();
+        client.download_to("/a.bin", &mut first, 0).await.unwrap();
+        client.download_to("/b.bin", &mut first, 0).await.unwrap();
+
+        let mut refused = Vec::new();
+        let result = client.download_to("/c.bin", &mut refused, 0).await;
+        assert!(matches!(result, Err(PrehrajtoError::BudgetExceeded(1000))));
+        assert!(refused.is_empty());
+        assert_eq!(client.session_bytes_used(), 800);
+    }
+
+    #[test]
+    fn test_connection_failed_is_retryable() {
+        let error = PrehrajtoError::ConnectionFailed("refused".to_string());
+        assert!(PrehrajtoClient::is_retryable(&error));
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/hledej/test"), "/hledej/test");
+        assert_eq!(normalize_path("hledej/test"), "/hledej/test");
+        assert_eq!(normalize_path("//hledej//test"), "/hledej/test");
+        assert_eq!(normalize_path("/video/abc?do=dl"), "/video/abc?do=dl");
+        assert_eq!(normalize_path("video/abc?next=//x"), "/video/abc?next=//x");
+        assert_eq!(normalize_path("/serialy/"), "/serialy/");
+        assert_eq!(normalize_path(""), "/");
+    }
+
+    #[test]
+    fn test_decode_body_windows_1250_header() {
+        // "Andělé dobývají" encoded as windows-1250
+        let bytes = b"And\xECl\xE9 dob\xFDvaj\xED";
+        let text = decode_body(bytes, Some("text/html; charset=windows-1250"));
+        assert_eq!(text, "Andělé dobývají");
+    }
+
+    #[test]
+    fn test_decode_body_windows_1250_fallback() {
+        // No charset header and invalid UTF-8 → windows-1250
+        let bytes = b"P\xF8\xEDli\x9A \x9Elu\x9Dou\xE8k\xFD k\xF9\xF2";
+        let text = decode_body(bytes, Some("text/html"));
+        assert_eq!(text, "Příliš žluťoučký kůň");
+    }
+
+    #[test]
+    fn test_decode_body_stray_invalid_utf8_byte() {
+        // UTF-8 page with one stray 0xFF byte and no charset header
+        let mut bytes = "<html><body><main><a href=\"/andele/abc123\"><h3>Andělé dobývají"
+            .as_bytes()
+            .to_vec();
+        bytes.push(0xFF);
+        bytes.extend_from_slice(" Manhattan</h3></a></main></body></html>".as_bytes());
+
+        let text = decode_body(&bytes, None);
+        assert!(text.contains("Andělé dobývají\u{FFFD} Manhattan"));
+
+        let results = crate::parser::parse_search_results(&text).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].name.starts_with("Andělé dobývají"));
+
+        // Same with a declared UTF-8 charset
+        let text = decode_body(&bytes, Some("text/html; charset=utf-8"));
+        assert!(text.contains("Andělé dobývají\u{FFFD} Manhattan"));
+    }
+
+    #[test]
+    fn test_decode_body_utf8() {
+        let text = decode_body("Andělé dobývají".as_bytes(), None);
+        assert_eq!(text, "Andělé dobývají");
+    }
+
+    #[test]
+    fn test_decode_body_strips_bom() {
+        let html = r#"<html><body><main>
+            <a href="/video-one/abc123"><div><div>01:00:00</div></div><h3>Video One</h3></a>
+        </main></body></html>"#;
+        let mut with_bom = "\u{FEFF}".as_bytes().to_vec();
+        with_bom.extend_from_slice(html.as_bytes());
+
+        for content_type in [None, Some("text/html; charset=utf-8")] {
+            let decoded = decode_body(&with_bom, content_type);
+            assert!(!decoded.starts_with('\u{FEFF}'));
+            assert_eq!(decoded, html);
+            assert_eq!(
+                crate::parser::parse_search_results(&decoded).unwrap(),
+                crate::parser::parse_search_results(html).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_charset_from_content_type() {
+        assert_eq!(
+            charset_from_content_type("text/html; charset=UTF-8"),
+            Some("UTF-8")
+        );
+        assert_eq!(
+            charset_from_content_type("text/html;Charset=\"windows-1250\""),
+            Some("windows-1250")
+        );
+        assert_eq!(charset_from_content_type("text/html"), None);
+    }
+
     #[tokio::test]
     async fn test_rate_limiter_acquire() {
         let limiter = RateLimiter::new(10.0); // 100ms interval
@@ -28,6 +28,10 @@ pub enum PrehrajtoError {
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
 
+    /// DNS resolution or TCP connection failed
+    #[error("Connection failed: {0} (check your internet connection and the configured base URL)")]
+    ConnectionFailed(String),
+
     /// Rate limited by server (HTTP 429)
     #[error("Rate limited - too many requests")]
     RateLimited,
@@ -39,6 +43,62 @@ pub enum PrehrajtoError {
     /// Invalid video ID provided
     #[error("Invalid video ID: {0}")]
     InvalidId(String),
+
+    /// A download would exceed the session byte budget (in bytes)
+    #[error("Session byte budget of {0} bytes exceeded")]
+    BudgetExceeded(u64),
+
+    /// The operation needs cookies but the client's cookie store is disabled
+    #[error("Cookie store is disabled; the download flow needs ClientConfig::cookie_store enabled")]
+    CookiesDisabled,
+
+    /// A multi-step operation ran past its overall deadline (in milliseconds)
+    ///
+    /// See [`crate::util::with_deadline`] and
+    /// [`crate::ClientConfig::operation_timeout_ms`]; distinct from the
+    /// per-request timeout, which surfaces as `HttpError`.
+    #[error("Operation timed out after {0} ms")]
+    Timeout(u64),
+
+    /// The connection dropped while streaming a download
+    ///
+    /// `bytes_written` counts the bytes already handed to the writer, so the
+    /// download can be resumed from there.
+    #[error("Download interrupted after {bytes_written} bytes")]
+    DownloadInterrupted {
+        /// Bytes written before the connection dropped
+        bytes_written: u64,
+    },
+
+    /// The circuit breaker is open, so the request was not sent
+    ///
+    /// Stands in for a network error that repeated
+    /// [`crate::CircuitBreakerConfig::failures`] times in a row; see
+    /// [`crate::ClientConfig::circuit_breaker`].
+    #[error("Circuit breaker open: {message}")]
+    CircuitOpen {
+        /// HTTP status of the repeated failure, `None` if the server never answered
+        status: Option<u16>,
+        /// Message of the repeated failure
+        message: String,
+    },
+}
+
+impl PrehrajtoError {
+    /// Add the path or operation that failed to the error message
+    ///
+    /// Appends `" for {context}"` to parse-level errors (`ParseError`,
+    /// `ElementNotFound`, `NotFound`) so logs show which video or page they
+    /// came from, e.g. "Could not find direct CDN URL in download page for
+    /// /doctor-who/63aba7f51f6cf". Other variants are returned unchanged.
+    pub fn with_context(self, context: &str) -> Self {
+        match self {
+            Self::ParseError(msg) => Self::ParseError(format!("{} for {}", msg, context)),
+            Self::ElementNotFound(msg) => Self::ElementNotFound(format!("{} for {}", msg, context)),
+            Self::NotFound(msg) => Self::NotFound(format!("{} for {}", msg, context)),
+            other => other,
+        }
+    }
 }
 
 impl Serialize for PrehrajtoError {
@@ -82,6 +142,15 @@ mod tests {
         assert_eq!(error.to_string(), "Invalid URL: not-a-url");
     }
 
+    #[test]
+    fn test_error_display_connection_failed() {
+        let error = PrehrajtoError::ConnectionFailed("dns error".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Connection failed: dns error (check your internet connection and the configured base URL)"
+        );
+    }
+
     #[test]
     fn test_error_display_rate_limited() {
         let error = PrehrajtoError::RateLimited;
@@ -100,6 +169,51 @@ mod tests {
         assert_eq!(error.to_string(), "Invalid video ID: ");
     }
 
+    #[test]
+    fn test_error_display_download_interrupted() {
+        let error = PrehrajtoError::DownloadInterrupted {
+            bytes_written: 4096,
+        };
+        assert_eq!(error.to_string(), "Download interrupted after 4096 bytes");
+    }
+
+    #[test]
+    fn test_error_display_cookies_disabled() {
+        let error = PrehrajtoError::CookiesDisabled;
+        assert!(error.to_string().contains("cookie_store"));
+    }
+
+    #[test]
+    fn test_error_display_timeout() {
+        let error = PrehrajtoError::Timeout(1500);
+        assert_eq!(error.to_string(), "Operation timed out after 1500 ms");
+    }
+
+    #[test]
+    fn test_error_display_circuit_open() {
+        let error = PrehrajtoError::CircuitOpen {
+            status: Some(503),
+            message: "server error".to_string(),
+        };
+        assert_eq!(error.to_string(), "Circuit breaker open: server error");
+    }
+
+    #[test]
+    fn test_error_with_context() {
+        let error = PrehrajtoError::NotFound("Could not find direct CDN URL".to_string())
+            .with_context("/doctor-who/63aba7f51f6cf");
+        assert_eq!(
+            error.to_string(),
+            "Video not found: Could not find direct CDN URL for /doctor-who/63aba7f51f6cf"
+        );
+
+        let error = PrehrajtoError::ParseError("bad".to_string()).with_context("/x/y");
+        assert_eq!(error.to_string(), "Failed to parse HTML: bad for /x/y");
+
+        let error = PrehrajtoError::RateLimited.with_context("/x/y");
+        assert!(matches!(error, PrehrajtoError::RateLimited));
+    }
+
     #[test]
     fn test_error_serialize() {
         let error = PrehrajtoError::RateLimited;
@@ -45,30 +45,50 @@
 //! **Important:** CDN URLs contain `token` and `expires` parameters and will
 //! stop working after expiration (typically hours). Do not cache them long-term.
 
+/// Version of this crate (e.g., for diagnostics and issue reports)
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 mod client;
 mod error;
 pub mod parser;
 mod scraper;
+mod text;
 mod types;
 pub mod url;
+pub mod util;
 
 // Re-export client types
-pub use client::{ClientConfig, PrehrajtoClient, RateLimiter};
+pub use client::{
+    CircuitBreakerConfig, ClientConfig, PrehrajtoClient, RateLimiter, is_soft_rate_limited,
+    with_correlation,
+};
 
 // Re-export error types
 pub use error::{PrehrajtoError, Result};
 
 // Re-export parser functions
 pub use parser::{
-    parse_direct_url, parse_original_download_url, parse_search_results, parse_subtitle_tracks,
-    parse_video_sources,
+    is_prehrajto_page, parse_availability, parse_cdn_hosts, parse_direct_url,
+    parse_download_options, parse_homepage, parse_original_download_url, parse_player_fingerprint,
+    parse_reported_quality, parse_search_fragment, parse_search_results,
+    parse_search_results_callback, parse_search_results_from_reader, parse_series_page,
+    parse_subtitle_tracks, parse_video_metadata, parse_video_page_result, parse_video_sources,
 };
 
 // Re-export main scraper API
 pub use scraper::PrehrajtoScraper;
 
 // Re-export data types
-pub use types::{SubtitleTrack, VideoPageData, VideoResult, VideoSource};
+pub use types::{
+    BatchOptions, DownloadOptions, FullVideoInfo, HomepageSections, Quality, QualityTier,
+    ResolveOptions, ResolvedVideo, ScoringConfig, SearchFilter, SearchOptions, SearchResponse,
+    SearchSort, SeriesInfo, SourceKind, SubtitleTrack, VideoBookmark, VideoMetadata, VideoPageData,
+    VideoResult, VideoSource,
+};
 
 // Re-export URL helper functions for convenience
-pub use url::{build_download_url, build_search_url, build_video_url, extract_video_info};
+pub use url::{
+    build_download_url, build_search_url, build_search_url_paged, build_video_url, cdn_file_key,
+    cdn_url_expires, decode_cdn_path, extract_video_info, normalize_search_query,
+    strip_cdn_tracking,
+};
@@ -4,9 +4,110 @@
 //! Supports multiple quality variants and original file downloads.
 
 use crate::error::{PrehrajtoError, Result};
-use crate::types::{SubtitleTrack, VideoSource};
-use regex::Regex;
-use scraper::{Html, Selector};
+use crate::text::decode_html_entities;
+use crate::types::{DownloadOptions, SubtitleTrack, VideoSource};
+use crate::url::decode_cdn_path;
+use regex::{Captures, Regex};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+/// CDN hosts (domain suffixes) always recognised, regardless of discovery
+pub const STATIC_CDN_HOSTS: &[&str] = &["premiumcdn.net"];
+
+// Regexes are compiled once on first use and shared by every call, so
+// parsing pages in a batch doesn't recompile them.
+//
+// The `regex` crate matches with finite automata and never backtracks, so
+// there is no exponential blow-up to guard against with a parse timeout.
+// The brace-delimited player patterns below still have open `[^}]*` gaps
+// that could run to the end of the page from every unclosed `{`, so they are
+// only ever run through `captures_bounded`, which limits each search to a
+// window of `2 * MAX_ENTRY_LEN` bytes. That keeps parsing linear in the page
+// size; counted repetitions like `[^}]{0,512}` would bound the gaps too, but
+// blow up the automata and make every page slower.
+
+/// Longest player entry (`{ … }` object) the brace-delimited patterns find
+///
+/// Entries up to this many bytes are always matched; anything longer is not
+/// a real player entry.
+const MAX_ENTRY_LEN: usize = 4096;
+
+/// Host of a `src:` / `file:` player URL
+static PLAYER_HOST_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:src|file):\s*"https?://([^/"?:]+)[^"]*""#).expect("valid regex")
+});
+
+/// Resolution such as `1080p` in freeform text
+static RESOLUTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{3,4})p").expect("valid regex"));
+
+/// VideoJS `videos.push({ src: "URL", type: '...', res: 'NUM', label: 'LABEL' ... })`
+///
+/// `res` may be quoted (`'1080'`, `"1080"`) or a bare number (`1080`).
+static VIDEOJS_SOURCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"videos\.push\(\{[^}]*src:\s*"([^"]+)"[^}]*res:\s*["']?(\d+)["']?[^}]*label:\s*'([^']+)'([^}]*)\}"#,
+    )
+    .expect("valid regex")
+});
+
+/// JWPlayer `{ file: "URL", label: 'LABEL' }`, label optional
+static JWPLAYER_SOURCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\{\s*file:\s*"([^"]+)"(?:[^}]*label:\s*'([^']+)')?([^}]*)\}"#)
+        .expect("valid regex")
+});
+
+/// `sources: { ... }` object keyed by resolution
+static OBJECT_SOURCES_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"sources\s*[:=]\s*\{([^{}]*)\}"#).expect("valid regex"));
+
+/// A `"1080": "url"` entry of a sources object
+static OBJECT_SOURCE_ENTRY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"["']?(\d{3,4})p?["']?\s*:\s*["']([^"']+)["']"#).expect("valid regex")
+});
+
+/// HLS/DASH manifest given as `src:` / `file:`
+static ADAPTIVE_SOURCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:src|file):\s*["']([^"']+\.(m3u8|mpd)(?:\?[^"']*)?)["']"#).expect("valid regex")
+});
+
+/// VideoJS `{ src: "URL", srclang: "LANG", label: "LABEL", kind: "captions" ... }`
+static VIDEOJS_TRACK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\{\s*src:\s*"([^"]+)"[^}]*srclang:\s*"([^"]+)"[^}]*label:\s*"([^"]+)"[^}]*kind:\s*"captions"([^}]*)\}"#,
+    )
+    .expect("valid regex")
+});
+
+/// JWPlayer `{ file: "URL.vtt...", ... label: "LABEL", kind: "captions" }`
+static JWPLAYER_TRACK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\{\s*file:\s*"([^"]+\.vtt[^"]*)"[^}]*label:\s*"([^"]+)"[^}]*kind:\s*"captions"([^}]*)\}"#,
+    )
+    .expect("valid regex")
+});
+
+/// JavaScript redirects, most specific first
+static JS_REDIRECT_RES: LazyLock<[Regex; 4]> = LazyLock::new(|| {
+    [
+        Regex::new(r#"window\.location\.href\s*=\s*["']([^"']+)["']"#).expect("valid regex"),
+        Regex::new(r#"window\.location\s*=\s*["']([^"']+)["']"#).expect("valid regex"),
+        Regex::new(r#"location\.href\s*=\s*["']([^"']+)["']"#).expect("valid regex"),
+        Regex::new(r#"location\s*=\s*["']([^"']+)["']"#).expect("valid regex"),
+    ]
+});
+
+/// Absolute URL in markup or script, a candidate CDN link
+static ABSOLUTE_URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"https?://[^"'\s<>]+"#).expect("valid regex"));
+
+/// Absolute URL inside a quoted string (the volatile part of a player config)
+static QUOTED_URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"https?://[^"'\s]*"#).expect("valid regex"));
+
+/// Markers of the inline script that sets up the player
+const PLAYER_CONFIG_MARKERS: &[&str] = &["videos.push(", "var sources", "sources:", "jwplayer("];
 
 // ---------------------------------------------------------------------------
 // Public API
@@ -15,23 +116,46 @@ use scraper::{Html, Selector};
 /// Parses video page HTML and extracts all quality variants
 ///
 /// Tries VideoJS `videos.push(...)` blocks first (best structured data),
-/// then falls back to JWPlayer `var sources = [...]` blocks.
+/// then falls back to JWPlayer `var sources = [...]` blocks, then to a
+/// `sources: { "1080": "url", ... }` object keyed by resolution. Adaptive
+/// HLS/DASH manifests are appended with `is_adaptive` set.
 ///
 /// # Arguments
 /// * `html` - Raw HTML string from the video page (NOT the download page)
 ///
 /// # Returns
-/// Vector of [`VideoSource`] sorted by resolution ascending.
+/// Vector of [`VideoSource`] in page order. Sources sharing a resolution
+/// (mirrors on different storage servers) are all kept.
 /// Empty vec if no player blocks found.
 pub fn parse_video_sources(html: &str) -> Vec<VideoSource> {
+    parse_video_sources_with_hosts(html, &[])
+}
+
+/// [`parse_video_sources`], also accepting JWPlayer files on discovered CDN hosts
+///
+/// See [`parse_download_options_with_hosts`] for `hosts`.
+pub(crate) fn parse_video_sources_with_hosts(html: &str, hosts: &[String]) -> Vec<VideoSource> {
     // Primary: VideoJS videos.push({...}) blocks
-    let sources = extract_videojs_sources(html);
-    if !sources.is_empty() {
-        return sources;
-    }
+    let mut sources = extract_videojs_sources(html);
 
     // Fallback: JWPlayer var sources = [...] block
-    extract_jwplayer_sources(html)
+    if sources.is_empty() {
+        sources = extract_jwplayer_sources(html, hosts);
+    }
+
+    // Fallback: sources: { "1080": "url", ... } object
+    if sources.is_empty() {
+        sources = extract_object_sources(html);
+    }
+
+    // Adaptive manifests (HLS/DASH) not already captured above
+    for adaptive in extract_adaptive_sources(html) {
+        if !sources.iter().any(|s| s.url == adaptive.url) {
+            sources.push(adaptive);
+        }
+    }
+
+    sources
 }
 
 /// Parses video page HTML and extracts all subtitle tracks
@@ -56,7 +180,9 @@ pub fn parse_subtitle_tracks(html: &str) -> Vec<SubtitleTrack> {
 /// Parses download redirect page and extracts the original file URL
 ///
 /// The download page (with cookies) contains an `<a>` tag pointing to the
-/// original uploaded file on premiumcdn.net.
+/// original uploaded file on premiumcdn.net. Packaged downloads (video with
+/// subtitles) are skipped in favour of the plain original file. A JSON
+/// response is tried first (see [`parse_download_options`]).
 ///
 /// # Arguments
 /// * `html` - Raw HTML string from the `?do=download` page (fetched with cookies)
@@ -67,40 +193,106 @@ pub fn parse_subtitle_tracks(html: &str) -> Vec<SubtitleTrack> {
 /// # Errors
 /// Returns `NotFound` if no CDN link found in the redirect page
 pub fn parse_original_download_url(html: &str) -> Result<VideoSource> {
+    let options = parse_download_options(html)?;
+    Ok(options.original)
+}
+
+/// Parses download redirect page and extracts all download options
+///
+/// Besides the original file, the page may offer a packaged download that
+/// bundles subtitles with the video. Such a link is recognised by its text
+/// or `class` mentioning subtitles ("titulky"/"subtitles"); the `href` is not
+/// consulted since original filenames often contain "titulky" too. If the page
+/// has no packaged link, only the original is returned.
+///
+/// A JSON response (`{"url": "..."}`) is accepted too, in case the endpoint
+/// switches from the HTML redirect page; it only yields the original file.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the `?do=download` page (fetched with cookies)
+///
+/// # Returns
+/// [`DownloadOptions`] with the original file and optional packaged download
+///
+/// # Errors
+/// Returns `NotFound` if no CDN link found in the redirect page
+pub fn parse_download_options(html: &str) -> Result<DownloadOptions> {
+    parse_download_options_with_hosts(html, &[])
+}
+
+/// [`parse_download_options`], also accepting links on discovered CDN hosts
+///
+/// `hosts` are domain suffixes accepted on top of [`STATIC_CDN_HOSTS`], as
+/// collected by [`crate::PrehrajtoScraper::refresh_cdn_hosts`].
+pub(crate) fn parse_download_options_with_hosts(
+    html: &str,
+    hosts: &[String],
+) -> Result<DownloadOptions> {
+    if let Some(original) = parse_download_json(html, hosts) {
+        return Ok(DownloadOptions {
+            original,
+            packaged: None,
+        });
+    }
+
     let document = Html::parse_document(html);
     let selector = Selector::parse("a[href]")
         .map_err(|_| PrehrajtoError::ParseError("Invalid selector".to_string()))?;
 
+    let mut original: Option<VideoSource> = None;
+    let mut packaged: Option<VideoSource> = None;
+
     for element in document.select(&selector) {
-        if let Some(href) = element.value().attr("href")
-            && is_cdn_url(href)
-        {
-            let url = decode_html_entities(href);
-            let filename = extract_filename_from_url(&url);
-            let resolution = filename
-                .as_deref()
-                .map(parse_resolution_from_text)
-                .unwrap_or(0);
-            let label = if resolution > 0 {
-                format!("{}p", resolution)
-            } else {
-                "original".to_string()
-            };
-            let format = extract_format_from_url(&url);
+        let Some(href) = element.value().attr("href") else {
+            continue;
+        };
+        if !is_cdn_url(href, hosts) {
+            continue;
+        }
 
-            return Ok(VideoSource {
-                url,
-                label,
-                resolution,
-                is_default: false,
-                format,
-            });
+        if is_packaged_anchor(&element) {
+            if packaged.is_none() {
+                let mut source = source_from_download_href(href);
+                source.includes_subtitles = true;
+                packaged = Some(source);
+            }
+        } else if original.is_none() {
+            original = Some(source_from_download_href(href));
         }
     }
 
-    Err(PrehrajtoError::NotFound(
-        "Could not find original download URL in redirect page".to_string(),
-    ))
+    // A page offering only the packaged download still has a usable file
+    let original = match (original, &packaged) {
+        (Some(original), _) => original,
+        (None, Some(packaged)) => packaged.clone(),
+        (None, None) => {
+            return Err(PrehrajtoError::NotFound(
+                "Could not find original download URL in redirect page".to_string(),
+            ));
+        }
+    };
+
+    Ok(DownloadOptions { original, packaged })
+}
+
+/// JSON body the download endpoint may return instead of a redirect page
+#[derive(Debug, Deserialize)]
+struct DownloadResponse {
+    #[serde(alias = "link", alias = "href", alias = "downloadUrl")]
+    url: String,
+}
+
+/// Extracts the original file from a JSON download response
+///
+/// Returns `None` if the body isn't JSON in the expected shape or its URL
+/// isn't a CDN link.
+fn parse_download_json(body: &str, hosts: &[String]) -> Option<VideoSource> {
+    let trimmed = body.trim();
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+    let response: DownloadResponse = serde_json::from_str(trimmed).ok()?;
+    is_cdn_url(&response.url, hosts).then(|| source_from_download_href(&response.url))
 }
 
 /// Parses download page HTML and extracts the direct CDN URL
@@ -117,26 +309,33 @@ pub fn parse_original_download_url(html: &str) -> Result<VideoSource> {
 /// # Errors
 /// Returns `NotFound` if no CDN URL could be extracted
 pub fn parse_direct_url(html: &str) -> Result<String> {
+    parse_direct_url_with_hosts(html, &[])
+}
+
+/// [`parse_direct_url`], also accepting links on discovered CDN hosts
+///
+/// See [`parse_download_options_with_hosts`] for `hosts`.
+pub(crate) fn parse_direct_url_with_hosts(html: &str, hosts: &[String]) -> Result<String> {
     // Try structured source parsing first — pick highest resolution
-    let sources = parse_video_sources(html);
-    if let Some(best) = sources.iter().max_by_key(|s| s.resolution) {
+    let sources = parse_video_sources_with_hosts(html, hosts);
+    if let Some(best) = best_source(&sources) {
         return Ok(best.url.clone());
     }
 
     // Fall back to generic extraction chain
-    if let Some(url) = extract_from_anchor(html) {
+    if let Some(url) = extract_from_anchor(html, hosts) {
         return Ok(url);
     }
-    if let Some(url) = extract_from_video_element(html) {
+    if let Some(url) = extract_from_video_element(html, hosts) {
         return Ok(url);
     }
-    if let Some(url) = extract_from_javascript(html) {
+    if let Some(url) = extract_from_javascript(html, hosts) {
         return Ok(url);
     }
-    if let Some(url) = extract_from_meta_refresh(html) {
+    if let Some(url) = extract_from_meta_refresh(html, hosts) {
         return Ok(url);
     }
-    if let Some(url) = extract_cdn_url_generic(html) {
+    if let Some(url) = extract_cdn_url_generic(html, hosts) {
         return Ok(url);
     }
 
@@ -145,10 +344,123 @@ pub fn parse_direct_url(html: &str) -> Result<String> {
     ))
 }
 
+/// Picks the best source: highest resolution progressive file
+///
+/// Sources with a known (positive) resolution always win. Adaptive
+/// manifests and label-less files get resolution 0, so they are only
+/// returned when nothing else exists, progressive files first. Ties keep
+/// page order.
+pub(crate) fn best_source(sources: &[VideoSource]) -> Option<&VideoSource> {
+    best_source_with(sources, true)
+}
+
+/// Like [`best_source`], optionally never picking a zero-resolution source
+///
+/// With `allow_zero` off, a list holding only adaptive or label-less
+/// sources yields `None`.
+pub(crate) fn best_source_with(sources: &[VideoSource], allow_zero: bool) -> Option<&VideoSource> {
+    sources
+        .iter()
+        .rev()
+        .filter(|s| allow_zero || s.resolution > 0)
+        .max_by_key(|s| (s.resolution > 0, !s.is_adaptive, s.resolution))
+}
+
+/// Parses video page HTML and extracts the CDN hosts used by the player
+///
+/// Looks at every `src: "..."` / `file: "..."` URL in the player blocks
+/// (video sources and subtitle tracks) and collects their hosts. Only hosts
+/// that look like the site's CDN are kept: subdomains of
+/// [`STATIC_CDN_HOSTS`] and storage nodes (`pf-storage4.…`, `storage12.…`),
+/// so ad and other third-party hosts in the player config are ignored.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the video page
+///
+/// # Returns
+/// Unique host names in page order (e.g., "pf-storage4.premiumcdn.net")
+pub fn parse_cdn_hosts(html: &str) -> Vec<String> {
+    let mut hosts: Vec<String> = Vec::new();
+
+    for caps in PLAYER_HOST_RE.captures_iter(html) {
+        if let Some(host) = caps.get(1) {
+            let host = host.as_str().to_lowercase();
+            if looks_like_cdn_host(&host) && !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+    }
+
+    hosts
+}
+
+/// Computes a fingerprint of the video page's player config
+///
+/// Hashes the player script (see `extract_player_config`) with every
+/// URL replaced by a placeholder and whitespace collapsed, so the per-visit
+/// CDN tokens and expiry times don't change it but any change to the
+/// markup around them does. Useful for noticing site changes that are
+/// likely to break the source parsers.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the video page
+///
+/// # Returns
+/// A 16-digit hex hash, stable across runs and builds, or `None` if the
+/// page has no player script
+pub fn parse_player_fingerprint(html: &str) -> Option<String> {
+    let config = extract_player_config(html)?;
+    let normalized = QUOTED_URL_RE.replace_all(&config, "url");
+    let normalized = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some(format!("{:016x}", fnv1a(normalized.as_bytes())))
+}
+
+/// Checks whether a host looks like one of the site's CDN nodes
+///
+/// Accepts subdomains of [`STATIC_CDN_HOSTS`] and hosts whose first label
+/// names a storage node, such as `pf-storage4` or `storage12`.
+fn looks_like_cdn_host(host: &str) -> bool {
+    if STATIC_CDN_HOSTS
+        .iter()
+        .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+    {
+        return true;
+    }
+    let Some((node, domain)) = host.split_once('.') else {
+        return false;
+    };
+    let name = node.strip_prefix("pf-").unwrap_or(node);
+    let name = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    name == "storage" && domain.contains('.')
+}
+
 // ---------------------------------------------------------------------------
 // Helpers — resolution & format parsing
 // ---------------------------------------------------------------------------
 
+/// Returns the text of the first inline script that sets up the player
+///
+/// The script is recognised by [`PLAYER_CONFIG_MARKERS`]; scripts loaded
+/// with `src` have no inline text and are skipped.
+pub(crate) fn extract_player_config(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("script:not([src])").ok()?;
+    document
+        .select(&selector)
+        .map(|script| script.text().collect::<String>())
+        .find(|text| PLAYER_CONFIG_MARKERS.iter().any(|m| text.contains(m)))
+}
+
+/// 64-bit FNV-1a hash
+///
+/// Used instead of `DefaultHasher`, whose output may change between Rust
+/// releases, so fingerprints stored by callers stay comparable.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 /// Parses numeric resolution from a quality label like "1080p"
 fn parse_resolution_from_label(label: &str) -> u32 {
     let trimmed = label.trim().to_lowercase();
@@ -159,8 +471,7 @@ fn parse_resolution_from_label(label: &str) -> u32 {
 /// Tries to find a resolution pattern in freeform text (e.g. filenames)
 fn parse_resolution_from_text(text: &str) -> u32 {
     // Match patterns like "2160p", "1080p", "4K"
-    if let Ok(re) = Regex::new(r"(\d{3,4})p")
-        && let Some(caps) = re.captures(text)
+    if let Some(caps) = RESOLUTION_RE.captures(text)
         && let Some(m) = caps.get(1)
         && let Ok(res) = m.as_str().parse::<u32>()
     {
@@ -203,6 +514,49 @@ fn extract_format_from_url(url: &str) -> Option<String> {
     None
 }
 
+/// Builds a [`VideoSource`] from a download page CDN link
+fn source_from_download_href(href: &str) -> VideoSource {
+    let url = decode_cdn_url(href);
+    let filename = extract_filename_from_url(&url);
+    let resolution = filename
+        .as_deref()
+        .map(parse_resolution_from_text)
+        .unwrap_or(0);
+    let label = if resolution > 0 {
+        format!("{}p", resolution)
+    } else {
+        "original".to_string()
+    };
+    let format = extract_format_from_url(&url);
+
+    VideoSource {
+        url,
+        label,
+        resolution,
+        is_default: false,
+        is_adaptive: false,
+        format,
+        includes_subtitles: false,
+        size_bytes: None,
+    }
+}
+
+/// Checks if a download anchor is a packaged (video + subtitles) download
+fn is_packaged_anchor(element: &ElementRef) -> bool {
+    let text = element.text().collect::<String>().to_lowercase();
+    let class = element.value().attr("class").unwrap_or("").to_lowercase();
+
+    ["titulk", "subtitle"]
+        .iter()
+        .any(|k| text.contains(k) || class.contains(k))
+}
+
+/// Checks if URL points to an adaptive streaming manifest (HLS/DASH)
+fn is_adaptive_url(url: &str) -> bool {
+    let path = url.split('?').next().unwrap_or(url).to_lowercase();
+    path.ends_with(".m3u8") || path.ends_with(".mpd")
+}
+
 /// Extracts filename from `filename=` query parameter
 fn extract_filename_from_url(url: &str) -> Option<String> {
     let query = url.split('?').nth(1)?;
@@ -219,19 +573,46 @@ fn extract_filename_from_url(url: &str) -> Option<String> {
 // VideoJS & JWPlayer extraction
 // ---------------------------------------------------------------------------
 
+/// Finds all matches of a brace-delimited player pattern in bounded windows
+///
+/// Like `re.captures_iter(html)`, but each search only sees the next
+/// `2 * MAX_ENTRY_LEN` bytes. When a window holds no match the next one
+/// starts `MAX_ENTRY_LEN` further on, so every match up to `MAX_ENTRY_LEN`
+/// bytes long is still found, and the work stays linear in `html.len()`
+/// however many braces are left unclosed.
+fn captures_bounded<'h>(re: &Regex, html: &'h str) -> Vec<Captures<'h>> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while pos < html.len() {
+        let end = floor_char_boundary(html, pos + 2 * MAX_ENTRY_LEN);
+        match re.captures_at(&html[..end], pos) {
+            Some(caps) => {
+                // Every pattern starts with a literal, so matches are never empty
+                pos = caps.get(0).map_or(end, |m| m.end());
+                found.push(caps);
+            }
+            None if end == html.len() => break,
+            None => pos = floor_char_boundary(html, pos + MAX_ENTRY_LEN),
+        }
+    }
+    found
+}
+
+/// Largest char boundary in `text` not after `index` (clamped to its length)
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
 /// Extracts sources from VideoJS `videos.push({...})` blocks
 fn extract_videojs_sources(html: &str) -> Vec<VideoSource> {
     let mut sources = Vec::new();
 
-    // Match: videos.push({ src: "URL", type: '...', res: 'NUM', label: 'LABEL' ... })
     // The `default: true` may or may not be present
-    let Ok(re) = Regex::new(
-        r#"videos\.push\(\{[^}]*src:\s*"([^"]+)"[^}]*res:\s*'(\d+)'[^}]*label:\s*'([^']+)'([^}]*)\}"#,
-    ) else {
-        return sources;
-    };
-
-    for caps in re.captures_iter(html) {
+    for caps in captures_bounded(&VIDEOJS_SOURCE_RE, html) {
         let url = caps.get(1).map(|m| m.as_str().to_string()).unwrap_or_default();
         let res_str = caps.get(2).map(|m| m.as_str()).unwrap_or("0");
         let label = caps.get(3).map(|m| m.as_str().to_string()).unwrap_or_default();
@@ -239,13 +620,17 @@ fn extract_videojs_sources(html: &str) -> Vec<VideoSource> {
         let is_default = rest.contains("default: true") || rest.contains("default:true");
         let resolution = res_str.parse::<u32>().unwrap_or(0);
         let format = extract_format_from_url(&url);
+        let is_adaptive = is_adaptive_url(&url);
 
         sources.push(VideoSource {
             url,
             label,
             resolution,
             is_default,
+            is_adaptive,
             format,
+            includes_subtitles: false,
+            size_bytes: None,
         });
     }
 
@@ -253,28 +638,116 @@ fn extract_videojs_sources(html: &str) -> Vec<VideoSource> {
 }
 
 /// Extracts sources from JWPlayer `var sources = [{ file: "...", label: '...' }]` block
-fn extract_jwplayer_sources(html: &str) -> Vec<VideoSource> {
+///
+/// Only files on the CDN (see [`is_cdn_url`]) are kept.
+fn extract_jwplayer_sources(html: &str, hosts: &[String]) -> Vec<VideoSource> {
     let mut sources = Vec::new();
 
-    // Match: { file: "URL...premiumcdn...", label: 'LABEL' }
-    let Ok(re) = Regex::new(
-        r#"\{\s*file:\s*"([^"]*premiumcdn[^"]*)"[^}]*label:\s*'([^']+)'"#,
-    ) else {
-        return sources;
-    };
-
-    for caps in re.captures_iter(html) {
+    for caps in captures_bounded(&JWPLAYER_SOURCE_RE, html) {
         let url = caps.get(1).map(|m| m.as_str().to_string()).unwrap_or_default();
-        let label = caps.get(2).map(|m| m.as_str().to_string()).unwrap_or_default();
-        let resolution = parse_resolution_from_label(&label);
+        if !is_cdn_url(&url, hosts) {
+            continue;
+        }
+        let rest = caps.get(3).map_or("", |m| m.as_str());
         let format = extract_format_from_url(&url);
+        let is_adaptive = is_adaptive_url(&url);
+
+        let (label, resolution) = match caps.get(2) {
+            Some(m) => (m.as_str().to_string(), parse_resolution_from_label(m.as_str())),
+            None => {
+                // Subtitle tracks share the syntax; manifests are picked up
+                // by the adaptive extractor with a better label
+                if rest.contains("kind:") || is_adaptive {
+                    continue;
+                }
+                let resolution = parse_resolution_from_text(&url);
+                let label = if resolution > 0 {
+                    format!("{}p", resolution)
+                } else {
+                    "Unknown".to_string()
+                };
+                (label, resolution)
+            }
+        };
 
         sources.push(VideoSource {
             url,
             label,
             resolution,
             is_default: false,
+            is_adaptive,
             format,
+            includes_subtitles: false,
+            size_bytes: None,
+        });
+    }
+
+    sources
+}
+
+/// Extracts sources from a `sources: { "1080": "URL", "720": "URL" }` object
+///
+/// Keys are resolutions, optionally quoted and with a `p` suffix.
+fn extract_object_sources(html: &str) -> Vec<VideoSource> {
+    let mut sources = Vec::new();
+
+    for block in captures_bounded(&OBJECT_SOURCES_RE, html) {
+        let Some(body) = block.get(1) else {
+            continue;
+        };
+
+        for caps in OBJECT_SOURCE_ENTRY_RE.captures_iter(body.as_str()) {
+            let resolution = caps
+                .get(1)
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+                .unwrap_or(0);
+            let url = caps
+                .get(2)
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default();
+            let format = extract_format_from_url(&url);
+            let is_adaptive = is_adaptive_url(&url);
+
+            sources.push(VideoSource {
+                url,
+                label: format!("{}p", resolution),
+                resolution,
+                is_default: false,
+                is_adaptive,
+                format,
+                includes_subtitles: false,
+                size_bytes: None,
+            });
+        }
+    }
+
+    sources
+}
+
+/// Extracts adaptive HLS (`.m3u8`) and DASH (`.mpd`) manifests
+///
+/// Matches `src: "..."` / `file: "..."` entries in any player block.
+/// Manifests carry no resolution, so it is left as 0.
+fn extract_adaptive_sources(html: &str) -> Vec<VideoSource> {
+    let mut sources: Vec<VideoSource> = Vec::new();
+
+    for caps in ADAPTIVE_SOURCE_RE.captures_iter(html) {
+        let url = caps.get(1).map(|m| m.as_str().to_string()).unwrap_or_default();
+        let ext = caps.get(2).map(|m| m.as_str()).unwrap_or("m3u8");
+        if sources.iter().any(|s| s.url == url) {
+            continue;
+        }
+        let label = if ext == "mpd" { "DASH" } else { "HLS" };
+
+        sources.push(VideoSource {
+            url,
+            label: label.to_string(),
+            resolution: 0,
+            is_default: false,
+            is_adaptive: true,
+            format: Some(ext.to_string()),
+            includes_subtitles: false,
+            size_bytes: None,
         });
     }
 
@@ -291,15 +764,8 @@ fn extract_jwplayer_sources(html: &str) -> Vec<VideoSource> {
 fn extract_videojs_tracks(html: &str) -> Vec<SubtitleTrack> {
     let mut tracks = Vec::new();
 
-    // Match: { src: "URL", srclang: "LANG", label: "LABEL", kind: "captions" ... }
     // `default: true` may or may not be present
-    let Ok(re) = Regex::new(
-        r#"\{\s*src:\s*"([^"]+)"[^}]*srclang:\s*"([^"]+)"[^}]*label:\s*"([^"]+)"[^}]*kind:\s*"captions"([^}]*)\}"#,
-    ) else {
-        return tracks;
-    };
-
-    for caps in re.captures_iter(html) {
+    for caps in captures_bounded(&VIDEOJS_TRACK_RE, html) {
         let url = caps.get(1).map(|m| m.as_str().to_string()).unwrap_or_default();
         let language = caps.get(2).map(|m| m.as_str().to_string()).unwrap_or_default();
         let raw_label = caps.get(3).map(|m| m.as_str()).unwrap_or("");
@@ -324,15 +790,8 @@ fn extract_videojs_tracks(html: &str) -> Vec<SubtitleTrack> {
 fn extract_jwplayer_tracks(html: &str) -> Vec<SubtitleTrack> {
     let mut tracks = Vec::new();
 
-    // Match: { file: "URL.vtt...", ... label: "LABEL", kind: "captions" }
     // "default": true may appear with quoted key
-    let Ok(re) = Regex::new(
-        r#"\{\s*file:\s*"([^"]+\.vtt[^"]*)"[^}]*label:\s*"([^"]+)"[^}]*kind:\s*"captions"([^}]*)\}"#,
-    ) else {
-        return tracks;
-    };
-
-    for caps in re.captures_iter(html) {
+    for caps in captures_bounded(&JWPLAYER_TRACK_RE, html) {
         let url = caps.get(1).map(|m| m.as_str().to_string()).unwrap_or_default();
         let raw_label = caps.get(2).map(|m| m.as_str()).unwrap_or("");
         let rest = caps.get(3).map(|m| m.as_str()).unwrap_or("");
@@ -390,30 +849,30 @@ fn extract_language_from_label(raw: &str) -> String {
 // ---------------------------------------------------------------------------
 
 /// Extracts CDN URL from anchor tags
-fn extract_from_anchor(html: &str) -> Option<String> {
+fn extract_from_anchor(html: &str, hosts: &[String]) -> Option<String> {
     let document = Html::parse_document(html);
     let selector = Selector::parse("a[href]").ok()?;
 
     for element in document.select(&selector) {
         if let Some(href) = element.value().attr("href")
-            && is_cdn_url(href)
+            && is_cdn_url(href, hosts)
         {
-            return Some(decode_html_entities(href));
+            return Some(decode_cdn_url(href));
         }
     }
     None
 }
 
 /// Extracts CDN URL from video/source elements
-fn extract_from_video_element(html: &str) -> Option<String> {
+fn extract_from_video_element(html: &str, hosts: &[String]) -> Option<String> {
     let document = Html::parse_document(html);
 
     if let Ok(selector) = Selector::parse("video[src]") {
         for element in document.select(&selector) {
             if let Some(src) = element.value().attr("src")
-                && is_cdn_url(src)
+                && is_cdn_url(src, hosts)
             {
-                return Some(decode_html_entities(src));
+                return Some(decode_cdn_url(src));
             }
         }
     }
@@ -421,9 +880,9 @@ fn extract_from_video_element(html: &str) -> Option<String> {
     if let Ok(selector) = Selector::parse("source[src]") {
         for element in document.select(&selector) {
             if let Some(src) = element.value().attr("src")
-                && is_cdn_url(src)
+                && is_cdn_url(src, hosts)
             {
-                return Some(decode_html_entities(src));
+                return Some(decode_cdn_url(src));
             }
         }
     }
@@ -432,28 +891,17 @@ fn extract_from_video_element(html: &str) -> Option<String> {
 }
 
 /// Extracts CDN URL from JavaScript redirects
-fn extract_from_javascript(html: &str) -> Option<String> {
-    let patterns = [
-        r#"window\.location\.href\s*=\s*["']([^"']+premiumcdn[^"']+)["']"#,
-        r#"window\.location\s*=\s*["']([^"']+premiumcdn[^"']+)["']"#,
-        r#"location\.href\s*=\s*["']([^"']+premiumcdn[^"']+)["']"#,
-        r#"location\s*=\s*["']([^"']+premiumcdn[^"']+)["']"#,
-    ];
-
-    for pattern in patterns {
-        if let Ok(re) = Regex::new(pattern)
-            && let Some(caps) = re.captures(html)
-            && let Some(url) = caps.get(1)
-        {
-            return Some(url.as_str().to_string());
-        }
-    }
-
-    None
+fn extract_from_javascript(html: &str, hosts: &[String]) -> Option<String> {
+    JS_REDIRECT_RES.iter().find_map(|re| {
+        re.captures_iter(html)
+            .filter_map(|caps| caps.get(1))
+            .find(|url| is_cdn_url(url.as_str(), hosts))
+            .map(|url| decode_cdn_path(url.as_str()))
+    })
 }
 
 /// Extracts CDN URL from meta refresh tag
-fn extract_from_meta_refresh(html: &str) -> Option<String> {
+fn extract_from_meta_refresh(html: &str, hosts: &[String]) -> Option<String> {
     let document = Html::parse_document(html);
     let selector = Selector::parse(r#"meta[http-equiv="refresh"]"#).ok()?;
 
@@ -462,8 +910,8 @@ fn extract_from_meta_refresh(html: &str) -> Option<String> {
             && let Some(url_part) = content.split("url=").nth(1)
         {
             let url = url_part.trim();
-            if is_cdn_url(url) {
-                return Some(decode_html_entities(url));
+            if is_cdn_url(url, hosts) {
+                return Some(decode_cdn_url(url));
             }
         }
     }
@@ -472,42 +920,117 @@ fn extract_from_meta_refresh(html: &str) -> Option<String> {
 }
 
 /// Generic regex search for CDN URLs in HTML
-fn extract_cdn_url_generic(html: &str) -> Option<String> {
-    let re = Regex::new(
-        r#"https?://[^"'\s<>]+premiumcdn\.net[^"'\s<>]*(?:token|expires)[^"'\s<>]*"#,
-    )
-    .ok()?;
-
-    if let Some(m) = re.find(html) {
-        return Some(decode_html_entities(m.as_str()));
-    }
-
-    let re_fallback =
-        Regex::new(r#"https?://[^"'\s<>]+premiumcdn\.net[^"'\s<>]+"#).ok()?;
+///
+/// Prefers a URL carrying a `token` or `expires` parameter.
+fn extract_cdn_url_generic(html: &str, hosts: &[String]) -> Option<String> {
+    let urls: Vec<&str> = ABSOLUTE_URL_RE
+        .find_iter(html)
+        .map(|m| m.as_str())
+        .filter(|url| is_cdn_url(url, hosts))
+        .collect();
+
+    urls.iter()
+        .find(|url| url.contains("token") || url.contains("expires"))
+        .or(urls.first())
+        .map(|url| decode_cdn_url(url))
+}
 
-    re_fallback
-        .find(html)
-        .map(|m| decode_html_entities(m.as_str()))
+/// Decodes HTML entities, then the percent-encoded path, of a CDN URL
+fn decode_cdn_url(raw: &str) -> String {
+    decode_cdn_path(&decode_html_entities(raw))
 }
 
-/// Decodes common HTML entities in URLs
-fn decode_html_entities(url: &str) -> String {
-    url.replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'")
+/// Checks if URL is a CDN URL (premiumcdn.net or one of `hosts`)
+pub(crate) fn is_cdn_url(url: &str, hosts: &[String]) -> bool {
+    if url.contains("premiumcdn.net") || url.contains("cdn.") && url.contains("premium") {
+        return true;
+    }
+    is_on_hosts(url, hosts)
 }
 
-/// Checks if URL is a CDN URL (premiumcdn.net)
-fn is_cdn_url(url: &str) -> bool {
-    url.contains("premiumcdn.net") || url.contains("cdn.") && url.contains("premium")
+/// Checks if URL is on one of `hosts` or a subdomain of one
+fn is_on_hosts(url: &str, hosts: &[String]) -> bool {
+    let Some(rest) = url.split("://").nth(1) else {
+        return false;
+    };
+    let host = rest
+        .split(['/', '?', ':'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    if host.is_empty() {
+        return false;
+    }
+
+    hosts
+        .iter()
+        .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_adversarial_page_yields_no_sources() {
+        // Many openings that almost match but never close their braces
+        let unit = concat!(
+            r#"videos.push({ src: "https://x.premiumcdn.net/a.mp4", res: '720', label: '720p' "#,
+            r#"{ file: "https://x.premiumcdn.net/b.vtt", label: 'a' "#,
+            r#"{ src: "s", srclang: "cs", label: "CZE" sources: { "720": "u" "#,
+        );
+        let html = unit.repeat(5_000);
+
+        assert!(parse_video_sources(&html).is_empty());
+        assert!(parse_subtitle_tracks(&html).is_empty());
+    }
+
+    #[test]
+    fn test_captures_bounded_limits_entry_span() {
+        let entry = |gap: usize| {
+            format!(
+                r#"videos.push({{ src: "https://x.premiumcdn.net/a.mp4",{} res: '720', label: '720p' }});"#,
+                " ".repeat(gap)
+            )
+        };
+
+        let short = entry(100);
+        assert_eq!(captures_bounded(&VIDEOJS_SOURCE_RE, &short).len(), 1);
+
+        // An entry no window can hold is never matched, so no search ever
+        // scans more than `2 * MAX_ENTRY_LEN` bytes
+        let long = entry(2 * MAX_ENTRY_LEN);
+        assert_eq!(VIDEOJS_SOURCE_RE.captures_iter(&long).count(), 1);
+        assert!(captures_bounded(&VIDEOJS_SOURCE_RE, &long).is_empty());
+    }
+
+    #[test]
+    fn test_captures_bounded_finds_entries_across_windows() {
+        let entry = r#"{ file: "https://x.premiumcdn.net/720p.mp4", label: '720p' }"#;
+        // Shift the entry across window boundaries, with unclosed junk before it
+        for offset in (0..3 * MAX_ENTRY_LEN).step_by(509) {
+            let html = format!("{}{}{}é", "{".repeat(offset), entry, entry);
+            let found = captures_bounded(&JWPLAYER_SOURCE_RE, &html);
+            assert_eq!(found.len(), 2, "offset {}", offset);
+        }
+    }
+
+    #[test]
+    fn test_repeated_parsing_gives_same_results() {
+        let html = r#"
+        <script>
+            videos.push({ src: "https://pf-storage3.premiumcdn.net/abc/720p.mp4?token=a", type: 'video/mp4', res: '720', label: '720p' });
+            videos.push({ src: "https://pf-storage3.premiumcdn.net/abc/1080p.mp4?token=b", type: 'video/mp4', res: '1080', label: '1080p', default: true });
+        </script>
+        "#;
+
+        let first = parse_video_sources(html);
+        assert_eq!(first.len(), 2);
+        for _ in 0..3 {
+            assert_eq!(parse_video_sources(html), first);
+        }
+    }
+
     // -----------------------------------------------------------------------
     // parse_video_sources — VideoJS
     // -----------------------------------------------------------------------
@@ -536,6 +1059,21 @@ mod tests {
         assert!(!sources[1].is_default);
     }
 
+    #[test]
+    fn test_parse_video_sources_videojs_unquoted_res() {
+        let html = r#"<script>
+            videos.push({src:"https://pf-storage3.premiumcdn.net/abc/1080p.mp4?token=x",type:'video/mp4',res:1080,label:'1080p'});
+            videos.push({ src: "https://pf-storage3.premiumcdn.net/abc/720p.mp4?token=y", type: 'video/mp4', res: "720", label: '720p' });
+        </script>"#;
+
+        let sources = parse_video_sources(html);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].resolution, 1080);
+        assert_eq!(sources[0].label, "1080p");
+        assert!(sources[0].url.contains("1080p.mp4"));
+        assert_eq!(sources[1].resolution, 720);
+    }
+
     // -----------------------------------------------------------------------
     // parse_video_sources — JWPlayer
     // -----------------------------------------------------------------------
@@ -563,6 +1101,54 @@ mod tests {
         assert_eq!(sources[1].label, "1080p");
     }
 
+    // -----------------------------------------------------------------------
+    // parse_video_sources — object keyed by resolution
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_parse_video_sources_object_form() {
+        let html = r#"
+        <script>
+            var player = new Player({
+                sources: {
+                    "1080": "https://pf-storage2.premiumcdn.net/abc/1080p.mp4?token=a",
+                    '720p': 'https://pf-storage2.premiumcdn.net/abc/720p.mp4?token=b',
+                    480: "https://pf-storage2.premiumcdn.net/abc/480p.webm?token=c"
+                },
+                autoplay: false
+            });
+        </script>
+        "#;
+
+        let sources = parse_video_sources(html);
+        assert_eq!(sources.len(), 3);
+
+        assert_eq!(sources[0].resolution, 1080);
+        assert_eq!(sources[0].label, "1080p");
+        assert!(sources[0].url.ends_with("1080p.mp4?token=a"));
+
+        assert_eq!(sources[1].resolution, 720);
+        assert_eq!(sources[2].resolution, 480);
+        assert_eq!(sources[2].format, Some("webm".to_string()));
+        assert!(sources.iter().all(|s| !s.is_default && !s.is_adaptive));
+    }
+
+    #[test]
+    fn test_parse_video_sources_object_form_not_used_when_array_found() {
+        let html = r#"
+        <script>
+            var sources = [
+                { file: "https://pf-storage3.premiumcdn.net/abc/720p.mp4?token=a", label: '720p' }
+            ];
+            var alt = { sources: { "1080": "https://other.premiumcdn.net/1080p.mp4" } };
+        </script>
+        "#;
+
+        let sources = parse_video_sources(html);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].resolution, 720);
+    }
+
     // -----------------------------------------------------------------------
     // parse_video_sources — both blocks (VideoJS preferred)
     // -----------------------------------------------------------------------
@@ -588,6 +1174,160 @@ mod tests {
         assert!(sources[0].url.contains("videojs"));
     }
 
+    // -----------------------------------------------------------------------
+    // parse_video_sources — mirrors (same resolution, different hosts)
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_parse_video_sources_keeps_videojs_mirrors() {
+        let html = r#"
+        <script>
+            videos.push({ src: "https://pf-storage1.premiumcdn.net/abc/1080p.mp4?token=x", type: 'video/mp4', res: '1080', label: '1080p', default: true });
+            videos.push({ src: "https://pf-storage7.premiumcdn.net/abc/1080p.mp4?token=y", type: 'video/mp4', res: '1080', label: '1080p' });
+        </script>
+        "#;
+
+        let sources = parse_video_sources(html);
+        assert_eq!(sources.len(), 2);
+        assert!(sources[0].url.contains("pf-storage1"));
+        assert!(sources[1].url.contains("pf-storage7"));
+        assert!(sources.iter().all(|s| s.resolution == 1080));
+    }
+
+    #[test]
+    fn test_parse_video_sources_jwplayer_without_label() {
+        let html = r#"
+        <script>
+            var sources = [
+                { file: "https://pf-storage3.premiumcdn.net/abc/Movie.1080p.mp4?token=a" },
+                { file: "https://pf-storage3.premiumcdn.net/abc/720p.mp4?token=b", label: '720p' }
+            ];
+            var tracks = [
+                { file: "https://pf-storage3.premiumcdn.net/abc/eng.vtt?token=c", kind: "captions" }
+            ];
+        </script>
+        "#;
+
+        let sources = parse_video_sources(html);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].resolution, 1080);
+        assert_eq!(sources[0].label, "1080p");
+        assert_eq!(sources[1].resolution, 720);
+        assert_eq!(sources[1].label, "720p");
+    }
+
+    #[test]
+    fn test_parse_video_sources_keeps_jwplayer_mirrors() {
+        let html = r#"
+        <script>
+            var sources = [
+                { file: "https://pf-storage2.premiumcdn.net/abc/720p.mp4?token=a", label: '720p' },
+                { file: "https://pf-storage5.premiumcdn.net/abc/720p.mp4?token=b", label: '720p' }
+            ];
+        </script>
+        "#;
+
+        let sources = parse_video_sources(html);
+        assert_eq!(sources.len(), 2);
+        assert_ne!(sources[0].url, sources[1].url);
+        assert!(sources.iter().all(|s| s.resolution == 720));
+    }
+
+    // -----------------------------------------------------------------------
+    // parse_video_sources — adaptive (HLS) mixed with progressive
+    // -----------------------------------------------------------------------
+
+    const MIXED_ADAPTIVE_PAGE: &str = r#"
+        <script>
+            var videos = [];
+            videos.push({ src: "https://pf-storage3.premiumcdn.net/abc/master.m3u8?token=h", type: 'application/x-mpegURL' });
+            videos.push({ src: "https://pf-storage3.premiumcdn.net/abc/480p.mp4?token=a", type: 'video/mp4', res: '480', label: '480p' });
+            videos.push({ src: "https://pf-storage3.premiumcdn.net/abc/1080p.mp4?token=b", type: 'video/mp4', res: '1080', label: '1080p' });
+            videos.push({ src: "https://pf-storage3.premiumcdn.net/abc/720p.mp4?token=c", type: 'video/mp4', res: '720', label: '720p', default: true });
+        </script>
+    "#;
+
+    #[test]
+    fn test_parse_video_sources_mixed_adaptive() {
+        let sources = parse_video_sources(MIXED_ADAPTIVE_PAGE);
+        assert_eq!(sources.len(), 4);
+
+        let adaptive: Vec<_> = sources.iter().filter(|s| s.is_adaptive).collect();
+        assert_eq!(adaptive.len(), 1);
+        assert!(adaptive[0].url.contains("master.m3u8"));
+        assert_eq!(adaptive[0].resolution, 0);
+        assert_eq!(adaptive[0].format, Some("m3u8".to_string()));
+
+        assert_eq!(sources.iter().filter(|s| !s.is_adaptive).count(), 3);
+    }
+
+    #[test]
+    fn test_parse_direct_url_ignores_adaptive() {
+        let url = parse_direct_url(MIXED_ADAPTIVE_PAGE).unwrap();
+        assert!(url.contains("1080p.mp4"));
+    }
+
+    fn zero_source(url: &str, is_adaptive: bool) -> VideoSource {
+        VideoSource {
+            resolution: 0,
+            label: String::new(),
+            is_adaptive,
+            ..source_from_download_href(url)
+        }
+    }
+
+    fn sized_source(url: &str, resolution: u32, is_adaptive: bool) -> VideoSource {
+        VideoSource {
+            resolution,
+            label: format!("{}p", resolution),
+            is_adaptive,
+            ..source_from_download_href(url)
+        }
+    }
+
+    #[test]
+    fn test_best_source_mixed_zero_and_positive() {
+        let sources = [
+            zero_source("https://cdn.premiumcdn.net/plain.mp4", false),
+            sized_source("https://cdn.premiumcdn.net/480p.mp4", 480, false),
+            zero_source("https://cdn.premiumcdn.net/master.m3u8", true),
+            sized_source("https://cdn.premiumcdn.net/720p.mp4", 720, false),
+        ];
+        assert!(best_source(&sources).unwrap().url.ends_with("720p.mp4"));
+        let strict = best_source_with(&sources, false).unwrap();
+        assert!(strict.url.ends_with("720p.mp4"));
+
+        // A labelled adaptive stream beats a label-less file
+        let sources = [
+            zero_source("https://cdn.premiumcdn.net/plain.mp4", false),
+            sized_source("https://cdn.premiumcdn.net/master.m3u8", 1080, true),
+        ];
+        assert!(best_source(&sources).unwrap().url.ends_with("master.m3u8"));
+    }
+
+    #[test]
+    fn test_best_source_all_zero() {
+        let sources = [
+            zero_source("https://cdn.premiumcdn.net/master.m3u8", true),
+            zero_source("https://cdn.premiumcdn.net/first.mp4", false),
+            zero_source("https://cdn.premiumcdn.net/second.mp4", false),
+        ];
+        // Progressive before adaptive, then page order
+        assert!(best_source(&sources).unwrap().url.ends_with("first.mp4"));
+        assert_eq!(best_source_with(&sources, false), None);
+        assert_eq!(best_source(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_direct_url_only_adaptive() {
+        let html = r#"<script>
+            var sources = [{ file: "https://pf-storage3.premiumcdn.net/abc/manifest.mpd?token=d" }];
+        </script>"#;
+
+        let url = parse_direct_url(html).unwrap();
+        assert!(url.contains("manifest.mpd"));
+    }
+
     // -----------------------------------------------------------------------
     // parse_video_sources — empty
     // -----------------------------------------------------------------------
@@ -621,6 +1361,32 @@ mod tests {
         assert!(!source.is_default);
     }
 
+    #[test]
+    fn test_parse_original_download_url_json() {
+        let url = "https://pf-storage3.premiumcdn.net/165065360/abc?filename=Movie+2160p+HEVC.mkv&token=xyz&expires=123";
+        let html = format!(
+            r#"<html><body><a href="{}">Please click here to continue</a></body></html>"#,
+            url
+        );
+        let json = format!(r#"{{"url": "{}"}}"#, url);
+
+        let from_html = parse_original_download_url(&html).unwrap();
+        let from_json = parse_original_download_url(&json).unwrap();
+        assert_eq!(from_json, from_html);
+        assert_eq!(from_json.resolution, 2160);
+
+        let aliased = format!(r#"  {{"link": "{}", "status": "ok"}}"#, url);
+        assert_eq!(parse_original_download_url(&aliased).unwrap(), from_html);
+    }
+
+    #[test]
+    fn test_parse_original_download_url_json_without_cdn_url() {
+        let result = parse_original_download_url(r#"{"url": "https://example.com/a.mp4"}"#);
+        assert!(matches!(result, Err(PrehrajtoError::NotFound(_))));
+        let result = parse_original_download_url(r#"{"error": "login required"}"#);
+        assert!(matches!(result, Err(PrehrajtoError::NotFound(_))));
+    }
+
     #[test]
     fn test_parse_original_download_url_no_link() {
         let html = r#"<html><body><p>No link here</p></body></html>"#;
@@ -633,6 +1399,65 @@ mod tests {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // parse_download_options
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_parse_download_options_with_packaged() {
+        let html = r#"
+        <html><body>
+            <p><a class="btn-subtitles" href="https://pf-storage3.premiumcdn.net/165065360/pkg?filename=Movie+1080p.mkv&token=a">Stáhnout s titulky</a></p>
+            <p><a href="https://pf-storage3.premiumcdn.net/165065360/abc?filename=Movie+1080p.mkv&token=b">Please click here to continue</a></p>
+        </body></html>
+        "#;
+
+        let options = parse_download_options(html).unwrap();
+        assert!(options.original.url.contains("token=b"));
+        assert!(!options.original.includes_subtitles);
+
+        let packaged = options.packaged.expect("packaged download expected");
+        assert!(packaged.url.contains("token=a"));
+        assert!(packaged.includes_subtitles);
+        assert_eq!(packaged.resolution, 1080);
+
+        // The plain original is still preferred by parse_original_download_url
+        let original = parse_original_download_url(html).unwrap();
+        assert!(original.url.contains("token=b"));
+    }
+
+    #[test]
+    fn test_parse_download_options_original_named_titulky() {
+        let html = r#"
+        <html><body>
+            <p><a href="https://pf-storage3.premiumcdn.net/165065360/abc?filename=Film+CZ+titulky.mkv&token=b">Please click here to continue</a></p>
+            <p><a href="https://pf-storage3.premiumcdn.net/165065360/pkg?filename=Film+CZ+titulky.mkv&token=a">Stáhnout s titulky</a></p>
+        </body></html>
+        "#;
+
+        let options = parse_download_options(html).unwrap();
+        assert!(options.original.url.contains("token=b"));
+        assert!(!options.original.includes_subtitles);
+
+        let packaged = options.packaged.expect("packaged download expected");
+        assert!(packaged.url.contains("token=a"));
+        assert!(packaged.includes_subtitles);
+    }
+
+    #[test]
+    fn test_parse_download_options_without_packaged() {
+        let html = r#"
+        <html><body>
+            <p><a href="https://pf-storage3.premiumcdn.net/165065360/abc?filename=Movie+720p.mp4&token=b">Please click here to continue</a></p>
+        </body></html>
+        "#;
+
+        let options = parse_download_options(html).unwrap();
+        assert!(options.packaged.is_none());
+        assert_eq!(options.original.resolution, 720);
+        assert!(!options.original.includes_subtitles);
+    }
+
     // -----------------------------------------------------------------------
     // parse_direct_url — best quality selection
     // -----------------------------------------------------------------------
@@ -652,10 +1477,35 @@ mod tests {
         assert!(result.contains("1080p.mp4"));
     }
 
+    #[test]
+    fn test_parse_direct_url_decodes_percent_encoded_path() {
+        let html = r#"<a href="https://pf-storage3.premiumcdn.net/abc%2FAndel%C3%A9%20Who.mp4?token=a%2Bb&amp;expires=1">Stáhnout</a>"#;
+
+        let result = parse_direct_url(html).unwrap();
+        assert_eq!(
+            result,
+            "https://pf-storage3.premiumcdn.net/abc%2FAndelé%20Who.mp4?token=a%2Bb&expires=1"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // Resolution & format helpers
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn test_parse_player_fingerprint() {
+        let html = r#"<script>var a = 1;</script><script>
+            videos.push({ src: "https://pf-storage4.premiumcdn.net/1/v.mp4?token=abc", res: '720', label: '720p' });
+            </script>"#;
+        let retokened = html.replace("token=abc", "token=xyz&expires=99");
+        let reindented = html.replace("\n            ", "\n  ");
+
+        let fingerprint = parse_player_fingerprint(html).unwrap();
+        assert_eq!(parse_player_fingerprint(&retokened).unwrap(), fingerprint);
+        assert_eq!(parse_player_fingerprint(&reindented).unwrap(), fingerprint);
+        assert!(parse_player_fingerprint("<script>var a = 1;</script>").is_none());
+    }
+
     #[test]
     fn test_parse_resolution_from_label() {
         assert_eq!(parse_resolution_from_label("720p"), 720);
@@ -814,11 +1664,43 @@ mod tests {
     #[test]
     fn test_is_cdn_url() {
         assert!(is_cdn_url(
-            "https://prg-c8-storage5.premiumcdn.net/123/file.mp4"
+            "https://prg-c8-storage5.premiumcdn.net/123/file.mp4",
+            &[]
         ));
-        assert!(is_cdn_url("https://cdn.premiumcdn.net/file.mp4"));
-        assert!(!is_cdn_url("https://prehraj.to/video/123"));
-        assert!(!is_cdn_url("https://example.com/file.mp4"));
+        assert!(is_cdn_url("https://cdn.premiumcdn.net/file.mp4", &[]));
+        assert!(!is_cdn_url("https://prehraj.to/video/123", &[]));
+        assert!(!is_cdn_url("https://example.com/file.mp4", &[]));
+    }
+
+    #[test]
+    fn test_parse_cdn_hosts() {
+        let html = r#"<script>
+            videos.push({ src: "https://pf-storage4.premiumcdn.net/a/720p.mp4?token=x", res: '720', label: '720p' });
+            videos.push({ src: "https://pf-storage4.premiumcdn.net/a/1080p.mp4?token=y", res: '1080', label: '1080p' });
+            var tracks = [{ file: "https://pf-storage9.vodcache.example:8443/a/eng.vtt", label: "ENG", kind: "captions" }];
+            var ad = { file: "https://ads.adnetwork.example/preroll.mp4" };
+            var logo = { src: "https://storage.example/logo.png" };
+        </script>"#;
+
+        assert_eq!(
+            parse_cdn_hosts(html),
+            vec!["pf-storage4.premiumcdn.net", "pf-storage9.vodcache.example"]
+        );
+    }
+
+    #[test]
+    fn test_discovered_cdn_host_is_accepted() {
+        let hosts = vec!["pf-storage1.discovered-cdn.example".to_string()];
+        let url = "https://pf-storage1.discovered-cdn.example/v/file.mp4?token=x";
+        assert!(!is_cdn_url(url, &[]));
+        assert!(is_cdn_url(url, &hosts));
+        let parent = "https://discovered-cdn.example/file.mp4";
+        assert!(!is_cdn_url(parent, &hosts));
+
+        let page = format!(r#"<html><a href="{}">Download</a></html>"#, url);
+        assert!(parse_download_options(&page).is_err());
+        let options = parse_download_options_with_hosts(&page, &hosts).unwrap();
+        assert_eq!(options.original.url, url);
     }
 
     #[test]
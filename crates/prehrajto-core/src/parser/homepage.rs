@@ -0,0 +1,239 @@
+//! Homepage parser for prehraj.to
+//!
+//! Parses the homepage listings ("latest" and "most viewed") into video results.
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::error::{PrehrajtoError, Result};
+use crate::parser::search::parse_video_card;
+use crate::types::{HomepageSections, VideoResult};
+
+/// Which homepage listing a section container belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionKind {
+    Latest,
+    Popular,
+}
+
+/// Keywords identifying the "latest" section (id/class or heading text)
+const LATEST_KEYWORDS: &[&str] = &["latest", "newest", "nejnovější", "nově přidané"];
+
+/// Keywords identifying the "popular" section (id/class or heading text)
+const POPULAR_KEYWORDS: &[&str] = &["popular", "most-viewed", "nejsledovanější", "populární"];
+
+/// Parses homepage HTML and returns the latest and popular listings
+///
+/// Each listing lives in its own `<section>` container. The section kind is
+/// taken from the container's `id`/`class` attributes, falling back to its
+/// heading text; keywords match whole words only. Wrapper sections that
+/// contain a classified section are skipped so cards aren't collected twice.
+/// Video cards inside are parsed the same way as search results.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the homepage
+///
+/// # Returns
+/// [`HomepageSections`] with empty vecs for sections that aren't present
+///
+/// # Errors
+/// Returns `ParseError` if a selector fails to compile
+pub fn parse_homepage(html: &str) -> Result<HomepageSections> {
+    let document = Html::parse_document(html);
+
+    let section_selector = Selector::parse("section")
+        .map_err(|e| PrehrajtoError::ParseError(format!("Invalid selector: {:?}", e)))?;
+    let link_selector = Selector::parse("a[href]")
+        .map_err(|e| PrehrajtoError::ParseError(format!("Invalid selector: {:?}", e)))?;
+
+    let mut sections = HomepageSections::default();
+
+    for section in document.select(&section_selector) {
+        let wraps_listing = section
+            .select(&section_selector)
+            .any(|inner| inner.id() != section.id() && classify_section(&inner).is_some());
+        if wraps_listing {
+            continue;
+        }
+
+        let target = match classify_section(&section) {
+            Some(SectionKind::Latest) => &mut sections.latest,
+            Some(SectionKind::Popular) => &mut sections.popular,
+            None => continue,
+        };
+
+        let videos: Vec<VideoResult> = section
+            .select(&link_selector)
+            .filter_map(|link| parse_video_card(&link))
+            .collect();
+        for mut video in videos {
+            video.position = target.len();
+            target.push(video);
+        }
+    }
+
+    Ok(sections)
+}
+
+/// Determines the section kind from its attributes or heading
+fn classify_section(section: &ElementRef) -> Option<SectionKind> {
+    let attrs = [section.value().id(), section.value().attr("class")]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    if let Some(kind) = match_keywords(&attrs) {
+        return Some(kind);
+    }
+
+    let heading_selector = Selector::parse("h1, h2").ok()?;
+    let heading = section
+        .select(&heading_selector)
+        .next()?
+        .text()
+        .collect::<String>()
+        .to_lowercase();
+
+    match_keywords(&heading)
+}
+
+/// Matches text against the section keyword lists
+///
+/// Both sides are split into words on anything that isn't alphanumeric
+/// (whitespace, `-`, `_`, punctuation), and a keyword matches only as a run
+/// of whole words, so `november` or `renovace` never count.
+fn match_keywords(text: &str) -> Option<SectionKind> {
+    let words = split_words(text);
+    let contains = |keyword: &&str| {
+        let keyword = split_words(keyword);
+        words.windows(keyword.len()).any(|run| run == keyword.as_slice())
+    };
+
+    if POPULAR_KEYWORDS.iter().any(contains) {
+        return Some(SectionKind::Popular);
+    }
+    if LATEST_KEYWORDS.iter().any(contains) {
+        return Some(SectionKind::Latest);
+    }
+    None
+}
+
+/// Splits text into its alphanumeric words
+fn split_words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_homepage_both_sections() {
+        let html = r#"
+        <html>
+        <body>
+        <main>
+            <section class="homepage-latest">
+                <h2>Nejnovější videa</h2>
+                <a href="/new-video-one/abc123">
+                    <div><div>00:42:00</div><div>700 MB</div></div>
+                    <h3>New Video One</h3>
+                </a>
+                <a href="/new-video-two/def456">
+                    <h3>New Video Two</h3>
+                </a>
+            </section>
+            <section>
+                <h2>Nejsledovanější</h2>
+                <a href="/popular-video/ghi789">
+                    <div><div>01:30:00</div><div>HD</div><div>2 GB</div></div>
+                    <h3>Popular Video</h3>
+                </a>
+            </section>
+        </main>
+        </body>
+        </html>
+        "#;
+
+        let sections = parse_homepage(html).unwrap();
+
+        assert_eq!(sections.latest.len(), 2);
+        assert_eq!(sections.latest[0].video_id, "abc123");
+        assert_eq!(sections.latest[0].duration, Some("00:42:00".to_string()));
+        assert_eq!(sections.latest[1].name, "New Video Two");
+
+        assert_eq!(sections.popular.len(), 1);
+        assert_eq!(sections.popular[0].video_id, "ghi789");
+        assert_eq!(sections.popular[0].quality, Some("HD".to_string()));
+    }
+
+    #[test]
+    fn test_parse_homepage_missing_section() {
+        let html = r#"
+        <html>
+        <body>
+        <main>
+            <section id="popular">
+                <a href="/popular-video/ghi789"><h3>Popular Video</h3></a>
+            </section>
+            <section>
+                <h2>Kategorie</h2>
+                <a href="/some-category/xyz"><h3>Category</h3></a>
+            </section>
+        </main>
+        </body>
+        </html>
+        "#;
+
+        let sections = parse_homepage(html).unwrap();
+        assert!(sections.latest.is_empty());
+        assert_eq!(sections.popular.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_homepage_nested_sections() {
+        let html = r#"
+        <html>
+        <body>
+        <main>
+            <section class="homepage">
+                <section id="latest">
+                    <h2>Nejnovější</h2>
+                    <a href="/new-video/abc123"><h3>New Video</h3></a>
+                </section>
+                <section id="most-viewed">
+                    <h2>Nejsledovanější</h2>
+                    <a href="/popular-video/ghi789"><h3>Popular Video</h3></a>
+                </section>
+            </section>
+        </main>
+        </body>
+        </html>
+        "#;
+
+        let sections = parse_homepage(html).unwrap();
+        assert_eq!(sections.latest.len(), 1);
+        assert_eq!(sections.latest[0].video_id, "abc123");
+        assert_eq!(sections.popular.len(), 1);
+        assert_eq!(sections.popular[0].video_id, "ghi789");
+    }
+
+    #[test]
+    fn test_match_keywords_whole_words() {
+        assert_eq!(match_keywords("homepage-latest"), Some(SectionKind::Latest));
+        assert_eq!(match_keywords("nově přidané filmy"), Some(SectionKind::Latest));
+        assert_eq!(match_keywords("most_viewed"), Some(SectionKind::Popular));
+        assert_eq!(match_keywords("november"), None);
+        assert_eq!(match_keywords("novela renovace"), None);
+    }
+
+    #[test]
+    fn test_parse_homepage_empty() {
+        let sections = parse_homepage("<html><body></body></html>").unwrap();
+        assert!(sections.latest.is_empty());
+        assert!(sections.popular.is_empty());
+    }
+}
@@ -3,9 +3,23 @@
 //! Contains modules for parsing different page types.
 
 pub mod direct_url;
+pub mod homepage;
 pub mod search;
+pub mod series;
+pub mod site;
+pub mod video_page;
 
 pub use direct_url::{
-    parse_direct_url, parse_original_download_url, parse_subtitle_tracks, parse_video_sources,
+    parse_cdn_hosts, parse_direct_url, parse_download_options, parse_original_download_url,
+    parse_player_fingerprint, parse_subtitle_tracks, parse_video_sources,
+};
+pub use homepage::parse_homepage;
+pub use search::{
+    parse_search_fragment, parse_search_results, parse_search_results_callback,
+    parse_search_results_from_reader,
+};
+pub use series::parse_series_page;
+pub use site::is_prehrajto_page;
+pub use video_page::{
+    parse_availability, parse_reported_quality, parse_video_metadata, parse_video_page_result,
 };
-pub use search::parse_search_results;
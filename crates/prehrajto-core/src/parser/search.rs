@@ -1,11 +1,19 @@
 //! Search results parser for prehraj.to
 //!
 //! Parses HTML from search results page and extracts video information.
+//!
+//! prehraj.to renders search results server-side as plain DOM cards; the
+//! page embeds no `__NEXT_DATA__`-style JSON island with the results, so the
+//! cards are the only source and there is no JSON path to prefer.
 
+use regex::Regex;
 use scraper::{Html, Selector, ElementRef};
+use std::io::Read;
+use std::sync::LazyLock;
 use crate::error::{PrehrajtoError, Result};
+use crate::text::{parse_duration_to_secs, parse_size_to_bytes};
 use crate::types::VideoResult;
-use crate::url::{build_download_url, extract_video_info};
+use crate::url::{build_download_url, build_video_url, extract_video_info, parse_video_link};
 
 /// Parses search results HTML and returns a list of video results
 ///
@@ -18,49 +26,198 @@ use crate::url::{build_download_url, extract_video_info};
 /// # Errors
 /// Returns `ParseError` if HTML structure is invalid
 pub fn parse_search_results(html: &str) -> Result<Vec<VideoResult>> {
-    let document = Html::parse_document(html);
-    
+    let mut results = Vec::new();
+    parse_search_results_callback(html, |video| results.push(video))?;
+    Ok(results)
+}
+
+/// Parses search results from a reader, e.g. a saved `.html` dump or stdin
+///
+/// Reads everything and parses it with [`parse_search_results`], so no
+/// network access is needed. Invalid UTF-8 is replaced rather than rejected,
+/// as saved pages are sometimes re-encoded.
+///
+/// # Arguments
+/// * `reader` - Source of the search results page HTML
+///
+/// # Returns
+/// Vector of `VideoResult` structs, empty if no results found
+///
+/// # Errors
+/// Returns `ParseError` if reading fails or the HTML structure is invalid
+pub fn parse_search_results_from_reader<R: Read>(mut reader: R) -> Result<Vec<VideoResult>> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| PrehrajtoError::ParseError(format!("Failed to read HTML: {}", e)))?;
+    parse_search_results(&String::from_utf8_lossy(&bytes))
+}
+
+/// Parses search results HTML, handing each video to a callback as it's parsed
+///
+/// Push-style variant of [`parse_search_results`]: `on_result` is invoked
+/// once per valid video card, in page order, without waiting for the whole
+/// page to be processed.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from search results page
+/// * `on_result` - Called with each parsed `VideoResult`
+///
+/// # Returns
+/// Number of results delivered to the callback
+///
+/// # Errors
+/// Returns `ParseError` if HTML structure is invalid
+pub fn parse_search_results_callback(
+    html: &str,
+    on_result: impl FnMut(VideoResult),
+) -> Result<usize> {
+    // Only the `<main>` region holds video cards, so parse just that slice
+    // and skip the header, ads and scripts around it
+    let document = match main_region(html) {
+        Some(main) => Html::parse_fragment(main),
+        None => Html::parse_document(html),
+    };
+    parse_video_cards(&document, on_result)
+}
+
+/// Opening `<main>` tag, any case and with optional attributes
+static MAIN_OPEN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<main[\s>]").expect("valid main open regex"));
+
+/// Closing `</main>` tag, any case
+static MAIN_CLOSE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)</main\s*>").expect("valid main close regex"));
+
+/// Inline `<script>...</script>` block, any case
+static SCRIPT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<script[\s>].*?</script\s*>").expect("valid script regex"));
+
+/// Slices the `<main>...</main>` region out of a page
+///
+/// Spans from the first opening tag to the last closing tag, ignoring tags
+/// that only appear inside inline scripts. Returns `None` if either tag is
+/// missing, so the caller parses the full document instead.
+fn main_region(html: &str) -> Option<&str> {
+    let scripts: Vec<std::ops::Range<usize>> =
+        SCRIPT_RE.find_iter(html).map(|m| m.range()).collect();
+    let in_script = |at: usize| scripts.iter().any(|script| script.contains(&at));
+
+    let start = MAIN_OPEN_RE
+        .find_iter(html)
+        .find(|m| !in_script(m.start()))?
+        .start();
+    let end = MAIN_CLOSE_RE
+        .find_iter(&html[start..])
+        .filter(|m| !in_script(start + m.start()))
+        .last()?
+        .end();
+    Some(&html[start..start + end])
+}
+
+/// Hands every video card under `<main>` in `document` to `on_result`
+fn parse_video_cards(document: &Html, mut on_result: impl FnMut(VideoResult)) -> Result<usize> {
     // Select all video card links in main content
     // Based on docs: main > div > div contains <a> links for each video
     let link_selector = Selector::parse("main a[href]")
         .map_err(|e| PrehrajtoError::ParseError(format!("Invalid selector: {:?}", e)))?;
-    
-    let mut results = Vec::new();
-    
+
+    let mut count = 0;
+
     for element in document.select(&link_selector) {
         // Try to parse each link as a video card
-        if let Some(video) = parse_video_card(&element) {
+        if let Some(mut video) = parse_video_card(&element) {
+            video.position = count;
+            count += 1;
+            on_result(video);
+        }
+    }
+
+    Ok(count)
+}
+
+/// Parses a lazy-loaded batch of search results
+///
+/// Accepts the body returned when a results page is requested as XHR (see
+/// [site assumptions](crate::url#site-assumptions)) in any of the shapes the
+/// site may send:
+/// - Nette snippet JSON: `{"snippets": {"snippet--id": "<a ...>...</a>"}}`
+/// - a bare HTML fragment containing the video card links
+/// - a full search page (handled by [`parse_search_results`])
+///
+/// # Arguments
+/// * `body` - Raw response body from the XHR endpoint
+///
+/// # Returns
+/// Vector of `VideoResult` structs, empty if the batch has no results
+///
+/// # Errors
+/// Returns `ParseError` if the body looks like JSON but can't be decoded
+pub fn parse_search_fragment(body: &str) -> Result<Vec<VideoResult>> {
+    let trimmed = body.trim_start();
+
+    let html = if trimmed.starts_with('{') {
+        let payload: serde_json::Value = serde_json::from_str(trimmed).map_err(|e| {
+            PrehrajtoError::ParseError(format!("Invalid search fragment JSON: {}", e))
+        })?;
+        payload
+            .get("snippets")
+            .and_then(|snippets| snippets.as_object())
+            .map(|snippets| {
+                snippets
+                    .values()
+                    .filter_map(|snippet| snippet.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default()
+    } else {
+        trimmed.to_string()
+    };
+
+    if html.contains("<main") {
+        return parse_search_results(&html);
+    }
+
+    let fragment = Html::parse_fragment(&html);
+    let link_selector = Selector::parse("a[href]")
+        .map_err(|e| PrehrajtoError::ParseError(format!("Invalid selector: {:?}", e)))?;
+
+    let mut results = Vec::new();
+    for element in fragment.select(&link_selector) {
+        if let Some(mut video) = parse_video_card(&element) {
+            video.position = results.len();
             results.push(video);
         }
     }
-    
+
     Ok(results)
 }
 
 /// Parses a single video card element
 ///
+/// The returned result has `position` 0; callers assign the real position.
+///
 /// # Arguments
 /// * `element` - Reference to an `<a>` element containing video card
 ///
 /// # Returns
 /// `Some(VideoResult)` if parsing succeeds, `None` otherwise
-fn parse_video_card(element: &ElementRef) -> Option<VideoResult> {
+pub(crate) fn parse_video_card(element: &ElementRef) -> Option<VideoResult> {
     // Get href attribute
     let href = element.value().attr("href")?;
     
-    // Extract slug and id from URL
-    let (video_slug, video_id) = extract_video_info(href)?;
-    
-    // Build URLs
-    let url = format!("https://prehraj.to{}", href.split('?').next().unwrap_or(href));
+    // Extract slug and id from URL (absolute links on any prehraj.to origin
+    // or site paths; query strings and fragments are ignored)
+    let (video_slug, video_id) = parse_video_link(href).or_else(|| extract_video_info(href))?;
+
+    // Build canonical URLs from slug and id rather than reusing the href
+    let url = build_video_url(&video_slug, &video_id);
     let download_url = build_download_url(&video_slug, &video_id);
     
-    // Extract video name from h3
+    // Extract video name (and secondary title, if any) from h3
     let h3_selector = Selector::parse("h3").ok()?;
-    let name = element
-        .select(&h3_selector)
-        .next()
-        .map(|el| el.text().collect::<String>().trim().to_string())?;
+    let (name, original_title) = element.select(&h3_selector).next().map(card_title)?;
     
     // Skip if name is empty (not a video card)
     if name.is_empty() {
@@ -85,21 +242,110 @@ fn parse_video_card(element: &ElementRef) -> Option<VideoResult> {
     }
     
     let duration = extract_duration(&texts);
+    let duration_secs = duration.as_deref().and_then(parse_duration_to_secs);
     let quality = extract_quality_from_element(element).or_else(|| extract_quality(&texts));
+    let resolution =
+        extract_resolution_from_element(element).or_else(|| extract_resolution(&texts));
     let file_size = extract_file_size(&texts);
+    let file_size_bytes = file_size.as_deref().and_then(parse_size_to_bytes);
+    let upload_date = extract_upload_date(&texts);
     
     Some(VideoResult {
         name,
+        original_title,
         url,
         video_id,
         video_slug,
         download_url,
         duration,
+        duration_secs,
         quality,
+        resolution,
         file_size,
+        file_size_bytes,
+        upload_date,
+        position: 0,
     })
 }
 
+/// Class of the `<span>` holding a card's original title
+const ORIGINAL_TITLE_CLASS: &str = "original";
+
+/// Class of the quality/resolution badge spans, never part of a title
+const BADGE_CLASS: &str = "format__text";
+
+/// Splits a card's `<h3>` into the main title and a secondary title
+///
+/// Cards of dubbed films add the original title in a `span.original`,
+/// either inside the `<h3>` or as the element right after it. Its text
+/// becomes the secondary title and the rest of the `<h3>` the main title;
+/// badge spans (`span.format__text`) and any other neighbouring spans are
+/// ignored. If the `<h3>` holds nothing but the original title, that text
+/// is the main title instead.
+fn card_title(h3: ElementRef) -> (String, Option<String>) {
+    let mut main = String::new();
+    let mut secondary = String::new();
+
+    for node in h3.descendants() {
+        let Some(text) = node.value().as_text() else {
+            continue;
+        };
+        let spans: Vec<_> = node
+            .ancestors()
+            .take_while(|ancestor| ancestor.id() != h3.id())
+            .filter_map(|ancestor| ancestor.value().as_element())
+            .filter(|element| element.name() == "span")
+            .collect();
+        if spans.iter().any(|span| has_class(span, BADGE_CLASS)) {
+            continue;
+        }
+        let original = spans
+            .iter()
+            .any(|span| has_class(span, ORIGINAL_TITLE_CLASS));
+        let target = if original { &mut secondary } else { &mut main };
+        target.push(' ');
+        target.push_str(text);
+    }
+
+    if secondary.trim().is_empty()
+        && let Some(sibling) = h3.next_siblings().find_map(ElementRef::wrap)
+        && sibling.value().name() == "span"
+        && has_class(sibling.value(), ORIGINAL_TITLE_CLASS)
+    {
+        secondary = sibling.text().collect();
+    }
+
+    let (main, secondary) = (clean_name(&main), clean_name(&secondary));
+    match (main.is_empty(), secondary.is_empty()) {
+        (true, _) => (secondary, None),
+        (false, true) => (main, None),
+        (false, false) => (main, Some(secondary)),
+    }
+}
+
+/// Checks whether an element carries the given class
+fn has_class(element: &scraper::node::Element, class: &str) -> bool {
+    element.classes().any(|c| c == class)
+}
+
+/// Normalizes a scraped name
+///
+/// Trims Unicode whitespace (including non-breaking spaces), collapses
+/// internal runs to a single space and drops stray trailing dots left by
+/// adjacent markup. Those end up detached (`"Matrix ."`), as the card's
+/// text nodes are joined with spaces; dots attached to a word are part of
+/// the name (`"Monsters, Inc."`, `"S.W.A.T."`, an ellipsis) and are kept.
+fn clean_name(name: &str) -> String {
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+
+    let is_stray = |word: &&str| word.chars().all(|c| c == '.');
+    while words.last().is_some_and(is_stray) {
+        words.pop();
+    }
+
+    words.join(" ")
+}
+
 /// Extracts duration from div texts
 ///
 /// Looks for time format HH:MM:SS or MM:SS
@@ -149,6 +395,41 @@ fn extract_quality(divs: &[String]) -> Option<String> {
     None
 }
 
+/// Extracts the resolution badge from element
+///
+/// Looks for span.format__text badges like "1080p" or "4K"; see
+/// [`resolution_badge`]
+fn extract_resolution_from_element(element: &ElementRef) -> Option<u32> {
+    let format_selector = Selector::parse("span.format__text").ok()?;
+    element
+        .select(&format_selector)
+        .find_map(|span| resolution_badge(&span.text().collect::<String>()))
+}
+
+/// Extracts the resolution badge from div texts (fallback)
+fn extract_resolution(divs: &[String]) -> Option<u32> {
+    divs.iter().find_map(|text| resolution_badge(text))
+}
+
+/// Parses a resolution badge into its height
+///
+/// The whole text must be the badge: "720p"/"1080p"/"2160p" give their
+/// number, "4K"/"UHD" give 2160 and "8K" 4320. Anything else (including
+/// the plain "HD" badge, and titles that merely mention a resolution)
+/// gives `None`.
+fn resolution_badge(text: &str) -> Option<u32> {
+    let badge = text.trim().to_lowercase();
+    match badge.as_str() {
+        "4k" | "uhd" => Some(2160),
+        "8k" => Some(4320),
+        _ => badge
+            .strip_suffix('p')?
+            .parse::<u32>()
+            .ok()
+            .filter(|r| (144..=4320).contains(r)),
+    }
+}
+
 /// Extracts file size from div texts
 ///
 /// Looks for patterns like "1.7 GB", "500 MB"
@@ -168,10 +449,133 @@ fn is_file_size_format(text: &str) -> bool {
         && text.chars().any(|c| c.is_ascii_digit())
 }
 
+/// Extracts upload date from div texts
+///
+/// Looks for dates in the `dd.mm.yyyy` form (e.g., "24.12.2023")
+fn extract_upload_date(divs: &[String]) -> Option<String> {
+    for text in divs {
+        if is_date_format(text) {
+            return Some(text.clone());
+        }
+    }
+    None
+}
+
+/// Checks if text matches date format (d.m.yyyy or dd.mm.yyyy)
+fn is_date_format(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('.').collect();
+    let [_, _, year] = parts.as_slice() else {
+        return false;
+    };
+    year.len() == 4
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_search_results_from_reader() {
+        let html = r#"<html><body><main>
+            <a href="/doctor-who-s07e05/63aba7f51f6cf"><h3>Doctor Who S07E05</h3></a>
+            <a href="/doctor-who-s07e06/63aba7f51f6d0"><h3>Doctor Who S07E06</h3></a>
+        </main></body></html>"#;
+
+        let results = parse_search_results_from_reader(std::io::Cursor::new(html)).unwrap();
+        assert_eq!(results, parse_search_results(html).unwrap());
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].video_id, "63aba7f51f6cf");
+    }
+
+    #[test]
+    fn test_parse_search_results_from_failing_reader() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk gone"))
+            }
+        }
+
+        let result = parse_search_results_from_reader(Failing);
+        assert!(
+            matches!(result, Err(PrehrajtoError::ParseError(msg)) if msg.contains("disk gone"))
+        );
+    }
+
+    #[test]
+    fn test_parse_card_with_original_title() {
+        let html = r#"<html><body><main>
+            <a href="/pelisky/abc123"><h3>Pelíšky <span class="original">Cosy Dens</span></h3></a>
+            <a href="/matrix/def456"><h3>Matrix</h3><span class="original">The Matrix</span><div>2:16:00</div></a>
+            <a href="/shrek/ghi789"><h3>Shrek</h3><div>1:30:00</div></a>
+        </main></body></html>"#;
+
+        let results = parse_search_results(html).unwrap();
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0].name, "Pelíšky");
+        assert_eq!(results[0].original_title.as_deref(), Some("Cosy Dens"));
+        assert_eq!(results[1].name, "Matrix");
+        assert_eq!(results[1].original_title.as_deref(), Some("The Matrix"));
+        assert_eq!(results[1].duration.as_deref(), Some("2:16:00"));
+        assert_eq!(results[2].name, "Shrek");
+        assert_eq!(results[2].original_title, None);
+    }
+
+    #[test]
+    fn test_parse_card_title_ignores_badge_spans() {
+        let html = r#"<html><body><main>
+            <a href="/matrix/abc123"><h3>Matrix</h3><span class="format__text">HD</span></a>
+            <a href="/shrek/def456"><h3>Shrek <span class="format__text">4K</span></h3></a>
+            <a href="/heat/ghi789"><h3>Heat</h3><span class="label">Novinka</span></a>
+        </main></body></html>"#;
+
+        let results = parse_search_results(html).unwrap();
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0].name, "Matrix");
+        assert_eq!(results[0].original_title, None);
+        assert_eq!(results[0].quality.as_deref(), Some("HD"));
+        assert_eq!(results[1].name, "Shrek");
+        assert_eq!(results[1].original_title, None);
+        assert_eq!(results[1].resolution, Some(2160));
+        assert_eq!(results[2].name, "Heat");
+        assert_eq!(results[2].original_title, None);
+    }
+
+    #[test]
+    fn test_clean_name() {
+        assert_eq!(clean_name("\u{A0}Pelíšky\u{A0}\u{A0}"), "Pelíšky");
+        assert_eq!(clean_name("Doctor   Who \t S07E05"), "Doctor Who S07E05");
+        assert_eq!(clean_name("Matrix . ."), "Matrix");
+        assert_eq!(clean_name("Matrix\u{A0}."), "Matrix");
+        assert_eq!(clean_name("Monsters, Inc."), "Monsters, Inc.");
+        assert_eq!(clean_name("Indiana Jones Jr."), "Indiana Jones Jr.");
+        assert_eq!(clean_name("Dr. Strangelove ."), "Dr. Strangelove");
+        assert_eq!(clean_name("Mr. & Mrs. Smith"), "Mr. & Mrs. Smith");
+        assert_eq!(clean_name("S.W.A.T."), "S.W.A.T.");
+        assert_eq!(clean_name("A pak nezbyl žádný..."), "A pak nezbyl žádný...");
+        assert_eq!(clean_name(" . "), "");
+    }
+
+    #[test]
+    fn test_parse_card_name_is_cleaned() {
+        let html = "<main><a href=\"/m/abc123\"><h3>\u{A0}Matrix  Reloaded\u{A0}.</h3></a></main>";
+        let results = parse_search_results(html).unwrap();
+        assert_eq!(results[0].name, "Matrix Reloaded");
+    }
+
+    #[test]
+    fn test_parse_card_title_only_in_span() {
+        let html = r#"<main><a href="/film/abc123"><h3><span>Film Title</span></h3></a></main>"#;
+        let results = parse_search_results(html).unwrap();
+        assert_eq!(results[0].name, "Film Title");
+        assert_eq!(results[0].original_title, None);
+    }
+
     #[test]
     fn test_parse_empty_html() {
         let html = "<html><body></body></html>";
@@ -213,8 +617,10 @@ mod tests {
         assert_eq!(video.url, "https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf");
         assert_eq!(video.download_url, "https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf?do=download");
         assert_eq!(video.duration, Some("00:44:20".to_string()));
+        assert_eq!(video.duration_secs, Some(2660));
         assert_eq!(video.quality, Some("HD".to_string()));
         assert_eq!(video.file_size, Some("1.7 GB".to_string()));
+        assert_eq!(video.file_size_bytes, Some(1_825_361_101));
     }
 
     #[test]
@@ -250,6 +656,212 @@ mod tests {
         assert_eq!(results[1].quality, Some("HD".to_string()));
     }
 
+    #[test]
+    fn test_parse_search_results_resolution_badges() {
+        let html = r#"
+        <html>
+        <body>
+        <main>
+            <div>
+                <a href="/video-one/abc123">
+                    <div><div>01:00:00</div><div>HD</div><div>1080p</div><div>2 GB</div></div>
+                    <h3>Video One</h3>
+                </a>
+                <a href="/video-two/def456">
+                    <div><div>02:00:00</div><span class="format__text">4K</span></div>
+                    <h3>Video Two</h3>
+                </a>
+                <a href="/video-three/ghi789">
+                    <div><div>00:30:00</div><div>HD</div></div>
+                    <h3>Video Three 720p</h3>
+                </a>
+            </div>
+        </main>
+        </body>
+        </html>
+        "#;
+
+        let results = parse_search_results(html).unwrap();
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0].resolution, Some(1080));
+        assert_eq!(results[0].quality, Some("HD".to_string()));
+        assert_eq!(results[1].resolution, Some(2160));
+        // A resolution in the title is not a badge
+        assert_eq!(results[2].resolution, None);
+    }
+
+    #[test]
+    fn test_resolution_badge() {
+        assert_eq!(resolution_badge("1080p"), Some(1080));
+        assert_eq!(resolution_badge(" 720P "), Some(720));
+        assert_eq!(resolution_badge("4K"), Some(2160));
+        assert_eq!(resolution_badge("HD"), None);
+        assert_eq!(resolution_badge("Movie 1080p"), None);
+        assert_eq!(resolution_badge("99999p"), None);
+    }
+
+    #[test]
+    fn test_main_region_matches_full_document_parse() {
+        let full_document = |html: &str| {
+            let mut results = Vec::new();
+            parse_video_cards(&Html::parse_document(html), |video| results.push(video)).unwrap();
+            results
+        };
+        let pages = [
+            // Ad-heavy page with markup around and after <main>
+            r#"<html><head><script>var ads = "<div>";</script></head><body>
+                <header><a href="/header-link/zzz999"><h3>Not a result</h3></a></header>
+                <div class="ad"><iframe src="https://ads.example/1"></iframe></div>
+                <MAIN class="results">
+                    <div><div>
+                        <a href="/video-one/abc123"><div><div>01:00:00</div><div>500 MB</div></div><h3>Video One</h3></a>
+                        <a href="/video-two/def456"><h3>Video Two <span>Original</span></h3></a>
+                    </div></div>
+                </MAIN>
+                <aside><a href="/sidebar/yyy888"><h3>Sidebar</h3></a></aside>
+            </body></html>"#,
+            // Two <main> elements: the region spans both
+            r#"<main><a href="/video-one/abc123"><h3>Video One</h3></a></main>
+               <main><a href="/video-two/def456"><h3>Video Two</h3></a></main>"#,
+            // Unclosed <main> falls back to the full document parse
+            r#"<html><body><main><a href="/video-one/abc123"><h3>Video One</h3></a>"#,
+            // No <main> at all
+            r#"<html><body><a href="/video-one/abc123"><h3>Video One</h3></a></body></html>"#,
+            // Tags inside inline scripts are not the real <main>
+            r#"<html><head><script>var tpl = "<main><a href='/fake/zzz999'><h3>Fake</h3></a>";</script></head>
+            <body><main><a href="/video-one/abc123"><h3>Video One</h3></a></main>
+            <script>document.write("</main>");</script></body></html>"#,
+        ];
+
+        assert!(main_region(pages[0]).is_some_and(|main| main.starts_with("<MAIN")));
+        assert_eq!(main_region(pages[2]), None);
+        assert_eq!(main_region(pages[3]), None);
+        assert_eq!(
+            main_region(pages[4]),
+            Some(r#"<main><a href="/video-one/abc123"><h3>Video One</h3></a></main>"#)
+        );
+
+        for page in pages {
+            assert_eq!(parse_search_results(page).unwrap(), full_document(page));
+        }
+        assert_eq!(parse_search_results(pages[0]).unwrap().len(), 2);
+        assert_eq!(parse_search_results(pages[1]).unwrap().len(), 2);
+        assert_eq!(parse_search_results(pages[2]).unwrap().len(), 1);
+        assert!(parse_search_results(pages[3]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_search_results_canonical_url() {
+        let html = r#"<html><body><main>
+            <a href="https://www.prehraj.to/video-one/abc123?utm_source=feed"><h3>Video One</h3></a>
+            <a href="/video-two/def456?ref=home&next=/a/b#t=10"><h3>Video Two</h3></a>
+            <a href="http://prehraj.to/video-three/ghi789/?utm_medium=x"><h3>Video Three</h3></a>
+        </main></body></html>"#;
+
+        let results = parse_search_results(html).unwrap();
+        let urls: Vec<&str> = results.iter().map(|v| v.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://prehraj.to/video-one/abc123",
+                "https://prehraj.to/video-two/def456",
+                "https://prehraj.to/video-three/ghi789",
+            ]
+        );
+        assert_eq!(
+            results[0].download_url,
+            "https://prehraj.to/video-one/abc123?do=download"
+        );
+    }
+
+    #[test]
+    fn test_pathological_cards_do_not_derail_parse() {
+        let deep = "<div>".repeat(500) + &"</div>".repeat(500);
+        let html = format!(
+            r#"<html><body><main>
+            <a href="/video-one/abc123"><h3>Video One</h3></a>
+            <a href="/broken/"><h3>{deep}</h3></a>
+            <a href="//?#/"><h3>&#0;&#xFFFF;</h3><div>99:99:99:99</div><div>GB</div></a>
+            <a href="/{long}/{long}"><div>{deep}</div></a>
+            <a href="/huge/ghi789"><h3>Huge</h3><div>18446744073709551615:00</div></a>
+            <a href="/video-two/def456"><div>01:00:00</div><h3>Video Two</h3></a>
+            </main></body></html>"#,
+            deep = deep,
+            long = "x".repeat(10_000),
+        );
+
+        let results = parse_search_results(&html).unwrap();
+        let ids: Vec<&str> = results.iter().map(|v| v.video_id.as_str()).collect();
+        assert_eq!(ids, vec!["abc123", "ghi789", "def456"]);
+        assert_eq!(results[1].duration_secs, None);
+    }
+
+    #[test]
+    fn test_parse_search_fragment_html() {
+        let html = r#"
+            <a href="/video-three/ghi789"><div><div>00:45:00</div></div><h3>Video Three</h3></a>
+            <a href="/video-four/jkl012"><h3>Video Four</h3></a>
+        "#;
+
+        let results = parse_search_fragment(html).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].video_id, "ghi789");
+        assert_eq!(results[0].duration, Some("00:45:00".to_string()));
+        assert_eq!(results[1].position, 1);
+    }
+
+    #[test]
+    fn test_parse_search_fragment_snippet_json() {
+        let json = r#"{"state":[],"snippets":{"snippet-vp-items":"<a href=\"/video-five/mno345\"><h3>Video Five</h3></a>"}}"#;
+
+        let results = parse_search_fragment(json).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].video_slug, "video-five");
+    }
+
+    #[test]
+    fn test_parse_search_fragment_empty_and_invalid() {
+        assert!(parse_search_fragment("").unwrap().is_empty());
+        let empty = parse_search_fragment(r#"{"snippets":{}}"#).unwrap();
+        assert!(empty.is_empty());
+        assert!(parse_search_fragment("{not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_search_results_callback() {
+        let html = r#"
+        <html><body><main>
+            <a href="/video-one/abc123"><h3>Video One</h3></a>
+            <a href="/kategorie/filmy">Not a video</a>
+            <a href="/video-two/def456"><h3>Video Two</h3></a>
+        </main></body></html>
+        "#;
+
+        let mut seen = Vec::new();
+        let count = parse_search_results_callback(html, |video| seen.push(video.video_id)).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(seen, vec!["abc123", "def456"]);
+        assert_eq!(count, parse_search_results(html).unwrap().len());
+    }
+
+    #[test]
+    fn test_parse_search_results_positions() {
+        let html = r#"
+        <html><body><main>
+            <a href="/video-one/abc123"><h3>Video One</h3></a>
+            <a href="/kategorie/filmy">Not a video</a>
+            <a href="/video-two/def456"><h3>Video Two</h3></a>
+            <a href="/video-three/ghi789"><h3>Video Three</h3></a>
+        </main></body></html>
+        "#;
+
+        let results = parse_search_results(html).unwrap();
+        let positions: Vec<usize> = results.iter().map(|v| v.position).collect();
+        assert_eq!(positions, vec![0, 1, 2]);
+    }
+
     #[test]
     fn test_parse_video_without_optional_fields() {
         let html = r#"
@@ -295,6 +907,35 @@ mod tests {
         assert!(!is_file_size_format("00:44:20"));
     }
 
+    #[test]
+    fn test_parse_upload_date() {
+        let html = r#"
+        <html>
+        <body>
+        <main>
+            <a href="/dated-video/abc123">
+                <div><div>01:00:00</div><div>24.12.2023</div><div>500 MB</div></div>
+                <h3>Dated Video</h3>
+            </a>
+        </main>
+        </body>
+        </html>
+        "#;
+
+        let results = parse_search_results(html).unwrap();
+        assert_eq!(results[0].upload_date, Some("24.12.2023".to_string()));
+        assert_eq!(results[0].file_size, Some("500 MB".to_string()));
+    }
+
+    #[test]
+    fn test_is_date_format() {
+        assert!(is_date_format("24.12.2023"));
+        assert!(is_date_format("5.3.2024"));
+        assert!(!is_date_format("1.7 GB"));
+        assert!(!is_date_format("00:44:20"));
+        assert!(!is_date_format("24.12.23"));
+    }
+
     #[test]
     fn test_skip_links_without_video_structure() {
         let html = r#"
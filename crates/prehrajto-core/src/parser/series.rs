@@ -0,0 +1,149 @@
+//! Series page parser for prehraj.to
+//!
+//! Parses a series page into its title and episode list with aggregate info.
+
+use std::time::Duration;
+
+use scraper::{Html, Selector};
+
+use crate::error::{PrehrajtoError, Result};
+use crate::parser::search::parse_search_results;
+use crate::text::parse_duration;
+use crate::types::SeriesInfo;
+use crate::util::clean_title;
+
+/// Parses series page HTML and returns the series title and episodes
+///
+/// The title is taken from the page's `<h1>`, falling back to the
+/// `og:title` / `<title>` metadata with the site-name suffix stripped
+/// (see [`clean_title`]). Episodes are the video cards
+/// on the page, parsed the same way as search results.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the series page
+///
+/// # Returns
+/// [`SeriesInfo`] with `episode_count` and `total_duration` computed from
+/// the episodes. `total_duration` sums the durations that could be parsed
+/// and is `None` if none could.
+///
+/// # Errors
+/// Returns `ParseError` if a selector fails to compile
+pub fn parse_series_page(html: &str) -> Result<SeriesInfo> {
+    let document = Html::parse_document(html);
+    let title_selector = Selector::parse("h1")
+        .map_err(|e| PrehrajtoError::ParseError(format!("Invalid selector: {:?}", e)))?;
+
+    let title = document
+        .select(&title_selector)
+        .next()
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .filter(|t| !t.is_empty())
+        .or_else(|| extract_meta_title(&document))
+        .unwrap_or_default();
+
+    let episodes = parse_search_results(html)?;
+
+    let durations: Vec<Duration> = episodes
+        .iter()
+        .filter_map(|e| e.duration.as_deref().and_then(parse_duration))
+        .collect();
+    let total_duration = if durations.is_empty() {
+        None
+    } else {
+        Some(durations.iter().sum())
+    };
+
+    Ok(SeriesInfo {
+        title,
+        episode_count: episodes.len(),
+        episodes,
+        total_duration,
+    })
+}
+
+/// Extracts the page title from `og:title` or `<title>` metadata
+fn extract_meta_title(document: &Html) -> Option<String> {
+    let og_selector = Selector::parse(r#"meta[property="og:title"]"#).ok()?;
+    let title_selector = Selector::parse("title").ok()?;
+
+    let raw = document
+        .select(&og_selector)
+        .next()
+        .and_then(|el| el.value().attr("content").map(str::to_string))
+        .or_else(|| {
+            document
+                .select(&title_selector)
+                .next()
+                .map(|el| el.text().collect::<String>())
+        })?;
+
+    let title = clean_title(&raw);
+    if title.is_empty() { None } else { Some(title) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_series_page() {
+        let html = r#"
+        <html>
+        <body>
+        <main>
+            <h1>Doctor Who - 7. série</h1>
+            <div>
+                <a href="/doctor-who-s07e01/aaa111">
+                    <div><div>00:45:00</div><div>1.2 GB</div></div>
+                    <h3>Doctor Who S07E01</h3>
+                </a>
+                <a href="/doctor-who-s07e02/bbb222">
+                    <div><div>00:44:20</div></div>
+                    <h3>Doctor Who S07E02</h3>
+                </a>
+                <a href="/doctor-who-s07e03/ccc333">
+                    <h3>Doctor Who S07E03</h3>
+                </a>
+            </div>
+        </main>
+        </body>
+        </html>
+        "#;
+
+        let series = parse_series_page(html).unwrap();
+        assert_eq!(series.title, "Doctor Who - 7. série");
+        assert_eq!(series.episode_count, 3);
+        assert_eq!(series.episodes[1].video_id, "bbb222");
+        // 45:00 + 44:20, the third episode has no duration
+        assert_eq!(series.total_duration, Some(Duration::from_secs(5360)));
+    }
+
+    #[test]
+    fn test_parse_series_page_without_durations() {
+        let html = r#"
+        <html><body><main>
+            <h1>Empty Series</h1>
+            <a href="/episode/abc123"><h3>Episode</h3></a>
+        </main></body></html>
+        "#;
+
+        let series = parse_series_page(html).unwrap();
+        assert_eq!(series.episode_count, 1);
+        assert_eq!(series.total_duration, None);
+    }
+
+    #[test]
+    fn test_parse_series_page_title_from_metadata() {
+        let html = r#"
+        <html><head>
+            <title>Doctor Who - 7. série | Prehraj.to</title>
+        </head><body><main>
+            <a href="/episode/abc123"><h3>Episode</h3></a>
+        </main></body></html>
+        "#;
+
+        let series = parse_series_page(html).unwrap();
+        assert_eq!(series.title, "Doctor Who - 7. série");
+    }
+}
@@ -0,0 +1,115 @@
+//! Site recognition for prehraj.to pages
+//!
+//! Tells genuine prehraj.to pages apart from captive portals, ISP block
+//! pages or a misconfigured base URL before they reach the parsers.
+
+use scraper::{Html, Selector};
+
+/// Lowercased site name looked for in the page markers
+const SITE_NAME: &str = "prehraj.to";
+
+/// Checks whether HTML looks like it was served by prehraj.to
+///
+/// Looks for site-identifying markers rather than any mention of the name,
+/// since block pages often quote the blocked domain:
+/// - `og:site_name` naming the site
+/// - a `<title>` starting or ending with "Prehraj.to" (the site's own format,
+///   e.g. "Doctor Who | Prehraj.to")
+/// - a logo image whose `alt` text names the site
+/// - a canonical link whose host is `prehraj.to` or `www.prehraj.to`
+///
+/// # Arguments
+/// * `html` - Raw HTML of a full page
+///
+/// # Returns
+/// `true` if at least one marker is present
+pub fn is_prehrajto_page(html: &str) -> bool {
+    let document = Html::parse_document(html);
+    let select = |css: &str| Selector::parse(css).ok();
+
+    if let Some(selector) = select("meta[property='og:site_name']")
+        && document.select(&selector).any(|meta| {
+            meta.value()
+                .attr("content")
+                .is_some_and(|content| content.to_lowercase().contains(SITE_NAME))
+        })
+    {
+        return true;
+    }
+
+    if let Some(selector) = select("title")
+        && document.select(&selector).any(|title| {
+            let text = title.text().collect::<String>().trim().to_lowercase();
+            text.starts_with(SITE_NAME) || text.ends_with(SITE_NAME)
+        })
+    {
+        return true;
+    }
+
+    if let Some(selector) = select("img[alt]")
+        && document.select(&selector).any(|img| {
+            img.value()
+                .attr("alt")
+                .is_some_and(|alt| alt.trim().to_lowercase().starts_with(SITE_NAME))
+        })
+    {
+        return true;
+    }
+
+    if let Some(selector) = select("link[rel='canonical'][href]")
+        && document.select(&selector).any(|link| {
+            link.value()
+                .attr("href")
+                .and_then(|href| reqwest::Url::parse(href.trim()).ok())
+                .is_some_and(|url| {
+                    matches!(url.scheme(), "http" | "https")
+                        && matches!(url.host_str(), Some("prehraj.to" | "www.prehraj.to"))
+                })
+        })
+    {
+        return true;
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_prehrajto_page_markers() {
+        assert!(is_prehrajto_page(
+            r#"<html><head><title>Doctor Who | Prehraj.to</title></head></html>"#
+        ));
+        assert!(is_prehrajto_page(
+            r#"<html><head><meta property="og:site_name" content="Prehraj.to"></head></html>"#
+        ));
+        assert!(is_prehrajto_page(
+            r#"<html><body><a href="/"><img src="/logo.svg" alt="Prehraj.to"></a></body></html>"#
+        ));
+        assert!(is_prehrajto_page(
+            r#"<html><head><link rel="canonical" href="https://prehraj.to/hledej/x"></head></html>"#
+        ));
+        assert!(is_prehrajto_page(
+            r#"<html><head><link rel="canonical" href="https://www.prehraj.to/hledej/x"></head></html>"#
+        ));
+    }
+
+    #[test]
+    fn test_is_prehrajto_page_unrelated() {
+        assert!(!is_prehrajto_page(
+            r#"<html><head><title>Hotel Wi-Fi Login</title></head><body><form></form></body></html>"#
+        ));
+        // Block pages quoting the domain aren't the site itself
+        assert!(!is_prehrajto_page(
+            r#"<html><head><title>Access denied</title></head>
+            <body><p>The site prehraj.to is blocked by your provider.</p></body></html>"#
+        ));
+        // Only the host counts, not a lookalike prefix
+        assert!(!is_prehrajto_page(
+            r#"<html><head><link rel="canonical" href="https://prehraj.to.example.com/x"></head></html>"#
+        ));
+        assert!(!is_prehrajto_page(""));
+    }
+}
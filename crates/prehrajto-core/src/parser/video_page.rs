@@ -0,0 +1,574 @@
+//! Video page metadata parser for prehraj.to
+//!
+//! Extracts the metadata stated on a video's own page and builds a
+//! [`VideoResult`] from it, for when the video is known by URL rather than
+//! found through search.
+
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use scraper::{Html, Node, Selector};
+use serde::Deserialize;
+
+use crate::error::{PrehrajtoError, Result};
+use crate::text::{parse_duration_to_secs, parse_size_to_bytes};
+use crate::types::{VideoMetadata, VideoResult};
+use crate::url::{build_download_url, build_video_url, parse_video_link, site_url};
+use crate::util::clean_title;
+
+/// Explicit resolution such as `1080p`
+static EXPLICIT_RESOLUTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(\d{3,4})p\b").expect("valid regex"));
+
+/// 4K / UHD wording
+static UHD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(4k|uhd|ultra hd)\b").expect("valid regex"));
+
+/// Full HD wording
+static FULL_HD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(full ?hd|fhd)\b").expect("valid regex"));
+
+/// Availability window such as "Dostupné do 31.12.2025"
+static AVAILABLE_UNTIL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:dostupn[ée]|k dispozici)\s+do:?\s*(\d{1,2})\.\s*(\d{1,2})\.\s*(\d{4})")
+        .expect("valid regex")
+});
+
+/// Parses the metadata stated on a video page
+///
+/// The title is taken from `og:title`, then `<h1>`, then `<title>`, with the
+/// site-name suffix stripped (see [`clean_title`]). The duration is read
+/// from the `video:duration` meta tag (seconds) if present. The reported
+/// resolution comes from [`parse_reported_quality`] and the availability
+/// date from [`parse_availability`]. The uploader comes from the first link
+/// to a `/profil/` page: its text is the name (falling back to the profile
+/// path segment) and its `href` the profile URL.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the video page
+///
+/// # Returns
+/// [`VideoMetadata`] with `None` for anything not found
+pub fn parse_video_metadata(html: &str) -> VideoMetadata {
+    metadata_in(&Html::parse_document(html))
+}
+
+/// Collects the [`VideoMetadata`] of a parsed video page
+fn metadata_in(document: &Html) -> VideoMetadata {
+    let title = extract_meta_content(document, "og:title")
+        .or_else(|| select_text(document, "h1"))
+        .or_else(|| select_text(document, "title"))
+        .map(|title| clean_title(&title))
+        .filter(|title| !title.is_empty());
+
+    let duration = extract_meta_content(document, "video:duration")
+        .and_then(|secs| secs.trim().parse::<u64>().ok())
+        .map(format_duration);
+
+    let (uploader, uploader_url) = uploader_in(document).unzip();
+
+    VideoMetadata {
+        title,
+        duration,
+        reported_resolution: reported_quality_in(document),
+        available_until: availability_in(document),
+        uploader,
+        uploader_url,
+    }
+}
+
+/// Finds the uploader's name and full profile URL
+fn uploader_in(document: &Html) -> Option<(String, String)> {
+    let selector = Selector::parse(r#"a[href*="/profil/"]"#).ok()?;
+    let link = document.select(&selector).next()?;
+    let href = link.value().attr("href")?;
+
+    let text = link.text().collect::<Vec<_>>().join(" ");
+    let name = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = if name.is_empty() {
+        let (_, profile) = href.split_once("/profil/")?;
+        let segment = profile.split(['/', '?', '#']).next()?;
+        urlencoding::decode(segment).ok()?.into_owned()
+    } else {
+        name
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((name, site_url(href)))
+}
+
+/// Parses the date until which the video stays available
+///
+/// Some uploads state an availability window in their visible text, e.g.
+/// "Dostupné do 31.12.2025" (Czech `D.M.YYYY` dates, spaces after the dots
+/// allowed).
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the video page
+///
+/// # Returns
+/// The last day the video is available, or `None` if the page doesn't say
+pub fn parse_availability(html: &str) -> Option<NaiveDate> {
+    availability_in(&Html::parse_document(html))
+}
+
+/// Parses the resolution the video page states in its text
+///
+/// Looks at the visible text (scripts and styles are skipped, so player
+/// configs don't count) for explicit resolutions like "1080p", falling back
+/// to "4K"/"UHD" (2160) and "Full HD"/"FHD" (1080). When several are
+/// mentioned, the highest is returned.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the video page
+///
+/// # Returns
+/// The stated resolution height, or `None` if the page doesn't state one
+pub fn parse_reported_quality(html: &str) -> Option<u32> {
+    reported_quality_in(&Html::parse_document(html))
+}
+
+/// Parses video page HTML into a [`VideoResult`]
+///
+/// Built from [`parse_video_metadata`], with gaps filled from the page's
+/// JSON-LD `VideoObject` if present (`name`, ISO 8601 `duration` and
+/// `contentSize`). Fields that only appear on search cards (quality, upload
+/// date) are `None`.
+///
+/// # Arguments
+/// * `html` - Raw HTML string from the video page
+/// * `video_slug` - URL slug of the video
+/// * `video_id` - ID of the video
+///
+/// # Returns
+/// `VideoResult` for the page
+///
+/// # Errors
+/// Returns `ParseError` if no title can be found on the page
+pub fn parse_video_page_result(
+    html: &str,
+    video_slug: &str,
+    video_id: &str,
+) -> Result<VideoResult> {
+    let document = Html::parse_document(html);
+    let metadata = metadata_in(&document);
+    let json_ld = json_ld_video_in(&document).unwrap_or_default();
+
+    let name = metadata
+        .title
+        .or_else(|| json_ld.name.map(|name| clean_title(&name)))
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| PrehrajtoError::ParseError("Could not find video title".to_string()))?;
+    let file_size = json_ld.content_size.filter(|size| !size.trim().is_empty());
+    let duration = metadata.duration.or_else(|| {
+        json_ld
+            .duration
+            .as_deref()
+            .and_then(parse_iso_duration)
+            .map(format_duration)
+    });
+
+    Ok(VideoResult {
+        name,
+        original_title: None,
+        url: build_video_url(video_slug, video_id),
+        video_id: video_id.to_string(),
+        video_slug: video_slug.to_string(),
+        download_url: build_download_url(video_slug, video_id),
+        duration_secs: duration.as_deref().and_then(parse_duration_to_secs),
+        duration,
+        quality: None,
+        resolution: reported_quality_in(&document),
+        file_size_bytes: file_size.as_deref().and_then(parse_size_to_bytes),
+        file_size,
+        upload_date: None,
+        position: 0,
+    })
+}
+
+/// Parses a video page that a search redirected to
+///
+/// A search matching exactly one video may be answered with that video's
+/// page instead of a results list. A page counts as a video page when it
+/// declares itself one (`og:type` of `video.*` or a JSON-LD `VideoObject`);
+/// its slug and ID come from `<link rel="canonical">` or `og:url`.
+///
+/// # Arguments
+/// * `html` - Raw HTML string of the fetched page
+///
+/// # Returns
+/// `Some(VideoResult)` built by [`parse_video_page_result`] for a video
+/// page, `None` for any other page
+pub(crate) fn parse_redirected_video_page(html: &str) -> Option<VideoResult> {
+    let document = Html::parse_document(html);
+    let declares_video = extract_meta_content(&document, "og:type")
+        .is_some_and(|kind| kind.starts_with("video"))
+        || json_ld_video_in(&document).is_some();
+    if !declares_video {
+        return None;
+    }
+
+    let canonical = Selector::parse(r#"link[rel="canonical"][href]"#)
+        .ok()
+        .and_then(|selector| {
+            document
+                .select(&selector)
+                .next()
+                .and_then(|el| el.value().attr("href"))
+                .map(str::to_string)
+        });
+    let (video_slug, video_id) = canonical
+        .into_iter()
+        .chain(extract_meta_content(&document, "og:url"))
+        .find_map(|url| parse_video_link(&url))
+        .filter(|(slug, _)| slug != "hledej")?;
+
+    parse_video_page_result(html, &video_slug, &video_id).ok()
+}
+
+/// Finds the highest resolution stated in the document's visible text
+fn reported_quality_in(document: &Html) -> Option<u32> {
+    let text = visible_text(document);
+
+    let stated = EXPLICIT_RESOLUTION_RE
+        .captures_iter(&text)
+        .filter_map(|caps| caps.get(1)?.as_str().parse::<u32>().ok())
+        .filter(|&r| (144..=4320).contains(&r))
+        .max();
+    if stated.is_some() {
+        return stated;
+    }
+
+    if UHD_RE.is_match(&text) {
+        return Some(2160);
+    }
+    if FULL_HD_RE.is_match(&text) {
+        return Some(1080);
+    }
+
+    None
+}
+
+/// Finds the availability date stated in the document's visible text
+fn availability_in(document: &Html) -> Option<NaiveDate> {
+    let text = visible_text(document);
+    let caps = AVAILABLE_UNTIL_RE.captures(&text)?;
+    let part = |i: usize| caps.get(i)?.as_str().parse::<u32>().ok();
+    NaiveDate::from_ymd_opt(part(3)? as i32, part(2)?, part(1)?)
+}
+
+/// Collects the document's text outside `<script>`, `<style>` and `<head>`
+fn visible_text(document: &Html) -> String {
+    let mut text = String::new();
+    for node in document.tree.nodes() {
+        let Node::Text(chunk) = node.value() else {
+            continue;
+        };
+        let hidden = node.ancestors().any(|parent| {
+            parent
+                .value()
+                .as_element()
+                .is_some_and(|el| matches!(el.name(), "script" | "style" | "head"))
+        });
+        if !hidden {
+            text.push_str(chunk);
+            text.push(' ');
+        }
+    }
+    text
+}
+
+/// Reads the `content` of a `<meta property="...">` tag
+fn extract_meta_content(document: &Html, property: &str) -> Option<String> {
+    let selector = Selector::parse(&format!(r#"meta[property="{}"]"#, property)).ok()?;
+    document
+        .select(&selector)
+        .next()
+        .and_then(|el| el.value().attr("content"))
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+/// Fields of a JSON-LD `VideoObject` used to fill in a [`VideoResult`]
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonLdVideo {
+    #[serde(rename = "@type", default)]
+    kind: Option<String>,
+    name: Option<String>,
+    duration: Option<String>,
+    content_size: Option<String>,
+}
+
+/// Finds the first JSON-LD `VideoObject` on the page
+///
+/// Blocks may hold a single object or an array of them; blocks that aren't
+/// valid JSON are skipped.
+fn json_ld_video_in(document: &Html) -> Option<JsonLdVideo> {
+    let selector = Selector::parse(r#"script[type="application/ld+json"]"#).ok()?;
+    document.select(&selector).find_map(|script| {
+        let json = script.text().collect::<String>();
+        let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+        let candidates = match value {
+            serde_json::Value::Array(items) => items,
+            other => vec![other],
+        };
+        candidates
+            .into_iter()
+            .filter_map(|item| serde_json::from_value::<JsonLdVideo>(item).ok())
+            .find(|video| video.kind.as_deref() == Some("VideoObject"))
+    })
+}
+
+/// Parses an ISO 8601 duration such as `PT1H2M3S` into seconds
+///
+/// Only the hour, minute and second parts are supported, which is what video
+/// durations use.
+fn parse_iso_duration(value: &str) -> Option<u64> {
+    let rest = value.trim().strip_prefix("PT")?;
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'H' | 'M' | 'S' => {
+                let amount: u64 = number.parse().ok()?;
+                let unit = match c {
+                    'H' => 3600,
+                    'M' => 60,
+                    _ => 1,
+                };
+                total = amount.checked_mul(unit)?.checked_add(total)?;
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+    number.is_empty().then_some(total)
+}
+
+/// Returns the trimmed text of the first element matching the selector
+fn select_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .next()
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+/// Formats seconds as "HH:MM:SS", matching the search card format
+fn format_duration(total_secs: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        total_secs / 3600,
+        (total_secs % 3600) / 60,
+        total_secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_redirected_video_page() {
+        let video = r#"<html><head>
+            <meta property="og:type" content="video.other">
+            <meta property="og:url" content="https://prehraj.to/matrix/def456">
+            <title>Matrix | Prehraj.to</title>
+        </head></html>"#;
+        let result = parse_redirected_video_page(video).unwrap();
+        assert_eq!(result.name, "Matrix");
+        assert_eq!(result.video_id, "def456");
+
+        // Search pages (no video type, or linking to /hledej/) are not video pages
+        let search = r#"<html><head>
+            <link rel="canonical" href="https://prehraj.to/hledej/matrix">
+            <title>Matrix | Prehraj.to</title>
+        </head><body><main></main></body></html>"#;
+        assert_eq!(parse_redirected_video_page(search), None);
+        let typed_search = search.replace(
+            "<title>",
+            r#"<meta property="og:type" content="video"><title>"#,
+        );
+        assert_eq!(parse_redirected_video_page(&typed_search), None);
+    }
+
+    #[test]
+    fn test_parse_availability_czech_date() {
+        let html = r#"<html><body>
+            <h1>Doctor Who</h1>
+            <p class="video__availability">Dostupné do 31. 12. 2025</p>
+        </body></html>"#;
+
+        assert_eq!(
+            parse_availability(html),
+            NaiveDate::from_ymd_opt(2025, 12, 31)
+        );
+        assert_eq!(
+            parse_video_metadata(html).available_until,
+            NaiveDate::from_ymd_opt(2025, 12, 31)
+        );
+        assert_eq!(
+            parse_availability("<p>dostupne do: 1.2.2026</p>"),
+            NaiveDate::from_ymd_opt(2026, 2, 1)
+        );
+    }
+
+    #[test]
+    fn test_parse_availability_absent_or_invalid() {
+        let html = "<html><body><h1>Doctor Who</h1><p>Nahráno 24.12.2023</p></body></html>";
+        assert_eq!(parse_availability(html), None);
+        assert_eq!(parse_video_metadata(html).available_until, None);
+        assert_eq!(parse_availability("<p>Dostupné do 31.2.2025</p>"), None);
+    }
+
+    #[test]
+    fn test_parse_video_page_result_from_og_title() {
+        let html = r#"
+        <html><head>
+            <title>Ignored | Prehraj.to</title>
+            <meta property="og:title" content="Doctor Who S07E05 | Prehraj.to">
+            <meta property="video:duration" content="2660">
+        </head><body><h1>Doctor Who</h1></body></html>
+        "#;
+
+        let video = parse_video_page_result(html, "doctor-who-s07e05", "63aba7f51f6cf").unwrap();
+        assert_eq!(video.name, "Doctor Who S07E05");
+        assert_eq!(video.duration, Some("00:44:20".to_string()));
+        assert_eq!(
+            video.url,
+            "https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf"
+        );
+        assert_eq!(
+            video.download_url,
+            "https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf?do=download"
+        );
+    }
+
+    #[test]
+    fn test_parse_video_page_result_title_fallbacks() {
+        let html = "<html><body><h1> Andělé </h1></body></html>";
+        let video = parse_video_page_result(html, "andele", "abc123").unwrap();
+        assert_eq!(video.name, "Andělé");
+        assert_eq!(video.duration, None);
+
+        let html = "<html><head><title>Andělé - Prehraj.to</title></head></html>";
+        let video = parse_video_page_result(html, "andele", "abc123").unwrap();
+        assert_eq!(video.name, "Andělé");
+    }
+
+    #[test]
+    fn test_parse_reported_quality_explicit() {
+        let html = r#"
+        <html><body>
+            <div class="video-info"><span>Kvalita:</span> <strong>Full HD 1080p</strong></div>
+            <script>videos.push({ src: "https://cdn/2160p.mp4", res: '2160', label: '2160p' });</script>
+        </body></html>
+        "#;
+        assert_eq!(parse_reported_quality(html), Some(1080));
+    }
+
+    #[test]
+    fn test_parse_reported_quality_keywords() {
+        let html = "<html><body><p>Dostupné ve 4K</p></body></html>";
+        assert_eq!(parse_reported_quality(html), Some(2160));
+
+        let html = "<html><body><p>FullHD verze</p></body></html>";
+        assert_eq!(parse_reported_quality(html), Some(1080));
+    }
+
+    #[test]
+    fn test_parse_reported_quality_none() {
+        let html = r#"
+        <html><head><title>Video 720p | Prehraj.to</title></head>
+        <body><h1>Doctor Who</h1><p>Popis videa</p></body></html>
+        "#;
+        assert_eq!(parse_reported_quality(html), None);
+    }
+
+    #[test]
+    fn test_parse_video_metadata() {
+        let html = r#"
+        <html><head>
+            <meta property="og:title" content="Doctor Who | Prehraj.to">
+            <meta property="video:duration" content="3600">
+        </head><body><span>720p</span></body></html>
+        "#;
+
+        let metadata = parse_video_metadata(html);
+        assert_eq!(metadata.title, Some("Doctor Who".to_string()));
+        assert_eq!(metadata.duration, Some("01:00:00".to_string()));
+        assert_eq!(metadata.reported_resolution, Some(720));
+    }
+
+    #[test]
+    fn test_parse_video_metadata_uploader() {
+        let html = r#"<html><body>
+            <h1>Doctor Who</h1>
+            <div class="video__uploader">
+                Nahrál: <a href="/profil/whovian/4821"> Whovian  CZ </a>
+            </div>
+            <a href="/profil/someone-else/1">Someone else</a>
+        </body></html>"#;
+        let metadata = parse_video_metadata(html);
+        assert_eq!(metadata.uploader.as_deref(), Some("Whovian CZ"));
+        assert_eq!(
+            metadata.uploader_url.as_deref(),
+            Some("https://prehraj.to/profil/whovian/4821")
+        );
+
+        // An avatar-only link falls back to the profile path segment
+        let html = r#"<a href="https://prehraj.to/profil/whovian/4821"><img src="a.png"></a>"#;
+        let metadata = parse_video_metadata(html);
+        assert_eq!(metadata.uploader.as_deref(), Some("whovian"));
+
+        let metadata = parse_video_metadata("<html><h1>Doctor Who</h1></html>");
+        assert_eq!(metadata.uploader, None);
+        assert_eq!(metadata.uploader_url, None);
+    }
+
+    #[test]
+    fn test_parse_video_page_result_json_ld() {
+        let html = r#"
+        <html><head>
+            <meta property="og:title" content="Doctor Who S07E05 | Prehraj.to">
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@type": "VideoObject",
+             "name": "Doctor Who S07E05", "duration": "PT44M20S", "contentSize": "1.7 GB"}
+            </script>
+        </head></html>
+        "#;
+
+        let result = parse_video_page_result(html, "doctor-who-s07e05", "63aba7f51f6cf").unwrap();
+        assert_eq!(result.name, "Doctor Who S07E05");
+        assert_eq!(result.duration, Some("00:44:20".to_string()));
+        assert_eq!(result.duration_secs, Some(2660));
+        assert_eq!(result.file_size, Some("1.7 GB".to_string()));
+        assert_eq!(result.file_size_bytes, Some(1_825_361_101));
+        assert_eq!(
+            result.url,
+            "https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf"
+        );
+    }
+
+    #[test]
+    fn test_parse_iso_duration() {
+        assert_eq!(parse_iso_duration("PT1H2M3S"), Some(3723));
+        assert_eq!(parse_iso_duration("PT45M"), Some(2700));
+        assert_eq!(parse_iso_duration("P1D"), None);
+        assert_eq!(parse_iso_duration("PT12"), None);
+        assert_eq!(parse_iso_duration("PT99999999999999999H"), None);
+        assert_eq!(parse_iso_duration("PT18446744073709551615S1S"), None);
+    }
+
+    #[test]
+    fn test_parse_video_page_result_without_title() {
+        let result = parse_video_page_result("<html></html>", "x", "y");
+        assert!(matches!(result, Err(PrehrajtoError::ParseError(_))));
+    }
+}
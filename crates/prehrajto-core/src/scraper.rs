@@ -2,14 +2,36 @@
 //!
 //! Provides the high-level API combining HTTP client and parsers.
 
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::stream::{self, BoxStream, StreamExt};
+
 use crate::client::{ClientConfig, PrehrajtoClient};
 use crate::error::{PrehrajtoError, Result};
+use crate::parser::direct_url::{
+    STATIC_CDN_HOSTS, best_source_with, parse_direct_url_with_hosts,
+    parse_download_options_with_hosts, parse_video_sources_with_hosts,
+};
+use crate::parser::video_page::parse_redirected_video_page;
+use crate::parser::{
+    is_prehrajto_page, parse_cdn_hosts, parse_player_fingerprint, parse_subtitle_tracks,
+};
 use crate::parser::{
-    parse_direct_url, parse_original_download_url, parse_subtitle_tracks, parse_video_sources,
+    parse_homepage, parse_search_fragment, parse_search_results, parse_search_results_callback,
+    parse_series_page, parse_video_metadata, parse_video_page_result,
+};
+use crate::types::{
+    BatchOptions, DownloadOptions, FullVideoInfo, HomepageSections, ResolveOptions, ResolvedVideo,
+    ScoringConfig, SearchFilter, SearchOptions, SearchResponse, SearchSort, SeriesInfo, SourceKind,
+    SubtitleTrack, VideoBookmark, VideoMetadata, VideoPageData, VideoResult, VideoSource,
 };
-use crate::parser::parse_search_results;
-use crate::types::{SubtitleTrack, VideoPageData, VideoResult, VideoSource};
-use crate::url::{build_download_url, build_search_url};
+use crate::url::{
+    build_download_url, build_search_url_paged, build_search_url_with_options, cdn_url_expires,
+    normalize_search_query, parse_video_link, site_path, strip_cdn_tracking,
+};
+use crate::util::{match_score, with_deadline};
 
 /// Main scraper API for prehraj.to
 ///
@@ -18,6 +40,7 @@ use crate::url::{build_download_url, build_search_url};
 /// getting download URLs.
 pub struct PrehrajtoScraper {
     client: PrehrajtoClient,
+    config: ClientConfig,
 }
 
 impl PrehrajtoScraper {
@@ -29,8 +52,7 @@ impl PrehrajtoScraper {
     /// # Errors
     /// Returns error if HTTP client initialization fails
     pub fn new() -> Result<Self> {
-        let client = PrehrajtoClient::new()?;
-        Ok(Self { client })
+        Self::with_config(ClientConfig::default())
     }
 
     /// Create a new scraper with custom client configuration
@@ -44,8 +66,34 @@ impl PrehrajtoScraper {
     /// # Errors
     /// Returns error if HTTP client initialization fails
     pub fn with_config(config: ClientConfig) -> Result<Self> {
-        let client = PrehrajtoClient::with_config(config)?;
-        Ok(Self { client })
+        let client = PrehrajtoClient::with_config(config.clone())?;
+        Ok(Self { client, config })
+    }
+
+    /// Change the request rate at runtime
+    ///
+    /// Reconfigures the rate limiter in place; cookies and connections are
+    /// kept. See [`crate::RateLimiter::set_rate`].
+    ///
+    /// # Arguments
+    /// * `requests_per_second` - New maximum number of requests per second
+    pub fn set_rate(&self, requests_per_second: f64) {
+        self.client.set_rate(requests_per_second);
+    }
+
+    /// List the cookies currently held for the site
+    ///
+    /// Diagnostics aid for the original-download flow; see
+    /// [`PrehrajtoClient::debug_cookies`].
+    pub fn debug_cookies(&self) -> Vec<(String, String)> {
+        self.client.debug_cookies()
+    }
+
+    /// Get the base URL the scraper is configured with
+    ///
+    /// Useful for logging and building links; never has a trailing slash.
+    pub fn base_url(&self) -> &str {
+        self.client.base_url()
     }
 
     /// Search for videos by query
@@ -61,121 +109,239 @@ impl PrehrajtoScraper {
     /// - `HttpError` if network request fails
     /// - `ParseError` if HTML parsing fails
     pub async fn search(&self, query: &str) -> Result<Vec<VideoResult>> {
-        let trimmed = query.trim();
-        if trimmed.is_empty() {
-            return Err(PrehrajtoError::InvalidId(
-                "Search query cannot be empty".to_string(),
-            ));
-        }
+        self.search_with_options(query, &SearchOptions::default()).await
+    }
 
-        let search_url = build_search_url(trimmed);
-        let path = search_url
-            .strip_prefix("https://prehraj.to")
-            .unwrap_or(&search_url);
+    /// Search for videos, also returning the raw page (diagnostics)
+    ///
+    /// Meant for bug reports such as "search returns nothing": the HTML the
+    /// results were parsed from can be attached as-is. Not for normal use;
+    /// the page isn't refetched if it looks truncated, so what was parsed
+    /// is exactly what is returned.
+    ///
+    /// # Arguments
+    /// * `query` - Search query string
+    ///
+    /// # Returns
+    /// The parsed results (as [`Self::search`] would return them) and the
+    /// raw HTML of the search page
+    ///
+    /// # Errors
+    /// Same as [`Self::search`]
+    pub async fn search_debug(&self, query: &str) -> Result<(Vec<VideoResult>, String)> {
+        let path = search_path(query, &SearchOptions::default())?;
+        let html = self.fetch_page(&path).await?;
+        let results = parse_search_page(&html).map_err(|e| e.with_context(&path))?;
+        Ok((results, html))
+    }
 
-        let html = self.client.fetch(path).await?;
-        parse_search_results(&html)
+    /// Search for videos on a given results page
+    ///
+    /// Page 1 is the same request as [`Self::search`]; later pages add the
+    /// site's page parameter (see [`crate::url::build_search_url_paged`]).
+    ///
+    /// # Arguments
+    /// * `query` - Search query string
+    /// * `page` - Results page, 1-based; 0 is treated as 1
+    ///
+    /// # Returns
+    /// Vector of video results on that page, empty if the page is past the
+    /// last one
+    ///
+    /// # Errors
+    /// Same as [`Self::search_with`], except that a missing page after the
+    /// first gives an empty Vec instead of `NotFound`
+    pub async fn search_page(&self, query: &str, page: u32) -> Result<Vec<VideoResult>> {
+        let options = SearchOptions::default().page(page);
+        match self.search_with_options(query, &options).await {
+            Err(PrehrajtoError::NotFound(_)) if page > 1 => Ok(Vec::new()),
+            other => other,
+        }
     }
 
-    /// Get download URL for a video
+    /// Search for videos across the regular result pages
+    ///
+    /// Fetches pages one by one with [`Self::search_page`], each spaced by
+    /// the rate limiter. Stops at an empty page, at `max_pages`, or at a page
+    /// bringing no new videos (the site may keep answering with its last
+    /// page), so it never loops forever. Results are de-duplicated by
+    /// `video_id`, keeping the first occurrence. Unlike
+    /// [`Self::search_all_pages`], no XHR endpoint is involved.
     ///
     /// # Arguments
-    /// * `video_slug` - URL-friendly video slug
-    /// * `video_id` - Unique video ID
+    /// * `query` - Search query string
+    /// * `max_pages` - Maximum number of pages to fetch, `None` for no limit
     ///
     /// # Returns
-    /// Download URL with `?do=download` parameter
+    /// All collected video results in page order
     ///
     /// # Errors
-    /// - `InvalidId` if video_id is empty
-    pub fn get_download_url(&self, video_slug: &str, video_id: &str) -> Result<String> {
-        if video_id.trim().is_empty() {
-            return Err(PrehrajtoError::InvalidId(
-                "Video ID cannot be empty".to_string(),
-            ));
+    /// Same as [`Self::search_page`]; errors on later pages are returned too
+    pub async fn search_all(
+        &self,
+        query: &str,
+        max_pages: Option<u32>,
+    ) -> Result<Vec<VideoResult>> {
+        let mut seen = HashSet::new();
+        let mut results = Vec::new();
+
+        let mut page = 1;
+        while max_pages.is_none_or(|max| page <= max) {
+            let new: Vec<VideoResult> = self
+                .search_page(query, page)
+                .await?
+                .into_iter()
+                .filter(|v| seen.insert(v.unique_key().to_string()))
+                .collect();
+            if new.is_empty() {
+                break;
+            }
+            results.extend(new);
+            page += 1;
         }
 
-        Ok(build_download_url(video_slug, video_id))
+        Ok(results)
     }
 
-    /// Get direct CDN URL for a video file (best quality)
+    /// Search for videos by query with custom search options
     ///
-    /// Fetches the video page and extracts the highest quality CDN URL
-    /// from the player initialization blocks.
+    /// Shortcut for [`Self::search_with`] returning only the results; the
+    /// `query` argument replaces `options.query`.
     ///
     /// # Arguments
-    /// * `video_slug` - URL slug of the video
-    /// * `video_id` - ID of the video
+    /// * `query` - Search query string
+    /// * `options` - Options controlling the search (see [`SearchOptions`])
     ///
     /// # Returns
-    /// Direct URL to CDN (premiumcdn.net) — highest resolution available
+    /// Vector of matching video results, empty if no results found
     ///
     /// # Errors
-    /// - `InvalidId` if video_id is empty
-    /// - `NotFound` if CDN URL cannot be found in the response
-    /// - `HttpError` for network errors
+    /// Same as [`Self::search_with`]
+    pub async fn search_with_options(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<VideoResult>> {
+        let options = SearchOptions {
+            query: query.to_string(),
+            ..options.clone()
+        };
+        Ok(self.search_with(options).await?.results)
+    }
+
+    /// Search for videos with full control over page, sort and filters
     ///
-    /// # Note
-    /// The returned URL has an expiration time (expires parameter),
-    /// so it cannot be cached long-term.
-    pub async fn get_direct_url(&self, video_slug: &str, video_id: &str) -> Result<String> {
-        if video_id.trim().is_empty() {
-            return Err(PrehrajtoError::InvalidId(
-                "Video ID cannot be empty".to_string(),
-            ));
-        }
+    /// Fetches the requested results page with the given ordering, then
+    /// applies `options.filter` client-side. If the site redirects a query
+    /// matching a single video straight to that video's page, the response
+    /// holds that one video.
+    ///
+    /// # Arguments
+    /// * `options` - Query and settings for the search (see [`SearchOptions`])
+    ///
+    /// # Returns
+    /// [`SearchResponse`] with the filtered results and the page they came from
+    ///
+    /// # Errors
+    /// - `InvalidId` if query is empty or whitespace only
+    /// - `HttpError` if network request fails
+    /// - `ParseError` if HTML parsing fails
+    pub async fn search_with(&self, options: SearchOptions) -> Result<SearchResponse> {
+        let path = search_path(&options.query, &options)?;
+        let parsed = self
+            .fetch_and_parse(&path, parse_search_page, Vec::is_empty)
+            .await?;
+        let total_parsed = parsed.len();
+        let results = parsed
+            .into_iter()
+            .filter(|v| options.filter.matches(v))
+            .collect();
 
-        // Fetch the video page (NOT ?do=download) to get player sources
-        let path = format!("/{}/{}", video_slug, video_id);
-        let html = self.client.fetch(&path).await?;
+        Ok(SearchResponse {
+            query: normalize_search_query(options.query.trim()),
+            results,
+            page: options.page.unwrap_or(1).max(1),
+            total_parsed,
+        })
+    }
 
-        parse_direct_url(&html)
+    /// Search for videos, delivering each result to a callback as it's parsed
+    ///
+    /// Fetches the first results page, then invokes `on_result` per video
+    /// card as the page is parsed (see [`parse_search_results_callback`]),
+    /// so a UI can show results before the whole page is processed.
+    ///
+    /// # Arguments
+    /// * `query` - Search query string
+    /// * `on_result` - Called with each parsed `VideoResult`, in page order
+    ///
+    /// # Returns
+    /// Number of results delivered
+    ///
+    /// # Errors
+    /// Same as [`Self::search`]
+    pub async fn search_each(
+        &self,
+        query: &str,
+        on_result: impl FnMut(VideoResult),
+    ) -> Result<usize> {
+        let path = search_path(query, &SearchOptions::default())?;
+        let html = self.fetch_page(&path).await?;
+        parse_search_results_callback(&html, on_result).map_err(|e| e.with_context(&path))
     }
 
-    /// Get all streaming quality variants for a video
+    /// Resolve user input that may be a video link into a single video
     ///
-    /// Fetches the video page and parses JS player sources to extract
-    /// all available quality variants (e.g., 720p, 1080p).
+    /// If the input is a prehraj.to video URL or path (see
+    /// [`parse_video_link`]), the video page is fetched and turned into a
+    /// [`VideoResult`] directly instead of running a text search. Any other
+    /// input is searched and the first result returned.
     ///
     /// # Arguments
-    /// * `video_slug` - URL slug of the video
-    /// * `video_id` - ID of the video
+    /// * `url_or_query` - Pasted video URL/path or a plain search query
     ///
     /// # Returns
-    /// Vector of [`VideoSource`] with all available qualities
+    /// The linked video, or the top search result
     ///
     /// # Errors
-    /// - `InvalidId` if video_id is empty
+    /// - `InvalidId` if the input is empty or whitespace only
+    /// - `NotFound` if the video page doesn't exist or the search is empty
+    /// - `ParseError` if the video page has no title
     /// - `HttpError` for network errors
-    pub async fn get_video_sources(
-        &self,
-        video_slug: &str,
-        video_id: &str,
-    ) -> Result<Vec<VideoSource>> {
-        let data = self.get_video_page_data(video_slug, video_id).await?;
-        Ok(data.sources)
+    pub async fn resolve_url(&self, url_or_query: &str) -> Result<VideoResult> {
+        let Some((video_slug, video_id)) = parse_video_link(url_or_query) else {
+            return self
+                .search(url_or_query)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    PrehrajtoError::NotFound(format!("No results for '{}'", url_or_query.trim()))
+                });
+        };
+
+        self.get_video_result(&video_slug, &video_id).await
     }
 
-    /// Get all streaming sources AND subtitle tracks for a video
+    /// Get a [`VideoResult`] for a video known by slug and ID
     ///
-    /// Fetches the video page **once** and parses both JS sources and
-    /// tracks arrays, avoiding double-fetching.
+    /// Fetches the video page and builds the result from its metadata (see
+    /// [`parse_video_page_result`]), for when there's no search to take it
+    /// from.
     ///
     /// # Arguments
     /// * `video_slug` - URL slug of the video
     /// * `video_id` - ID of the video
     ///
     /// # Returns
-    /// [`VideoPageData`] with sources and subtitles
+    /// The video as a [`VideoResult`]; quality and upload date are `None`
     ///
     /// # Errors
     /// - `InvalidId` if video_id is empty
+    /// - `NotFound` if the video page doesn't exist
+    /// - `ParseError` if the video page has no title
     /// - `HttpError` for network errors
-    pub async fn get_video_page_data(
-        &self,
-        video_slug: &str,
-        video_id: &str,
-    ) -> Result<VideoPageData> {
+    pub async fn get_video_result(&self, video_slug: &str, video_id: &str) -> Result<VideoResult> {
         if video_id.trim().is_empty() {
             return Err(PrehrajtoError::InvalidId(
                 "Video ID cannot be empty".to_string(),
@@ -183,141 +349,1227 @@ impl PrehrajtoScraper {
         }
 
         let path = format!("/{}/{}", video_slug, video_id);
-        let html = self.client.fetch(&path).await?;
-
-        Ok(VideoPageData {
-            sources: parse_video_sources(&html),
-            subtitles: parse_subtitle_tracks(&html),
-        })
+        let html = self.fetch_page(&path).await?;
+        parse_video_page_result(&html, video_slug, video_id).map_err(|e| e.with_context(&path))
     }
 
-    /// Get subtitle tracks for a video
+    /// Re-fetch the current details of a bookmarked video
     ///
-    /// Convenience method — fetches the video page and extracts subtitle tracks.
+    /// Same as [`Self::get_video_result`] for the bookmark's slug and ID, so
+    /// the name, duration and size reflect the page as it is now.
     ///
     /// # Arguments
-    /// * `video_slug` - URL slug of the video
-    /// * `video_id` - ID of the video
+    /// * `bookmark` - Saved video identity
     ///
     /// # Returns
-    /// Vector of [`SubtitleTrack`] (empty if no subtitles available)
+    /// Fresh [`VideoResult`] for the bookmarked video
     ///
     /// # Errors
-    /// - `InvalidId` if video_id is empty
-    /// - `HttpError` for network errors
-    pub async fn get_subtitle_tracks(
-        &self,
-        video_slug: &str,
-        video_id: &str,
-    ) -> Result<Vec<SubtitleTrack>> {
-        let data = self.get_video_page_data(video_slug, video_id).await?;
-        Ok(data.subtitles)
+    /// Same as [`Self::get_video_result`]
+    pub async fn refresh_bookmark(&self, bookmark: &VideoBookmark) -> Result<VideoResult> {
+        self.get_video_result(&bookmark.slug, &bookmark.id).await
     }
 
-    /// Get the original uploaded file URL via download flow
+    /// Search for videos across all lazy-loaded result batches
     ///
-    /// Performs a two-step cookie flow:
-    /// 1. GET video page — sets required cookies (`_nss`, `u_uid`)
-    /// 2. GET `?do=download` with cookies — returns redirect page with original file link
+    /// The first batch comes from the regular search page; further batches
+    /// request the next results page as XHR (`X-Requested-With`), which the
+    /// site is assumed to answer with just the new cards (see
+    /// [site assumptions](crate::url#site-assumptions)). Stops when a batch
+    /// is empty, brings no new videos, or `max_pages` is reached.
+    /// Results are de-duplicated by `video_id`, keeping the first occurrence.
     ///
     /// # Arguments
-    /// * `video_slug` - URL slug of the video
-    /// * `video_id` - ID of the video
+    /// * `query` - Search query string
+    /// * `max_pages` - Maximum number of batches to load, `None` for no limit
     ///
     /// # Returns
-    /// A [`VideoSource`] representing the original uploaded file
+    /// All collected video results in page order
     ///
     /// # Errors
-    /// - `InvalidId` if video_id is empty
-    /// - `NotFound` if original file URL cannot be found
-    /// - `HttpError` for network errors
-    pub async fn get_original_url(
+    /// Same as [`Self::search`]; errors on later batches are returned too
+    pub async fn search_all_pages(
         &self,
-        video_slug: &str,
-        video_id: &str,
-    ) -> Result<VideoSource> {
-        if video_id.trim().is_empty() {
-            return Err(PrehrajtoError::InvalidId(
-                "Video ID cannot be empty".to_string(),
-            ));
-        }
+        query: &str,
+        max_pages: Option<u32>,
+    ) -> Result<Vec<VideoResult>> {
+        let mut results = self.search(query).await?;
+        let mut seen: HashSet<String> =
+            results.iter().map(|v| v.unique_key().to_string()).collect();
 
-        // Step 1: Fetch video page to set cookies (_nss, u_uid)
-        let video_path = format!("/{}/{}", video_slug, video_id);
-        let _ = self.client.fetch(&video_path).await?;
+        let mut page = 1;
+        while !results.is_empty() && max_pages.is_none_or(|max| page < max) {
+            page += 1;
 
-        // Step 2: Fetch download page with cookies (no redirect following)
-        let download_path = format!("/{}/{}?do=download", video_slug, video_id);
-        let html = self.client.fetch_download_page(&download_path).await?;
+            let path = site_path(&build_search_url_paged(query.trim(), page));
+            let body = self.fetch_xhr_page(&path).await?;
+
+            let new: Vec<VideoResult> = parse_search_fragment(&body)?
+                .into_iter()
+                .filter(|v| seen.insert(v.unique_key().to_string()))
+                .collect();
+            if new.is_empty() {
+                break;
+            }
+            results.extend(new);
+        }
 
-        parse_original_download_url(&html)
+        Ok(results)
     }
 
-    /// Search for a movie by name, returning the best match
+    /// Search the first `n` result pages concurrently
+    ///
+    /// Fetches pages `1..=n` through [`Self::search_with`], up to
+    /// `max_concurrent_requests` at a time and still spaced by the rate
+    /// limiter. Results are merged in page order, then card position, and
+    /// de-duplicated by `video_id`, keeping the first occurrence.
     ///
     /// # Arguments
-    /// * `movie_name` - Movie title to search for
-    /// * `year` - Optional release year to narrow results
+    /// * `query` - Search query string
+    /// * `n` - Number of pages to fetch; 0 is treated as 1
     ///
     /// # Returns
-    /// The best matching `VideoResult`, or `None` if no results found
-    pub async fn search_movie(
-        &self,
-        movie_name: &str,
-        year: Option<i32>,
-    ) -> Result<Option<VideoResult>> {
-        let results = self.search_movie_all(movie_name, year).await?;
-        Ok(results.into_iter().next())
+    /// The merged video results
+    ///
+    /// # Errors
+    /// Same as [`Self::search_with`]; the first failing page's error is returned
+    pub async fn search_first_n_pages(&self, query: &str, n: u32) -> Result<Vec<VideoResult>> {
+        let limit = self.config.max_concurrent_requests.max(1);
+
+        let pages: Vec<Result<SearchResponse>> = stream::iter(1..=n.max(1))
+            .map(|page| self.search_with(SearchOptions::new(query).page(page)))
+            .buffered(limit)
+            .collect()
+            .await;
+
+        let mut seen = HashSet::new();
+        let mut results = Vec::new();
+        for page in pages {
+            let new = page?
+                .results
+                .into_iter()
+                .filter(|v| seen.insert(v.unique_key().to_string()));
+            results.extend(new);
+        }
+        Ok(results)
     }
 
-    /// Search for all movie sources by name
+    /// Search for recent videos by taking the first results
+    ///
+    /// Not every card shows an upload date, so this is a pragmatic recency
+    /// filter: with `sort_newest` the site is asked to order results
+    /// newest-first ([`SearchSort::Newest`]) and only the first `limit`
+    /// results are kept.
     ///
     /// # Arguments
-    /// * `movie_name` - Movie title to search for
-    /// * `year` - Optional release year to narrow results
+    /// * `query` - Search query string
+    /// * `sort_newest` - Request newest-first ordering
+    /// * `limit` - Maximum number of results to return
     ///
     /// # Returns
-    /// Vector of matching video results, empty if no results found
-    pub async fn search_movie_all(
+    /// At most `limit` video results
+    ///
+    /// # Errors
+    /// Same as [`Self::search_with`]
+    pub async fn search_recent(
         &self,
-        movie_name: &str,
-        year: Option<i32>,
+        query: &str,
+        sort_newest: bool,
+        limit: usize,
     ) -> Result<Vec<VideoResult>> {
-        let trimmed = movie_name.trim();
-        if trimmed.is_empty() {
-            return Err(PrehrajtoError::InvalidId(
-                "Movie name cannot be empty".to_string(),
-            ));
+        let mut options = SearchOptions::new(query);
+        if sort_newest {
+            options = options.sort(SearchSort::Newest);
         }
 
-        let query = match year {
-            Some(y) => format!("{} {}", trimmed, y),
-            None => trimmed.to_string(),
-        };
-
-        self.search(&query).await
+        let mut results = self.search_with(options).await?.results;
+        results.truncate(limit);
+        Ok(results)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Search for videos and keep only results matching the filter
+    ///
+    /// Filters are applied client-side after parsing.
+    ///
+    /// # Arguments
+    /// * `query` - Search query string
+    /// * `filter` - Filters to apply (see [`SearchFilter`])
+    ///
+    /// # Returns
+    /// Vector of matching video results that pass the filter
+    ///
+    /// # Errors
+    /// Same as [`Self::search`]
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+    ) -> Result<Vec<VideoResult>> {
+        let options = SearchOptions::new(query).filter(filter.clone());
+        Ok(self.search_with(options).await?.results)
+    }
 
-    #[test]
-    fn test_scraper_creation() {
-        let scraper = PrehrajtoScraper::new();
-        assert!(scraper.is_ok());
+    /// Get the "latest" and "most viewed" listings from the homepage
+    ///
+    /// # Returns
+    /// [`HomepageSections`] with empty vecs for sections not present on the page
+    ///
+    /// # Errors
+    /// - `HttpError` if network request fails
+    /// - `ParseError` if HTML parsing fails
+    pub async fn get_homepage_sections(&self) -> Result<HomepageSections> {
+        let html = self.fetch_page("/").await?;
+        parse_homepage(&html)
     }
 
-    #[test]
-    fn test_scraper_with_custom_config() {
-        let config = ClientConfig {
-            requests_per_second: 1.0,
-            timeout_secs: 60,
-            max_retries: 5,
-        };
-        let scraper = PrehrajtoScraper::with_config(config);
-        assert!(scraper.is_ok());
+    /// Get a series page with its episodes, episode count and total runtime
+    ///
+    /// # Arguments
+    /// * `series_url` - Full URL or path of the series page
+    ///
+    /// # Returns
+    /// [`SeriesInfo`] for the page
+    ///
+    /// # Errors
+    /// - `InvalidUrl` if the URL is empty
+    /// - `HttpError` if network request fails
+    /// - `ParseError` if HTML parsing fails
+    pub async fn get_series_info(&self, series_url: &str) -> Result<SeriesInfo> {
+        let path = site_path(series_url);
+        if path.trim_matches('/').is_empty() {
+            return Err(PrehrajtoError::InvalidUrl(
+                "Series URL cannot be empty".to_string(),
+            ));
+        }
+
+        let html = self.fetch_page(&path).await?;
+        parse_series_page(&html).map_err(|e| e.with_context(&path))
+    }
+
+    /// Get the other uploads listed on an uploader's profile page
+    ///
+    /// The profile URL comes from [`VideoMetadata::uploader_url`]. The page
+    /// lists videos as regular cards, so they're parsed like search results.
+    ///
+    /// # Arguments
+    /// * `uploader_url` - Full URL or path of the profile page
+    ///
+    /// # Returns
+    /// Vector of [`VideoResult`] in page order, empty if the profile lists none
+    ///
+    /// # Errors
+    /// - `InvalidUrl` if the URL is empty
+    /// - `HttpError` if network request fails
+    /// - `ParseError` if HTML parsing fails
+    pub async fn get_uploader_videos(&self, uploader_url: &str) -> Result<Vec<VideoResult>> {
+        let path = site_path(uploader_url);
+        if path.trim_matches('/').is_empty() {
+            return Err(PrehrajtoError::InvalidUrl(
+                "Uploader URL cannot be empty".to_string(),
+            ));
+        }
+
+        self.fetch_and_parse(&path, parse_search_fragment, Vec::is_empty)
+            .await
+    }
+
+    /// Get download URL for a video
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL-friendly video slug
+    /// * `video_id` - Unique video ID
+    ///
+    /// # Returns
+    /// Download URL with `?do=download` parameter
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    pub fn get_download_url(&self, video_slug: &str, video_id: &str) -> Result<String> {
+        if video_id.trim().is_empty() {
+            return Err(PrehrajtoError::InvalidId(
+                "Video ID cannot be empty".to_string(),
+            ));
+        }
+
+        Ok(build_download_url(video_slug, video_id))
+    }
+
+    /// Get direct CDN URL for a video file (best quality)
+    ///
+    /// Fetches the video page and extracts the highest quality CDN URL
+    /// from the player initialization blocks.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// Direct URL to CDN (premiumcdn.net) — highest resolution available
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `NotFound` if CDN URL cannot be found in the response
+    /// - `HttpError` for network errors
+    ///
+    /// # Note
+    /// The returned URL has an expiration time (expires parameter),
+    /// so it cannot be cached long-term.
+    pub async fn get_direct_url(&self, video_slug: &str, video_id: &str) -> Result<String> {
+        if video_id.trim().is_empty() {
+            return Err(PrehrajtoError::InvalidId(
+                "Video ID cannot be empty".to_string(),
+            ));
+        }
+
+        // Fetch the video page (NOT ?do=download) to get player sources
+        let path = format!("/{}/{}", video_slug, video_id);
+        let hosts = self.discovered_cdn_hosts();
+        let url = self
+            .fetch_and_parse(
+                &path,
+                |html| parse_direct_url_with_hosts(html, &hosts),
+                String::is_empty,
+            )
+            .await?;
+        Ok(self.clean_cdn_url(url))
+    }
+
+    /// Search and return the direct URL of the top result, for quick playback
+    ///
+    /// Takes the first result of [`Self::search`] and resolves it with
+    /// [`Self::get_direct_url`], so only the search page and that one video
+    /// page are fetched.
+    ///
+    /// # Arguments
+    /// * `query` - Search query string
+    ///
+    /// # Returns
+    /// Direct CDN URL of the top result's best source
+    ///
+    /// # Errors
+    /// - `InvalidId` if query is empty or whitespace only
+    /// - `NotFound` if the search has no results or the video has no CDN URL
+    /// - `HttpError` for network errors
+    /// - `Timeout` if [`ClientConfig::operation_timeout_ms`] passes
+    pub async fn play_first(&self, query: &str) -> Result<String> {
+        self.within_deadline(async {
+            let first = self
+                .search(query)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    PrehrajtoError::NotFound(format!("No results for '{}'", query.trim()))
+                })?;
+            self.get_direct_url(&first.video_slug, &first.video_id)
+                .await
+        })
+        .await
+    }
+
+    /// Check whether a video's file can still be downloaded
+    ///
+    /// Resolves the best direct URL with [`Self::get_direct_url`] and sends a
+    /// HEAD request to it. Uploads whose file is gone still show up in
+    /// search, so this is a cheap liveness check for a library.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// `false` if the page is gone, has no sources, or the CDN answers
+    /// 404/403 or with an HTML error page; `true` otherwise
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `HttpError` / `ConnectionFailed` for other network errors
+    pub async fn is_video_available(&self, video_slug: &str, video_id: &str) -> Result<bool> {
+        let url = match self.get_direct_url(video_slug, video_id).await {
+            Ok(url) => url,
+            Err(PrehrajtoError::NotFound(_)) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+
+        match self.client.head_content_length(&url).await {
+            Ok(_) => Ok(true),
+            Err(PrehrajtoError::NotFound(_)) => Ok(false),
+            Err(PrehrajtoError::HttpError(e))
+                if e.status().is_some_and(|status| {
+                    status == reqwest::StatusCode::NOT_FOUND
+                        || status == reqwest::StatusCode::FORBIDDEN
+                }) =>
+            {
+                Ok(false)
+            }
+            Err(PrehrajtoError::CircuitOpen {
+                status: Some(403 | 404),
+                ..
+            }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get a direct CDN URL that stays valid for at least `min_ttl`
+    ///
+    /// Resolves the URL with [`Self::get_direct_url`] and, if its `expires`
+    /// parameter leaves less than `min_ttl`, re-resolves once in case the
+    /// first fetch caught a stale source. If the second URL still expires
+    /// too soon it is returned anyway and a warning is logged via `tracing`.
+    /// URLs without a parseable `expires` are returned as is.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    /// * `min_ttl` - Minimum time the URL must remain valid
+    ///
+    /// # Returns
+    /// The freshest direct CDN URL obtained
+    ///
+    /// # Errors
+    /// Same as [`Self::get_direct_url`]
+    pub async fn get_fresh_direct_url(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+        min_ttl: Duration,
+    ) -> Result<String> {
+        let url = self.get_direct_url(video_slug, video_id).await?;
+        if has_min_ttl(&url, min_ttl) {
+            return Ok(url);
+        }
+
+        let url = self.get_direct_url(video_slug, video_id).await?;
+        if !has_min_ttl(&url, min_ttl) {
+            tracing::warn!(
+                video_id,
+                min_ttl_secs = min_ttl.as_secs(),
+                "direct URL expires sooner than requested after re-resolving"
+            );
+        }
+        Ok(url)
+    }
+
+    /// Get all streaming quality variants for a video
+    ///
+    /// Fetches the video page and parses JS player sources to extract
+    /// all available quality variants (e.g., 720p, 1080p).
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// Vector of [`VideoSource`] with all available qualities
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `HttpError` for network errors
+    pub async fn get_video_sources(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<Vec<VideoSource>> {
+        let data = self.get_video_page_data(video_slug, video_id).await?;
+        Ok(data.sources)
+    }
+
+    /// Get all streaming quality variants annotated with their file sizes
+    ///
+    /// Like [`Self::get_video_sources`], then issues a HEAD request to each
+    /// CDN URL to fill [`VideoSource::size_bytes`] without downloading.
+    /// Requests run concurrently up to `max_concurrent_requests` and are
+    /// spaced by the rate limiter. A failed HEAD leaves that size as `None`.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// Vector of [`VideoSource`] in page order with `size_bytes` filled where known
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `HttpError` for network errors fetching the video page
+    pub async fn get_video_sources_with_sizes(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<Vec<VideoSource>> {
+        let sources = self.get_video_sources(video_slug, video_id).await?;
+        let limit = self.config.max_concurrent_requests.max(1);
+
+        let sized = stream::iter(sources)
+            .map(|source| self.with_size(source))
+            .buffered(limit)
+            .collect()
+            .await;
+
+        Ok(sized)
+    }
+
+    /// Stream streaming quality variants as their file sizes resolve
+    ///
+    /// Like [`Self::get_video_sources_with_sizes`], but yields each source
+    /// as soon as its HEAD request completes instead of waiting for the
+    /// slowest one, so a quality picker can fill in progressively. Sources
+    /// therefore arrive in completion order, not page order. Requests run
+    /// concurrently up to `max_concurrent_requests`; a failed HEAD yields the
+    /// source with `size_bytes` left as `None`.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// Stream of [`VideoSource`] with `size_bytes` filled where known
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `HttpError` for network errors fetching the video page
+    pub async fn stream_video_sources_with_sizes(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<BoxStream<'_, VideoSource>> {
+        let sources = self.get_video_sources(video_slug, video_id).await?;
+        let limit = self.config.max_concurrent_requests.max(1);
+
+        Ok(stream::iter(sources)
+            .map(|source| self.with_size(source))
+            .buffer_unordered(limit)
+            .boxed())
+    }
+
+    /// Fill a source's `size_bytes` with a HEAD request, leaving it `None` on failure
+    async fn with_size(&self, mut source: VideoSource) -> VideoSource {
+        source.size_bytes = self
+            .client
+            .head_content_length(&source.url)
+            .await
+            .ok()
+            .flatten();
+        source
+    }
+
+    /// Get all streaming sources AND subtitle tracks for a video
+    ///
+    /// Fetches the video page **once** and parses both JS sources and
+    /// tracks arrays, avoiding double-fetching.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// [`VideoPageData`] with sources and subtitles
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `HttpError` for network errors
+    pub async fn get_video_page_data(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<VideoPageData> {
+        if video_id.trim().is_empty() {
+            return Err(PrehrajtoError::InvalidId(
+                "Video ID cannot be empty".to_string(),
+            ));
+        }
+
+        let path = format!("/{}/{}", video_slug, video_id);
+        let hosts = self.discovered_cdn_hosts();
+        self.fetch_and_parse(
+            &path,
+            |html| {
+                Ok(VideoPageData {
+                    sources: parse_video_sources_with_hosts(html, &hosts),
+                    subtitles: parse_subtitle_tracks(html),
+                })
+            },
+            |data| !data.has_sources(),
+        )
+        .await
+    }
+
+    /// Get a fingerprint of a video page's player markup
+    ///
+    /// Polling a few known videos and comparing fingerprints over time
+    /// shows when the site changes its player setup, which usually breaks
+    /// source parsing. CDN tokens differ on every visit but don't affect
+    /// the result; see [`parse_player_fingerprint`].
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// A 16-digit hex hash of the normalized player config
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `NotFound` if the page has no player script
+    /// - `HttpError` for network errors
+    pub async fn page_fingerprint(&self, video_slug: &str, video_id: &str) -> Result<String> {
+        if video_id.trim().is_empty() {
+            return Err(PrehrajtoError::InvalidId(
+                "Video ID cannot be empty".to_string(),
+            ));
+        }
+
+        let path = format!("/{}/{}", video_slug, video_id);
+        self.fetch_and_parse(
+            &path,
+            |html| {
+                parse_player_fingerprint(html).ok_or_else(|| {
+                    PrehrajtoError::NotFound("No player config on video page".to_string())
+                })
+            },
+            |_| false,
+        )
+        .await
+    }
+
+    /// Get the metadata stated on a video's page
+    ///
+    /// Includes the resolution the page text claims, which can be
+    /// cross-checked against the parsed sources with
+    /// [`VideoMetadata::resolution_matches`].
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// [`VideoMetadata`] with `None` for anything the page doesn't state
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `HttpError` for network errors
+    pub async fn get_video_metadata(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<VideoMetadata> {
+        if video_id.trim().is_empty() {
+            return Err(PrehrajtoError::InvalidId(
+                "Video ID cannot be empty".to_string(),
+            ));
+        }
+
+        let path = format!("/{}/{}", video_slug, video_id);
+        self.fetch_and_parse(
+            &path,
+            |html| Ok(parse_video_metadata(html)),
+            |metadata| metadata.title.is_none(),
+        )
+        .await
+    }
+
+    /// Get metadata, sources, subtitles and the best direct URL for a video
+    ///
+    /// Everything is parsed from a single fetch of the video page, so this
+    /// is cheaper than calling [`Self::get_video_metadata`],
+    /// [`Self::get_video_page_data`] and [`Self::get_direct_url`] separately.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// [`FullVideoInfo`]; `best_direct_url` is `None` if no CDN URL was found
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `HttpError` for network errors
+    /// - `Timeout` if [`ClientConfig::operation_timeout_ms`] passes (a
+    ///   refetch of a truncated page counts towards it)
+    pub async fn get_full_video_info(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<FullVideoInfo> {
+        if video_id.trim().is_empty() {
+            return Err(PrehrajtoError::InvalidId(
+                "Video ID cannot be empty".to_string(),
+            ));
+        }
+
+        let path = format!("/{}/{}", video_slug, video_id);
+        let hosts = self.discovered_cdn_hosts();
+        let fetch = self.fetch_and_parse(
+            &path,
+            |html| {
+                Ok(FullVideoInfo {
+                    metadata: parse_video_metadata(html),
+                    sources: parse_video_sources_with_hosts(html, &hosts),
+                    subtitles: parse_subtitle_tracks(html),
+                    best_direct_url: parse_direct_url_with_hosts(html, &hosts).ok(),
+                })
+            },
+            |info| info.sources.is_empty(),
+        );
+        let info = self.within_deadline(fetch).await?;
+        Ok(FullVideoInfo {
+            best_direct_url: info.best_direct_url.map(|url| self.clean_cdn_url(url)),
+            ..info
+        })
+    }
+
+    /// Get subtitle tracks for a video
+    ///
+    /// Convenience method — fetches the video page and extracts subtitle tracks.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// Vector of [`SubtitleTrack`] (empty if no subtitles available)
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `HttpError` for network errors
+    pub async fn get_subtitle_tracks(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<Vec<SubtitleTrack>> {
+        let data = self.get_video_page_data(video_slug, video_id).await?;
+        Ok(data.subtitles)
+    }
+
+    /// Get the original uploaded file URL via download flow
+    ///
+    /// Performs a two-step cookie flow:
+    /// 1. GET video page — sets required cookies (`_nss`, `u_uid`)
+    /// 2. GET `?do=download` with cookies — returns redirect page with original file link
+    ///
+    /// Waits `download_flow_delay_ms` (see [`ClientConfig`]) between the steps.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// A [`VideoSource`] representing the original uploaded file
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `CookiesDisabled` if [`ClientConfig::cookie_store`] is off
+    /// - `NotFound` if original file URL cannot be found
+    /// - `HttpError` for network errors
+    pub async fn get_original_url(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<VideoSource> {
+        let options = self.get_download_options(video_slug, video_id).await?;
+        Ok(options.original)
+    }
+
+    /// Get all download options via the download flow
+    ///
+    /// Same cookie flow as [`Self::get_original_url`], but also returns the
+    /// packaged download (video with subtitles) when the site offers one.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    ///
+    /// # Returns
+    /// [`DownloadOptions`] with the original file and optional packaged download
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `CookiesDisabled` if [`ClientConfig::cookie_store`] is off
+    /// - `NotFound` if original file URL cannot be found
+    /// - `HttpError` for network errors
+    /// - `Timeout` if [`ClientConfig::operation_timeout_ms`] passes
+    pub async fn get_download_options(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+    ) -> Result<DownloadOptions> {
+        if video_id.trim().is_empty() {
+            return Err(PrehrajtoError::InvalidId(
+                "Video ID cannot be empty".to_string(),
+            ));
+        }
+        // Step 2 would be refused without the cookies from step 1
+        if !self.config.cookie_store {
+            return Err(PrehrajtoError::CookiesDisabled);
+        }
+
+        let download_path = format!("/{}/{}?do=download", video_slug, video_id);
+        let html = self
+            .within_deadline(async {
+                // Step 1: Fetch video page to set cookies (_nss, u_uid)
+                let video_path = format!("/{}/{}", video_slug, video_id);
+                let _ = self.client.fetch(&video_path).await?;
+
+                // Give the session cookie time to activate server-side
+                if self.config.download_flow_delay_ms > 0 {
+                    let delay = Duration::from_millis(self.config.download_flow_delay_ms);
+                    tokio::time::sleep(delay).await;
+                }
+
+                // Step 2: Fetch download page with cookies (no redirect following)
+                self.client.fetch_download_page(&download_path).await
+            })
+            .await?;
+
+        let mut options = parse_download_options_with_hosts(&html, &self.discovered_cdn_hosts())
+            .map_err(|e| e.with_context(&download_path))?;
+        options.original.url = self.clean_cdn_url(options.original.url);
+        if let Some(packaged) = options.packaged.as_mut() {
+            packaged.url = self.clean_cdn_url(std::mem::take(&mut packaged.url));
+        }
+        Ok(options)
+    }
+
+    /// Resolve the best available file for a video
+    ///
+    /// By default returns the highest resolution streaming source. With
+    /// `prefer_original`, the original-file download flow runs first and
+    /// streaming is used only if no original file is found.
+    ///
+    /// # Arguments
+    /// * `video_slug` - URL slug of the video
+    /// * `video_id` - ID of the video
+    /// * `options` - Resolution preferences (see [`ResolveOptions`])
+    ///
+    /// # Returns
+    /// [`ResolvedVideo`] with the chosen source and its [`SourceKind`]
+    ///
+    /// # Errors
+    /// - `InvalidId` if video_id is empty
+    /// - `NotFound` if neither an original nor a streaming source exists
+    /// - `HttpError` for network errors
+    pub async fn resolve_best(
+        &self,
+        video_slug: &str,
+        video_id: &str,
+        options: &ResolveOptions,
+    ) -> Result<ResolvedVideo> {
+        if options.prefer_original {
+            match self.get_original_url(video_slug, video_id).await {
+                Ok(source) => {
+                    return Ok(ResolvedVideo {
+                        source,
+                        kind: SourceKind::Original,
+                    });
+                }
+                Err(PrehrajtoError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let sources = self.get_video_sources(video_slug, video_id).await?;
+        let best = best_source_with(&sources, !options.skip_zero_resolution);
+        let mut best = best.cloned().ok_or_else(|| {
+            PrehrajtoError::NotFound("No streaming sources found on video page".to_string())
+                .with_context(&format!("/{}/{}", video_slug, video_id))
+        })?;
+        best.url = self.clean_cdn_url(best.url);
+
+        Ok(ResolvedVideo {
+            source: best,
+            kind: SourceKind::Streaming,
+        })
+    }
+
+    /// Search and resolve the direct CDN URL of every result
+    ///
+    /// Results are resolved one by one with [`Self::get_direct_url`]; a
+    /// failing item doesn't stop the batch, its error is returned alongside
+    /// it. With [`BatchOptions::fail_fast`], the first item acts as a
+    /// connectivity canary: a connection, rate-limit or 403 block error there
+    /// aborts the batch before any further requests are made.
+    ///
+    /// # Arguments
+    /// * `query` - Search query string
+    /// * `options` - Batch behaviour
+    ///
+    /// # Returns
+    /// Each search result paired with its direct URL or the error resolving it
+    ///
+    /// # Errors
+    /// - Any error from the search itself
+    /// - `ConnectionFailed`, `RateLimited`, or a network or 403 `HttpError`
+    ///   from the canary when `fail_fast` is set
+    pub async fn search_with_direct_urls(
+        &self,
+        query: &str,
+        options: &BatchOptions,
+    ) -> Result<Vec<(VideoResult, Result<String>)>> {
+        let results = self.search(query).await?;
+
+        let mut resolved = Vec::with_capacity(results.len());
+        for video in results {
+            let direct = if options.fail_fast && resolved.is_empty() {
+                let direct = self.canary_direct_url(&video).await;
+                if let Err(e) = &direct
+                    && is_connectivity_error(e)
+                {
+                    return Err(direct.unwrap_err());
+                }
+                direct
+            } else {
+                self.get_direct_url(&video.video_slug, &video.video_id)
+                    .await
+            };
+            resolved.push((video, direct));
+        }
+
+        Ok(resolved)
+    }
+
+    /// Resolve the first batch item as a connectivity canary
+    ///
+    /// Same as [`Self::get_direct_url`], except the page is fetched with
+    /// [`PrehrajtoClient::fetch_canary`], so a 403 block page fails with
+    /// `HttpError` instead of being parsed as a page without sources.
+    async fn canary_direct_url(&self, video: &VideoResult) -> Result<String> {
+        let path = format!("/{}/{}", video.video_slug, video.video_id);
+        let html = self.client.fetch_canary(&path).await?;
+        let html = self.verify_site_page(&path, html)?;
+        let url = parse_direct_url_with_hosts(&html, &self.discovered_cdn_hosts())
+            .map_err(|e| e.with_context(&path))?;
+        Ok(self.clean_cdn_url(url))
+    }
+
+    /// Discover the CDN hosts currently used by the site
+    ///
+    /// Fetches the homepage, opens the first listed video and collects the
+    /// hosts of its player URLs that look like CDN nodes (see
+    /// [`parse_cdn_hosts`]). Discovered hosts are kept on this scraper, so
+    /// its later URL extraction and redirect handling accept links on them;
+    /// other scrapers in the process are unaffected.
+    ///
+    /// # Returns
+    /// The hosts discovered by this call
+    ///
+    /// # Errors
+    /// - `NotFound` if the homepage lists no video to sample
+    /// - `HttpError` / `ConnectionFailed` for network errors
+    pub async fn refresh_cdn_hosts(&self) -> Result<Vec<String>> {
+        let hosts = self.discover_cdn_hosts().await?;
+        self.client.add_cdn_hosts(&hosts);
+        Ok(hosts)
+    }
+
+    /// Get all CDN hosts this scraper accepts: the static list plus any
+    /// found by [`Self::refresh_cdn_hosts`]
+    pub fn known_cdn_hosts(&self) -> Vec<String> {
+        let mut hosts: Vec<String> = STATIC_CDN_HOSTS.iter().map(|h| h.to_string()).collect();
+        hosts.extend(self.discovered_cdn_hosts());
+        hosts
+    }
+
+    /// Snapshot of the hosts found by [`Self::refresh_cdn_hosts`]
+    fn discovered_cdn_hosts(&self) -> Vec<String> {
+        self.client.cdn_hosts()
+    }
+
+    /// Fetch a sample video page and extract its CDN hosts
+    async fn discover_cdn_hosts(&self) -> Result<Vec<String>> {
+        let sections = self.get_homepage_sections().await?;
+        let sample = sections
+            .latest
+            .first()
+            .or_else(|| sections.popular.first())
+            .ok_or_else(|| PrehrajtoError::NotFound("No sample video on homepage".to_string()))?;
+
+        let path = format!("/{}/{}", sample.video_slug, sample.video_id);
+        let html = self.fetch_page(&path).await?;
+        Ok(parse_cdn_hosts(&html))
+    }
+
+    /// Fetch a page and parse it, refetching once on a truncated empty parse
+    ///
+    /// With `refetch_on_empty_parse` enabled, a body that parses to nothing
+    /// (empty per `is_empty`, or a `NotFound` error) and lacks a closing
+    /// `</html>` tag is fetched a second time. The second result is returned
+    /// as-is — there is never more than one refetch. Parse errors carry the
+    /// path as context (see [`PrehrajtoError::with_context`]).
+    async fn fetch_and_parse<T>(
+        &self,
+        path: &str,
+        parse: impl Fn(&str) -> Result<T>,
+        is_empty: impl Fn(&T) -> bool,
+    ) -> Result<T> {
+        let parse = |html: &str| parse(html).map_err(|e| e.with_context(path));
+
+        let html = self.fetch_page(path).await?;
+        let parsed = parse(&html);
+
+        if !self.config.refetch_on_empty_parse || !looks_truncated(&html) {
+            return parsed;
+        }
+
+        let parsed_empty = match &parsed {
+            Ok(value) => is_empty(value),
+            Err(PrehrajtoError::NotFound(_)) => true,
+            Err(_) => false,
+        };
+        if !parsed_empty {
+            return parsed;
+        }
+
+        let html = self.fetch_page(path).await?;
+        parse(&html)
+    }
+
+    /// Run a multi-step operation under [`ClientConfig::operation_timeout_ms`], if set
+    async fn within_deadline<T>(&self, operation: impl Future<Output = Result<T>>) -> Result<T> {
+        match self.config.operation_timeout_ms {
+            Some(ms) => with_deadline(Duration::from_millis(ms), operation).await,
+            None => operation.await,
+        }
+    }
+
+    /// Fetch a site page, checking it came from prehraj.to if configured
+    ///
+    /// With `verify_site_pages` enabled, a page without site markers (see
+    /// [`is_prehrajto_page`]) fails with `ParseError`.
+    async fn fetch_page(&self, path: &str) -> Result<String> {
+        let html = self.client.fetch(path).await?;
+        self.verify_site_page(path, html)
+    }
+
+    /// Fetch a site page as XHR, with the same check as [`Self::fetch_page`]
+    ///
+    /// Snippet JSON and bare HTML fragments carry no site markers, so only
+    /// responses that are full HTML documents are verified.
+    async fn fetch_xhr_page(&self, path: &str) -> Result<String> {
+        let body = self.client.fetch_xhr(path).await?;
+        if is_html_document(&body) {
+            self.verify_site_page(path, body)
+        } else {
+            Ok(body)
+        }
+    }
+
+    /// Fail with `ParseError` if `verify_site_pages` is enabled and the page
+    /// has no site markers
+    fn verify_site_page(&self, path: &str, html: String) -> Result<String> {
+        if self.config.verify_site_pages && !is_prehrajto_page(&html) {
+            return Err(PrehrajtoError::ParseError(format!(
+                "Response for {} is not a prehraj.to page (captive portal, block page or wrong base URL?)",
+                path
+            )));
+        }
+        Ok(html)
+    }
+
+    /// Apply [`strip_cdn_tracking`] if `clean_cdn_urls` is enabled
+    fn clean_cdn_url(&self, url: String) -> String {
+        if self.config.clean_cdn_urls {
+            strip_cdn_tracking(&url)
+        } else {
+            url
+        }
+    }
+
+    /// Search for a movie by name, returning the best match
+    ///
+    /// # Arguments
+    /// * `movie_name` - Movie title to search for
+    /// * `year` - Optional release year to narrow results
+    ///
+    /// # Returns
+    /// The best matching `VideoResult`, or `None` if no results found
+    pub async fn search_movie(
+        &self,
+        movie_name: &str,
+        year: Option<i32>,
+    ) -> Result<Option<VideoResult>> {
+        self.search_movie_with_scoring(movie_name, year, &ScoringConfig::default())
+            .await
+    }
+
+    /// Search for a movie by name, scoring results with a custom config
+    ///
+    /// Results are scored with [`match_score`] against the movie name;
+    /// stop-words from `scoring` don't count toward the overlap. Ties keep
+    /// the site's order.
+    ///
+    /// # Arguments
+    /// * `movie_name` - Movie title to search for
+    /// * `year` - Optional release year to narrow results
+    /// * `scoring` - Stop-words to ignore when scoring
+    ///
+    /// # Returns
+    /// The best matching `VideoResult`, or `None` if no results found
+    pub async fn search_movie_with_scoring(
+        &self,
+        movie_name: &str,
+        year: Option<i32>,
+        scoring: &ScoringConfig,
+    ) -> Result<Option<VideoResult>> {
+        let results = self.search_movie_all(movie_name, year).await?;
+
+        let mut best: Option<(usize, VideoResult)> = None;
+        for video in results {
+            let score = match_score(movie_name, &video.name, scoring);
+            if best.as_ref().is_none_or(|(top, _)| score > *top) {
+                best = Some((score, video));
+            }
+        }
+        Ok(best.map(|(_, video)| video))
+    }
+
+    /// Search for all movie sources by name
+    ///
+    /// # Arguments
+    /// * `movie_name` - Movie title to search for
+    /// * `year` - Optional release year to narrow results
+    ///
+    /// # Returns
+    /// Vector of matching video results, empty if no results found
+    pub async fn search_movie_all(
+        &self,
+        movie_name: &str,
+        year: Option<i32>,
+    ) -> Result<Vec<VideoResult>> {
+        let trimmed = movie_name.trim();
+        if trimmed.is_empty() {
+            return Err(PrehrajtoError::InvalidId(
+                "Movie name cannot be empty".to_string(),
+            ));
+        }
+
+        let query = match year {
+            Some(y) => format!("{} {}", trimmed, y),
+            None => trimmed.to_string(),
+        };
+
+        self.search(&query).await
+    }
+}
+
+/// Checks whether a CDN URL remains valid for at least `min_ttl`
+///
+/// URLs without a parseable `expires` parameter are assumed to be fine.
+fn has_min_ttl(url: &str, min_ttl: Duration) -> bool {
+    let Some(expires) = cdn_url_expires(url) else {
+        return true;
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    expires.saturating_sub(now) >= min_ttl.as_secs()
+}
+
+/// Builds the site path of the search page for `query` with `options`
+///
+/// `options.query` is ignored in favour of `query`.
+///
+/// # Errors
+/// - `InvalidId` if query is empty or whitespace only
+fn search_path(query: &str, options: &SearchOptions) -> Result<String> {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return Err(PrehrajtoError::InvalidId(
+            "Search query cannot be empty".to_string(),
+        ));
+    }
+    Ok(site_path(&build_search_url_with_options(trimmed, options)))
+}
+
+/// Checks whether an error means the site is unreachable or blocking us
+fn is_connectivity_error(error: &PrehrajtoError) -> bool {
+    match error {
+        PrehrajtoError::ConnectionFailed(_) | PrehrajtoError::RateLimited => true,
+        PrehrajtoError::HttpError(e) => {
+            e.is_connect() || e.is_timeout() || e.status() == Some(reqwest::StatusCode::FORBIDDEN)
+        }
+        PrehrajtoError::CircuitOpen { status, .. } => matches!(status, None | Some(403)),
+        _ => false,
+    }
+}
+
+/// Parses a search response, which may be the video page a search redirected to
+fn parse_search_page(html: &str) -> Result<Vec<VideoResult>> {
+    let results = parse_search_results(html)?;
+    if results.is_empty()
+        && let Some(video) = parse_redirected_video_page(html)
+    {
+        return Ok(vec![video]);
+    }
+    Ok(results)
+}
+
+/// Checks whether a body is a full HTML document rather than a fragment
+fn is_html_document(body: &str) -> bool {
+    let lower = body.to_ascii_lowercase();
+    lower.contains("<html") || lower.contains("<title")
+}
+
+/// Checks whether an HTML body looks truncated (no closing `</html>` tag)
+fn looks_truncated(html: &str) -> bool {
+    !html.to_ascii_lowercase().contains("</html>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Scraper pointed at a mock server, with fast rate limiting and no retries
+    fn mock_scraper(server: &MockServer, config: ClientConfig) -> PrehrajtoScraper {
+        PrehrajtoScraper::with_config(ClientConfig {
+            requests_per_second: 100.0,
+            max_retries: 0,
+            base_url: server.uri(),
+            ..config
+        })
+        .unwrap()
+    }
+
+    /// Search result card linking to `/{slug}/{id}`
+    fn search_card(slug: &str, id: &str) -> String {
+        format!(r#"<a href="/{slug}/{id}"><h3>{slug}</h3></a>"#)
+    }
+
+    /// Search results page listing the given cards
+    fn search_page_html(cards: &[String]) -> String {
+        format!("<main>{}</main></html>", cards.concat())
+    }
+
+    #[test]
+    fn test_scraper_creation() {
+        let scraper = PrehrajtoScraper::new();
+        assert!(scraper.is_ok());
+    }
+
+    #[test]
+    fn test_scraper_with_custom_config() {
+        let config = ClientConfig {
+            requests_per_second: 1.0,
+            timeout_secs: 60,
+            max_retries: 5,
+            ..Default::default()
+        };
+        let scraper = PrehrajtoScraper::with_config(config);
+        assert!(scraper.is_ok());
+    }
+
+    #[test]
+    fn test_base_url_reflects_config() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        assert_eq!(scraper.base_url(), "https://prehraj.to");
+
+        let scraper = PrehrajtoScraper::with_config(ClientConfig {
+            base_url: "http://localhost:8080/".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(scraper.base_url(), "http://localhost:8080");
     }
 
     #[test]
@@ -331,112 +1583,1558 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_get_download_url_empty_id() {
-        let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.get_download_url("some-slug", "");
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(msg)) => {
-                assert!(msg.contains("empty"));
-            }
-            _ => panic!("Expected InvalidId error"),
-        }
+    #[test]
+    fn test_get_download_url_empty_id() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.get_download_url("some-slug", "");
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(msg)) => {
+                assert!(msg.contains("empty"));
+            }
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[test]
+    fn test_get_download_url_whitespace_id() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.get_download_url("some-slug", "   ");
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(_)) => {}
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_empty_query() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.search("").await;
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(msg)) => {
+                assert!(msg.contains("empty"));
+            }
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_whitespace_query() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.search("   ").await;
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(_)) => {}
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_direct_url_empty_id() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.get_direct_url("some-slug", "").await;
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(msg)) => {
+                assert!(msg.contains("empty"));
+            }
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_direct_url_whitespace_id() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.get_direct_url("some-slug", "   ").await;
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(_)) => {}
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_movie_empty_name() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.search_movie("", None).await;
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(msg)) => {
+                assert!(msg.contains("empty"));
+            }
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_movie_whitespace_name() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.search_movie("   ", Some(2020)).await;
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(_)) => {}
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_movie_prefers_best_score() {
+        let server = MockServer::start().await;
+        let html = r#"<html><body><main>
+            <a href="/the-batman-film-cz-dabing/abc123"><h3>The Batman film CZ dabing</h3></a>
+            <a href="/matrix-1999/def456"><h3>Matrix 1999</h3></a>
+        </main></body></html>"#;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(html))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let best = scraper
+            .search_movie("The Matrix film CZ dabing", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(best.video_id, "def456");
+    }
+
+    #[tokio::test]
+    async fn test_search_movie_all_empty_name() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.search_movie_all("", Some(2020)).await;
+        assert!(result.is_err());
+        match result {
+            Err(PrehrajtoError::InvalidId(msg)) => {
+                assert!(msg.contains("empty"));
+            }
+            _ => panic!("Expected InvalidId error"),
+        }
+    }
+
+    #[test]
+    fn test_looks_truncated() {
+        assert!(looks_truncated("<html><body><script>var videos"));
+        assert!(!looks_truncated("<html><body></body></HTML>\n"));
+    }
+
+    #[tokio::test]
+    async fn test_refetch_on_truncated_body() {
+        let server = MockServer::start().await;
+        let full = r#"<html><body><script>
+            videos.push({ src: "https://cdn.premiumcdn.net/abc/720p.mp4?token=x", type: 'video/mp4', res: '720', label: '720p' });
+        </script></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/video/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html><body><scr"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/video/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(full))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(
+            &server,
+            ClientConfig {
+                refetch_on_empty_parse: true,
+                ..Default::default()
+            },
+        );
+        let url = scraper.get_direct_url("video", "abc123").await.unwrap();
+        assert_eq!(url, "https://cdn.premiumcdn.net/abc/720p.mp4?token=x");
+    }
+
+    #[tokio::test]
+    async fn test_no_refetch_when_disabled() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/hledej/test"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html><body><main>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let results = scraper.search("test").await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refetch_happens_only_once() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/video/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html><body>"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(
+            &server,
+            ClientConfig {
+                refetch_on_empty_parse: true,
+                ..Default::default()
+            },
+        );
+        let sources = scraper.get_video_sources("video", "abc123").await.unwrap();
+        assert!(sources.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_original_url_requires_cookie_store() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .append_header("Set-Cookie", "_nss=abc; Path=/")
+                    .set_body_string("<html></html>"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .and(query_param("do", "download"))
+            .and(header("Cookie", "_nss=abc"))
+            .respond_with(ResponseTemplate::new(302).set_body_string(
+                r#"<html><a href="https://pf-storage1.premiumcdn.net/orig/movie.mkv?token=x">Download</a></html>"#,
+            ))
+            .with_priority(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let enabled = mock_scraper(&server, ClientConfig::default());
+        let source = enabled.get_original_url("movie", "abc123").await.unwrap();
+        assert_eq!(source.format, Some("mkv".to_string()));
+
+        // Fails before sending anything, so the expectations above still hold
+        let disabled = mock_scraper(
+            &server,
+            ClientConfig {
+                cookie_store: false,
+                ..Default::default()
+            },
+        );
+        let result = disabled.get_original_url("movie", "abc123").await;
+        assert!(matches!(result, Err(PrehrajtoError::CookiesDisabled)));
+    }
+
+    #[tokio::test]
+    async fn test_download_flow_delay_is_awaited() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .and(query_param("do", "download"))
+            .respond_with(ResponseTemplate::new(302).set_body_string(
+                r#"<html><a href="https://pf-storage1.premiumcdn.net/orig/movie.mkv?token=x">Download</a></html>"#,
+            ))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(
+            &server,
+            ClientConfig {
+                download_flow_delay_ms: 300,
+                ..Default::default()
+            },
+        );
+
+        let start = Instant::now();
+        let source = scraper.get_original_url("movie", "abc123").await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(source.format, Some("mkv".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_download_flow_exceeds_operation_timeout() {
+        let server = MockServer::start().await;
+
+        // Each step alone is well within the per-request timeout
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("<html></html>")
+                    .set_delay(Duration::from_millis(150)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .and(query_param("do", "download"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .set_body_string(r#"<a href="https://pf-storage1.premiumcdn.net/o.mkv">x</a>"#)
+                    .set_delay(Duration::from_millis(150)),
+            )
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let config = |operation_timeout_ms| ClientConfig {
+            operation_timeout_ms,
+            ..Default::default()
+        };
+
+        let scraper = mock_scraper(&server, config(Some(200)));
+        let result = scraper.get_original_url("movie", "abc123").await;
+        assert!(matches!(result, Err(PrehrajtoError::Timeout(200))));
+
+        let scraper = mock_scraper(&server, config(Some(2_000)));
+        assert!(scraper.get_original_url("movie", "abc123").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_video_result() {
+        let server = MockServer::start().await;
+        let page = r#"<html><head>
+            <meta property="og:title" content="Doctor Who S07E05 | Prehraj.to">
+            <meta property="video:duration" content="2660">
+            <script type="application/ld+json">{"@type": "VideoObject", "contentSize": "1.7 GB"}</script>
+            </head><body></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/doctor-who-s07e05/63aba7f51f6cf"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let result = scraper
+            .get_video_result("doctor-who-s07e05", "63aba7f51f6cf")
+            .await
+            .unwrap();
+
+        assert_eq!(result.name, "Doctor Who S07E05");
+        assert_eq!(result.video_slug, "doctor-who-s07e05");
+        assert_eq!(result.video_id, "63aba7f51f6cf");
+        assert_eq!(result.duration.as_deref(), Some("00:44:20"));
+        assert_eq!(result.file_size.as_deref(), Some("1.7 GB"));
+        let download_path = "/doctor-who-s07e05/63aba7f51f6cf?do=download";
+        assert!(result.download_url.ends_with(download_path));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_bookmark() {
+        let server = MockServer::start().await;
+        let page = r#"<html><head>
+            <meta property="og:title" content="Doctor Who S07E05 (remastered) | Prehraj.to">
+            <meta property="video:duration" content="2660">
+            </head><body></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/doctor-who-s07e05/63aba7f51f6cf"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let bookmark = VideoBookmark {
+            name: "Doctor Who S07E05".to_string(),
+            slug: "doctor-who-s07e05".to_string(),
+            id: "63aba7f51f6cf".to_string(),
+        };
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let result = scraper.refresh_bookmark(&bookmark).await.unwrap();
+
+        assert_eq!(result.name, "Doctor Who S07E05 (remastered)");
+        assert_eq!(result.duration.as_deref(), Some("00:44:20"));
+        assert_eq!(VideoBookmark::from(&result).id, bookmark.id);
+    }
+
+    #[tokio::test]
+    async fn test_get_full_video_info() {
+        let server = MockServer::start().await;
+        let page = r#"<html><head>
+            <meta property="og:title" content="Doctor Who | Prehraj.to">
+            <meta property="video:duration" content="3600">
+            </head><body><span>Full HD 1080p</span><p>Dostupné do 31. 12. 2026</p><script>
+            videos.push({ src: "https://cdn.premiumcdn.net/a/1080p.mp4?token=a", type: 'video/mp4', res: '1080', label: '1080p' });
+            videos.push({ src: "https://cdn.premiumcdn.net/a/720p.mp4?token=b", type: 'video/mp4', res: '720', label: '720p' });
+            var tracks = [{ src: "https://cdn.premiumcdn.net/a/cze.vtt", srclang: "cs", label: "CZE", kind: "captions", default: true }];
+            </script></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/doctor-who/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let info = scraper
+            .get_full_video_info("doctor-who", "abc123")
+            .await
+            .unwrap();
+
+        assert_eq!(info.metadata.title.as_deref(), Some("Doctor Who"));
+        assert_eq!(info.metadata.duration.as_deref(), Some("01:00:00"));
+        assert_eq!(info.metadata.reported_resolution, Some(1080));
+        assert!(info.metadata.available_until.is_some());
+        assert_eq!(info.sources.len(), 2);
+        assert_eq!(info.subtitles.len(), 1);
+        assert_eq!(info.subtitles[0].language, "cs");
+        assert_eq!(
+            info.best_direct_url.as_deref(),
+            Some("https://cdn.premiumcdn.net/a/1080p.mp4?token=a")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_full_video_info_empty_id() {
+        let scraper = PrehrajtoScraper::new().unwrap();
+        let result = scraper.get_full_video_info("movie", " ").await;
+        assert!(matches!(result, Err(PrehrajtoError::InvalidId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_site_pages_rejects_foreign_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<html><head><title>Wi-Fi Login</title></head><body><main>
+                <a href="/video-one/abc123"><h3>Video One</h3></a></main></body></html>"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let lenient = mock_scraper(&server, ClientConfig::default());
+        assert_eq!(lenient.search("video").await.unwrap().len(), 1);
+
+        let strict = mock_scraper(
+            &server,
+            ClientConfig {
+                verify_site_pages: true,
+                ..Default::default()
+            },
+        );
+        let result = strict.search("video").await;
+        assert!(
+            matches!(result, Err(PrehrajtoError::ParseError(msg)) if msg.contains("not a prehraj.to page"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_debug_returns_raw_html() {
+        let server = MockServer::start().await;
+        let html = r#"<html><body><main>
+            <a href="/doctor-who-s07e05/63aba7f51f6cf"><div><div>00:44:20</div></div><h3>Doctor Who S07E05</h3></a>
+            </main></body></html>"#;
+        Mock::given(method("GET"))
+            .and(path("/hledej/doctor%20who"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(html))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let (results, raw) = scraper.search_debug("doctor who").await.unwrap();
+
+        assert_eq!(raw, html);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].video_id, "63aba7f51f6cf");
+        assert_eq!(results[0].duration_secs, Some(2660));
+    }
+
+    #[tokio::test]
+    async fn test_search_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(search_page_html(&[search_card("one", "a1")])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("vp-page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(search_page_html(&[search_card("two", "a2")])),
+            )
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("vp-page", "9"))
+            .respond_with(ResponseTemplate::new(404))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let first = scraper.search_page("video", 1).await.unwrap();
+        assert_eq!(first, scraper.search("video").await.unwrap());
+        assert_eq!(first[0].video_id, "a1");
+
+        let second = scraper.search_page("video", 2).await.unwrap();
+        assert_eq!(second[0].video_id, "a2");
+        assert!(scraper.search_page("video", 9).await.unwrap().is_empty());
+
+        // Page 1 is requested exactly like a plain search
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests[0].url.query(), None);
+        assert_eq!(requests[0].url, requests[1].url);
     }
 
-    #[test]
-    fn test_get_download_url_whitespace_id() {
-        let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.get_download_url("some-slug", "   ");
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(_)) => {}
-            _ => panic!("Expected InvalidId error"),
-        }
+    #[tokio::test]
+    async fn test_search_all_stops_when_site_repeats_last_page() {
+        let server = MockServer::start().await;
+        // Any page past the second gets the second page again
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(search_page_html(&[
+                    search_card("two", "a2"),
+                    search_card("three", "a3"),
+                ])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(|request: &wiremock::Request| request.url.query().is_none())
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(search_page_html(&[
+                    search_card("one", "a1"),
+                    search_card("two", "a2"),
+                ])),
+            )
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let results = scraper.search_all("video", None).await.unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|v| v.video_id.as_str()).collect();
+        assert_eq!(ids, ["a1", "a2", "a3"]);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+
+        let limited = scraper.search_all("video", Some(1)).await.unwrap();
+        assert_eq!(limited.len(), 2);
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
     }
 
     #[tokio::test]
-    async fn test_search_empty_query() {
-        let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.search("").await;
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(msg)) => {
-                assert!(msg.contains("empty"));
-            }
-            _ => panic!("Expected InvalidId error"),
-        }
+    async fn test_search_first_n_pages() {
+        let server = MockServer::start().await;
+        // Page 1 is slow, so concurrent pages finish out of order
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(search_page_html(&[
+                        search_card("one", "a1"),
+                        search_card("two", "a2"),
+                    ]))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("vp-page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(search_page_html(&[
+                    search_card("two", "a2"),
+                    search_card("three", "a3"),
+                ])),
+            )
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("vp-page", "3"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(search_page_html(&[search_card("four", "a4")])),
+            )
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let results = scraper.search_first_n_pages("video", 3).await.unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|v| v.video_id.as_str()).collect();
+        assert_eq!(ids, ["a1", "a2", "a3", "a4"]);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
     }
 
     #[tokio::test]
-    async fn test_search_whitespace_query() {
-        let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.search("   ").await;
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(_)) => {}
-            _ => panic!("Expected InvalidId error"),
-        }
+    async fn test_play_first() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/matrix"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<html><body><main>
+                <a href="/matrix-1999/abc123"><h3>Matrix 1999</h3></a>
+                <a href="/matrix-reloaded/def456"><h3>Matrix Reloaded</h3></a>
+                </main></body></html>"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/matrix-1999/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<html><script>
+                videos.push({ src: "https://cdn.premiumcdn.net/m/1080p.mp4?token=a", type: 'video/mp4', res: '1080', label: '1080p' });
+                </script></html>"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let url = scraper.play_first("matrix").await.unwrap();
+        assert_eq!(url, "https://cdn.premiumcdn.net/m/1080p.mp4?token=a");
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
     }
 
     #[tokio::test]
-    async fn test_get_direct_url_empty_id() {
-        let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.get_direct_url("some-slug", "").await;
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(msg)) => {
-                assert!(msg.contains("empty"));
-            }
-            _ => panic!("Expected InvalidId error"),
-        }
+    async fn test_play_first_no_results() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html><body></body></html>"))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let result = scraper.play_first("nothing").await;
+        assert!(matches!(result, Err(PrehrajtoError::NotFound(_))));
     }
 
     #[tokio::test]
-    async fn test_get_direct_url_whitespace_id() {
-        let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.get_direct_url("some-slug", "   ").await;
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(_)) => {}
-            _ => panic!("Expected InvalidId error"),
+    async fn test_is_video_available() {
+        let server = MockServer::start().await;
+        let page = |file: &str| {
+            format!(
+                r#"<html><script>
+                videos.push({{ src: "{}/cdn/{}?token=a", type: 'video/mp4', res: '720', label: '720p' }});
+                </script></html>"#,
+                server.uri(),
+                file
+            )
+        };
+
+        Mock::given(method("GET"))
+            .and(path("/live/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page("live.mp4")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/dead/def456"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page("dead.mp4")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/empty/ghi789"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .mount(&server)
+            .await;
+        let html_page = ResponseTemplate::new(200).insert_header("Content-Type", "text/html");
+        Mock::given(method("GET"))
+            .and(path("/gone/jkl012"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page("gone.mp4")))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/cdn/gone.mp4"))
+            .respond_with(html_page)
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/cdn/live.mp4"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/cdn/dead.mp4"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        assert!(scraper.is_video_available("live", "abc123").await.unwrap());
+        assert!(!scraper.is_video_available("dead", "def456").await.unwrap());
+        assert!(!scraper.is_video_available("empty", "ghi789").await.unwrap());
+        assert!(!scraper.is_video_available("gone", "jkl012").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_page_fingerprint_ignores_tokens() {
+        let server = MockServer::start().await;
+        let page = |token: &str, expires: u64, label: &str| {
+            format!(
+                r#"<html><script src="/player.js"></script><script>
+                var player = videojs('player');
+                videos.push({{ src: "https://pf-storage4.premiumcdn.net/1/v.mp4?token={token}&expires={expires}", type: 'video/mp4', res: '1080', label: '{label}' }});
+                </script></html>"#
+            )
+        };
+
+        for (video_path, body) in [
+            ("/movie/abc123", page("aaa111", 1700000000, "1080p")),
+            ("/movie/def456", page("zzz999", 1800000000, "1080p")),
+            ("/movie/ghi789", page("aaa111", 1700000000, "Full HD")),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(video_path))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
         }
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let first = scraper.page_fingerprint("movie", "abc123").await.unwrap();
+        let second = scraper.page_fingerprint("movie", "def456").await.unwrap();
+        let changed = scraper.page_fingerprint("movie", "ghi789").await.unwrap();
+
+        assert_eq!(first.len(), 16);
+        assert_eq!(first, second);
+        assert_ne!(first, changed);
     }
 
     #[tokio::test]
-    async fn test_search_movie_empty_name() {
-        let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.search_movie("", None).await;
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(msg)) => {
-                assert!(msg.contains("empty"));
-            }
-            _ => panic!("Expected InvalidId error"),
+    async fn test_get_video_sources_with_sizes() {
+        let server = MockServer::start().await;
+        let page = format!(
+            r#"<html><script>
+            videos.push({{ src: "{0}/cdn/1080p.mp4?token=a", type: 'video/mp4', res: '1080', label: '1080p' }});
+            videos.push({{ src: "{0}/cdn/720p.mp4?token=b", type: 'video/mp4', res: '720', label: '720p' }});
+            videos.push({{ src: "{0}/cdn/480p.mp4?token=c", type: 'video/mp4', res: '480', label: '480p' }});
+            </script></html>"#,
+            server.uri()
+        );
+
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/cdn/1080p.mp4"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 2048]))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/cdn/720p.mp4"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 1024]))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/cdn/480p.mp4"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let sources = scraper
+            .get_video_sources_with_sizes("movie", "abc123")
+            .await
+            .unwrap();
+
+        assert_eq!(sources.len(), 3);
+        assert_eq!(sources[0].size_bytes, Some(2048));
+        assert_eq!(sources[1].size_bytes, Some(1024));
+        // Failed HEAD is tolerated
+        assert_eq!(sources[2].size_bytes, None);
+    }
+
+    #[tokio::test]
+    async fn test_stream_video_sources_with_sizes() {
+        let server = MockServer::start().await;
+        let page = format!(
+            r#"<html><script>
+            videos.push({{ src: "{0}/cdn/1080p.mp4?token=a", type: 'video/mp4', res: '1080', label: '1080p' }});
+            videos.push({{ src: "{0}/cdn/720p.mp4?token=b", type: 'video/mp4', res: '720', label: '720p' }});
+            videos.push({{ src: "{0}/cdn/480p.mp4?token=c", type: 'video/mp4', res: '480', label: '480p' }});
+            </script></html>"#,
+            server.uri()
+        );
+
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page))
+            .mount(&server)
+            .await;
+        // The slowest HEAD belongs to the first source in page order
+        let heads = [("1080p", 4096, 600), ("720p", 2048, 300), ("480p", 1024, 0)];
+        for (file, size, delay_ms) in heads {
+            Mock::given(method("HEAD"))
+                .and(path(format!("/cdn/{}.mp4", file)))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_bytes(vec![0u8; size])
+                        .set_delay(Duration::from_millis(delay_ms)),
+                )
+                .mount(&server)
+                .await;
+        }
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let sources: Vec<VideoSource> = scraper
+            .stream_video_sources_with_sizes("movie", "abc123")
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(sources.len(), 3);
+        // The fastest HEAD arrives first
+        assert_eq!(sources[0].resolution, 480);
+        for source in &sources {
+            let expected = match source.resolution {
+                1080 => 4096,
+                720 => 2048,
+                _ => 1024,
+            };
+            assert_eq!(source.size_bytes, Some(expected));
         }
     }
 
+    /// Mounts a video page with one 720p streaming source and a download page
+    async fn mount_video_with_download_page(server: &MockServer, download_page: &str) {
+        let video_page = r#"<html><script>
+            videos.push({ src: "https://cdn.premiumcdn.net/abc/720p.mp4?token=s", type: 'video/mp4', res: '720', label: '720p' });
+        </script></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(video_page))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .and(query_param("do", "download"))
+            .respond_with(ResponseTemplate::new(302).set_body_string(download_page))
+            .with_priority(1)
+            .mount(server)
+            .await;
+    }
+
     #[tokio::test]
-    async fn test_search_movie_whitespace_name() {
+    async fn test_resolve_best_prefers_original_when_available() {
+        let server = MockServer::start().await;
+        mount_video_with_download_page(
+            &server,
+            r#"<html><a href="https://pf-storage1.premiumcdn.net/orig?filename=Movie+2160p.mkv&token=o">Download</a></html>"#,
+        )
+        .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let options = ResolveOptions {
+            prefer_original: true,
+            ..Default::default()
+        };
+        let resolved = scraper
+            .resolve_best("movie", "abc123", &options)
+            .await
+            .unwrap();
+
+        assert_eq!(resolved.kind, SourceKind::Original);
+        assert_eq!(resolved.source.resolution, 2160);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_best_falls_back_to_streaming() {
+        let server = MockServer::start().await;
+        mount_video_with_download_page(&server, "<html><p>Gate page</p></html>").await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let options = ResolveOptions {
+            prefer_original: true,
+            ..Default::default()
+        };
+        let resolved = scraper
+            .resolve_best("movie", "abc123", &options)
+            .await
+            .unwrap();
+
+        assert_eq!(resolved.kind, SourceKind::Streaming);
+        assert_eq!(resolved.source.resolution, 720);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_best_streaming_by_default() {
+        let server = MockServer::start().await;
+        mount_video_with_download_page(&server, "<html></html>").await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let resolved = scraper
+            .resolve_best("movie", "abc123", &ResolveOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(resolved.kind, SourceKind::Streaming);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_best_skip_zero_resolution() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/movie/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<html><script>
+                var sources = [{ file: "https://cdn.premiumcdn.net/abc/master.m3u8?token=h" }];
+                </script></html>"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let resolved = scraper
+            .resolve_best("movie", "abc123", &ResolveOptions::default())
+            .await
+            .unwrap();
+        assert!(resolved.source.is_adaptive);
+
+        let options = ResolveOptions {
+            skip_zero_resolution: true,
+            ..Default::default()
+        };
+        let result = scraper.resolve_best("movie", "abc123", &options).await;
+        assert!(matches!(result, Err(PrehrajtoError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_series_info_empty_url() {
         let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.search_movie("   ", Some(2020)).await;
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(_)) => {}
-            _ => panic!("Expected InvalidId error"),
-        }
+        let result = scraper.get_series_info("https://prehraj.to/").await;
+        assert!(matches!(result, Err(PrehrajtoError::InvalidUrl(_))));
     }
 
     #[tokio::test]
-    async fn test_search_movie_all_empty_name() {
+    async fn test_get_uploader_videos() {
+        let server = MockServer::start().await;
+        let profile = r#"<html><body>
+            <header><a href="/profil/whovian/4821"><h3>Whovian CZ</h3></a></header>
+            <main>
+                <h2>Nahraná videa</h2>
+                <a href="/doctor-who-s07e05/63aba7f51f6cf"><div><div>00:44:20</div><div>1.7 GB</div></div><h3>Doctor Who S07E05</h3></a>
+                <a href="/doctor-who-s07e06/63aba7f51f6d0"><h3>Doctor Who S07E06</h3></a>
+            </main>
+        </body></html>"#;
+        Mock::given(method("GET"))
+            .and(path("/profil/whovian/4821"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(profile))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let videos = scraper
+            .get_uploader_videos("https://prehraj.to/profil/whovian/4821")
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = videos.iter().map(|v| v.video_id.as_str()).collect();
+        assert_eq!(ids, ["63aba7f51f6cf", "63aba7f51f6d0"]);
+        assert_eq!(videos[0].duration.as_deref(), Some("00:44:20"));
+
+        let result = scraper.get_uploader_videos(" ").await;
+        assert!(matches!(result, Err(PrehrajtoError::InvalidUrl(_))));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_cdn_hosts_discovers_new_host() {
+        let server = MockServer::start().await;
+        let homepage = r#"<html><body><main>
+            <section id="latest">
+                <a href="/sample-video/abc123"><h3>Sample Video</h3></a>
+            </section>
+        </main></body></html>"#;
+        let video_page = r#"<html><script>
+            videos.push({ src: "https://pf-storage7.rotated-vod.example/x/720p.mp4?token=t", type: 'video/mp4', res: '720', label: '720p' });
+            var ad = { file: "https://ads.adnetwork.example/preroll.mp4" };
+        </script></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(homepage))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/sample-video/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(video_page))
+            .mount(&server)
+            .await;
+
+        let download_page = r#"<html><a href="https://pf-storage7.rotated-vod.example/orig?filename=Movie.mkv&token=o">Download</a></html>"#;
+        Mock::given(method("GET"))
+            .and(path("/sample-video/abc123"))
+            .and(query_param("do", "download"))
+            .respond_with(ResponseTemplate::new(302).set_body_string(download_page))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        // JWPlayer sources on the new host, behind a redirect to that host
+        let jwplayer_page = r#"<html><script>
+            var sources = [{ file: "https://pf-storage7.rotated-vod.example/y/1080p.mp4?token=j", label: '1080p' }];
+        </script></html>"#;
+        Mock::given(method("GET"))
+            .and(path("/jw-video/def456"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header(
+                        "Location",
+                        "https://pf-storage7.rotated-vod.example/y/1080p.mp4",
+                    )
+                    .set_body_string(jwplayer_page),
+            )
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let other = mock_scraper(&server, ClientConfig::default());
+        let before = scraper.get_original_url("sample-video", "abc123").await;
+        assert!(before.is_err());
+
+        let hosts = scraper.refresh_cdn_hosts().await.unwrap();
+        assert_eq!(hosts, vec!["pf-storage7.rotated-vod.example"]);
+        let known = scraper.known_cdn_hosts();
+        assert!(known.contains(&"premiumcdn.net".to_string()));
+
+        let after = scraper.get_original_url("sample-video", "abc123").await;
+        assert!(after.unwrap().url.contains("pf-storage7."));
+        let direct = scraper.get_direct_url("jw-video", "def456").await.unwrap();
+        assert_eq!(
+            direct,
+            "https://pf-storage7.rotated-vod.example/y/1080p.mp4?token=j"
+        );
+        // Discovery is per scraper
+        let elsewhere = other.get_original_url("sample-video", "abc123").await;
+        assert!(elsewhere.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_cdn_hosts_returns_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        assert!(scraper.refresh_cdn_hosts().await.is_err());
+        assert_eq!(scraper.known_cdn_hosts(), vec!["premiumcdn.net"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_with_page_sort_and_filter() {
+        let server = MockServer::start().await;
+        let html = r#"<html><body><main>
+            <a href="/doctor-who-new/abc123"><div><div>2:00:00</div><div>5.3.2024</div></div><h3>Doctor Who New</h3></a>
+            <a href="/doctor-who-old/def456"><div><div>1:00:00</div><div>1.1.2020</div></div><h3>Doctor Who Old</h3></a>
+            <a href="/doctor-who-nodate/ghi789"><h3>Doctor Who Undated</h3></a>
+        </main></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/hledej/doctor+who"))
+            .and(query_param("vp-page", "2"))
+            .and(query_param("order", "newest"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(html))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let options = SearchOptions::new("doctor who")
+            .page(2)
+            .sort(SearchSort::Newest)
+            .match_all(true)
+            .filter(SearchFilter {
+                uploaded_after: chrono::NaiveDate::from_ymd_opt(2023, 1, 1),
+            });
+        let response = scraper.search_with(options).await.unwrap();
+
+        assert_eq!(response.query, "doctor who");
+        assert_eq!(response.page, 2);
+        assert_eq!(response.total_parsed, 3);
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].video_id, "abc123");
+    }
+
+    #[tokio::test]
+    async fn test_search_with_echoes_normalized_query() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<html><body><main><a href="/tom-jerry/abc123"><h3>Tom Jerry</h3></a></main></body></html>"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let response = scraper
+            .search_with(SearchOptions::new("  Tom &amp; Jerry "))
+            .await
+            .unwrap();
+        assert_eq!(response.query, "Tom & Jerry");
+    }
+
+    #[tokio::test]
+    async fn test_search_redirected_to_video_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/pelisky"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", "/pelisky/abc123"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pelisky/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<html><head>
+                <meta property="og:type" content="video.movie">
+                <meta property="og:title" content="Pelíšky | Prehraj.to">
+                <link rel="canonical" href="https://prehraj.to/pelisky/abc123">
+                </head><body><h1>Pelíšky</h1></body></html>"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let results = scraper.search("pelisky").await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Pelíšky");
+        assert_eq!(results[0].video_slug, "pelisky");
+        assert_eq!(results[0].video_id, "abc123");
+    }
+
+    #[tokio::test]
+    async fn test_search_with_empty_query() {
         let scraper = PrehrajtoScraper::new().unwrap();
-        let result = scraper.search_movie_all("", Some(2020)).await;
-        assert!(result.is_err());
-        match result {
-            Err(PrehrajtoError::InvalidId(msg)) => {
-                assert!(msg.contains("empty"));
-            }
-            _ => panic!("Expected InvalidId error"),
-        }
+        let result = scraper.search_with(SearchOptions::new("  ")).await;
+        assert!(matches!(result, Err(PrehrajtoError::InvalidId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_search_all_pages_loads_xhr_batches() {
+        let server = MockServer::start().await;
+        let first_page = r#"<html><body><main>
+            <a href="/video-one/abc123"><h3>Video One</h3></a>
+            <a href="/video-two/def456"><h3>Video Two</h3></a>
+        </main></body></html>"#;
+        // Second batch repeats one video, third batch repeats only known ones
+        let second_batch = r#"{"snippets":{"snippet-vp-items":"<a href=\"/video-two/def456\"><h3>Video Two</h3></a><a href=\"/video-three/ghi789\"><h3>Video Three</h3></a>"}}"#;
+        let third_batch = r#"<a href="/video-three/ghi789"><h3>Video Three</h3></a>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("vp-page", "2"))
+            .and(header("X-Requested-With", "XMLHttpRequest"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(second_batch))
+            .expect(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("vp-page", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(third_batch))
+            .expect(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(first_page))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let results = scraper.search_all_pages("video", None).await.unwrap();
+        let ids: Vec<&str> = results.iter().map(|v| v.video_id.as_str()).collect();
+        assert_eq!(ids, vec!["abc123", "def456", "ghi789"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_all_pages_respects_max_pages() {
+        let server = MockServer::start().await;
+        let first_page = r#"<html><body><main>
+            <a href="/video-one/abc123"><h3>Video One</h3></a>
+        </main></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("vp-page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"<a href="/video-two/def456"><h3>Video Two</h3></a>"#),
+            )
+            .expect(0)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(first_page))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let results = scraper.search_all_pages("video", Some(1)).await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_all_pages_verifies_xhr_batches() {
+        let server = MockServer::start().await;
+        let first_page = r#"<html><head><title>Video | Prehraj.to</title></head><body><main>
+            <a href="/video-one/abc123"><h3>Video One</h3></a>
+        </main></body></html>"#;
+        let portal = r#"<html><head><title>Wi-Fi Login</title></head><body>
+            <a href="/video-two/def456"><h3>Video Two</h3></a></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("vp-page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(portal))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(first_page))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(
+            &server,
+            ClientConfig {
+                verify_site_pages: true,
+                ..Default::default()
+            },
+        );
+        let result = scraper.search_all_pages("video", None).await;
+        assert!(
+            matches!(result, Err(PrehrajtoError::ParseError(msg)) if msg.contains("not a prehraj.to page"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_url_pasted_url_and_path() {
+        let server = MockServer::start().await;
+        let video_page = r#"<html><head>
+            <meta property="og:title" content="Doctor Who S07E05 | Prehraj.to">
+        </head><body></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/doctor-who-s07e05/63aba7f51f6cf"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(video_page))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+
+        let video = scraper
+            .resolve_url("https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf")
+            .await
+            .unwrap();
+        assert_eq!(video.name, "Doctor Who S07E05");
+        assert_eq!(video.video_id, "63aba7f51f6cf");
+
+        let video = scraper
+            .resolve_url("/doctor-who-s07e05/63aba7f51f6cf")
+            .await
+            .unwrap();
+        assert_eq!(video.video_slug, "doctor-who-s07e05");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_url_plain_query_searches() {
+        let server = MockServer::start().await;
+        let html = r#"<html><body><main>
+            <a href="/doctor-who/abc123"><h3>Doctor Who</h3></a>
+        </main></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/hledej/doctor%20who"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(html))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let video = scraper.resolve_url("doctor who").await.unwrap();
+        assert_eq!(video.video_id, "abc123");
+    }
+
+    #[tokio::test]
+    async fn test_search_recent_sorts_newest_and_caps() {
+        let server = MockServer::start().await;
+        let html = r#"<html><body><main>
+            <a href="/video-one/abc123"><h3>Video One</h3></a>
+            <a href="/video-two/def456"><h3>Video Two</h3></a>
+            <a href="/video-three/ghi789"><h3>Video Three</h3></a>
+        </main></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .and(query_param("order", "newest"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(html))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let results = scraper.search_recent("video", true, 2).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].video_id, "abc123");
+    }
+
+    const BATCH_SEARCH_HTML: &str = r#"<html><body><main>
+        <a href="/video-one/abc123"><h3>Video One</h3></a>
+        <a href="/video-two/def456"><h3>Video Two</h3></a>
+        <a href="/video-three/ghi789"><h3>Video Three</h3></a>
+    </main></body></html>"#;
+
+    #[tokio::test]
+    async fn test_search_with_direct_urls_fail_fast_aborts() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(BATCH_SEARCH_HTML))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/video-one/abc123"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/video-two/def456"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let options = BatchOptions { fail_fast: true };
+        let result = scraper.search_with_direct_urls("video", &options).await;
+        assert!(matches!(result, Err(PrehrajtoError::RateLimited)));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_direct_urls_fail_fast_aborts_when_blocked() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(BATCH_SEARCH_HTML))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/video-one/abc123"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("<h1>Access denied</h1>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/video-two/def456"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let options = BatchOptions { fail_fast: true };
+        let result = scraper.search_with_direct_urls("video", &options).await;
+        assert!(
+            matches!(result, Err(PrehrajtoError::HttpError(e)) if e.status() == Some(reqwest::StatusCode::FORBIDDEN))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_with_direct_urls_without_fail_fast() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(BATCH_SEARCH_HTML))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/video-two/def456"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<a href="https://pf-storage3.premiumcdn.net/two.mp4?token=a">Stáhnout</a>"#,
+            ))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let resolved = scraper
+            .search_with_direct_urls("video", &BatchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(resolved.len(), 3);
+        assert!(matches!(resolved[0].1, Err(PrehrajtoError::RateLimited)));
+        assert_eq!(
+            resolved[1].1.as_deref().unwrap(),
+            "https://pf-storage3.premiumcdn.net/two.mp4?token=a"
+        );
+        assert!(resolved[2].1.is_err());
+    }
+
+    fn unix_now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    /// Video page whose only CDN link expires `secs` from now
+    fn page_expiring_in(secs: u64) -> String {
+        let expires = unix_now() + secs;
+        format!(
+            r#"<a href="https://pf-storage3.premiumcdn.net/a.mp4?token=t&amp;expires={}">Stáhnout</a>"#,
+            expires
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_fresh_direct_url_far_expiry_resolves_once() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/video/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page_expiring_in(3600)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let url = scraper
+            .get_fresh_direct_url("video", "abc123", Duration::from_secs(600))
+            .await
+            .unwrap();
+        assert!(url.contains("expires="));
+    }
+
+    #[tokio::test]
+    async fn test_get_fresh_direct_url_near_expiry_re_resolves() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/video/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page_expiring_in(30)))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        let fresh = page_expiring_in(3600);
+        Mock::given(method("GET"))
+            .and(path("/video/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(fresh))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let url = scraper
+            .get_fresh_direct_url("video", "abc123", Duration::from_secs(600))
+            .await
+            .unwrap();
+        assert!(cdn_url_expires(&url).unwrap() > unix_now() + 600);
+    }
+
+    #[tokio::test]
+    async fn test_get_fresh_direct_url_still_stale_returned_anyway() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/video/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(page_expiring_in(30)))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let url = scraper
+            .get_fresh_direct_url("video", "abc123", Duration::from_secs(600))
+            .await
+            .unwrap();
+        assert!(cdn_url_expires(&url).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_contains_path() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/doctor-who/63aba7f51f6cf"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let error = scraper
+            .get_direct_url("doctor-who", "63aba7f51f6cf")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, PrehrajtoError::NotFound(_)));
+        assert!(
+            error.to_string().ends_with("for /doctor-who/63aba7f51f6cf"),
+            "{}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_each_invokes_callback_per_result() {
+        let server = MockServer::start().await;
+        let html = r#"<html><body><main>
+            <a href="/video-one/abc123"><h3>Video One</h3></a>
+            <a href="/video-two/def456"><h3>Video Two</h3></a>
+            <a href="/video-three/ghi789"><h3>Video Three</h3></a>
+        </main></body></html>"#;
+
+        Mock::given(method("GET"))
+            .and(path("/hledej/video"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(html))
+            .mount(&server)
+            .await;
+
+        let scraper = mock_scraper(&server, ClientConfig::default());
+        let mut names = Vec::new();
+        let count = scraper
+            .search_each("video", |video| names.push(video.name))
+            .await
+            .unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(names, vec!["Video One", "Video Two", "Video Three"]);
     }
 }
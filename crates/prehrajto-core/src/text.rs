@@ -0,0 +1,203 @@
+//! Text normalization helpers
+//!
+//! Shared helpers for comparing human-entered and scraped text
+//! (video names, search queries) and parsing displayed values.
+
+use std::time::Duration;
+
+/// Replaces a Czech/Slovak (and common Latin-1) accented letter with its base letter
+fn fold_char(c: char) -> char {
+    match c {
+        'á' | 'ä' | 'à' | 'â' | 'ã' | 'å' => 'a',
+        'č' | 'ç' | 'ć' => 'c',
+        'ď' => 'd',
+        'é' | 'ě' | 'ë' | 'è' | 'ê' => 'e',
+        'í' | 'ï' | 'ì' | 'î' => 'i',
+        'ľ' | 'ĺ' | 'ł' => 'l',
+        'ň' | 'ñ' | 'ń' => 'n',
+        'ó' | 'ö' | 'ô' | 'ò' | 'õ' | 'ő' => 'o',
+        'ř' | 'ŕ' => 'r',
+        'š' | 'ś' => 's',
+        'ť' => 't',
+        'ú' | 'ů' | 'ü' | 'ù' | 'û' | 'ű' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ž' | 'ź' | 'ż' => 'z',
+        other => other,
+    }
+}
+
+/// Folds text for loose comparison
+///
+/// Lowercases, strips diacritics, turns punctuation into spaces and
+/// collapses whitespace: `"Andělé  dobývají: Manhattan"` → `"andele dobyvaji manhattan"`.
+pub(crate) fn fold_text(text: &str) -> String {
+    let folded: String = text
+        .to_lowercase()
+        .chars()
+        .map(fold_char)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decodes common HTML entities (`&amp;`, `&lt;`, `&gt;`, `&quot;`, `&#39;`)
+pub(crate) fn decode_html_entities(text: &str) -> String {
+    text.replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+}
+
+/// Parses a displayed duration (`HH:MM:SS` or `MM:SS`)
+///
+/// Every part must be plain digits; returns `None` for anything else,
+/// including out-of-range minutes/seconds and totals overflowing `u64`.
+pub(crate) fn parse_duration(text: &str) -> Option<Duration> {
+    let parts: Vec<u64> = text
+        .trim()
+        .split(':')
+        .map(|p| {
+            p.bytes()
+                .all(|b| b.is_ascii_digit())
+                .then(|| p.parse::<u64>().ok())?
+        })
+        .collect::<Option<_>>()?;
+
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (*h, *m, *s),
+        [m, s] => (0, *m, *s),
+        _ => return None,
+    };
+    if seconds >= 60 || (parts.len() == 3 && minutes >= 60) {
+        return None;
+    }
+
+    let secs = hours
+        .checked_mul(3600)?
+        .checked_add(minutes.checked_mul(60)?)?
+        .checked_add(seconds)?;
+    Some(Duration::from_secs(secs))
+}
+
+/// Parses a displayed duration into whole seconds
+///
+/// Same formats as [`parse_duration`]: `"01:02:03"` → 3723, `"44:20"` → 2660.
+pub(crate) fn parse_duration_to_secs(text: &str) -> Option<u64> {
+    parse_duration(text).map(|duration| duration.as_secs())
+}
+
+/// Parses a displayed file size such as `1.7 GB` or `700 MB` into bytes
+///
+/// Accepts a decimal point or comma and the units B, KB, MB, GB and TB
+/// (case-insensitive, binary multiples). Returns `None` for anything else.
+pub(crate) fn parse_size_to_bytes(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.replace(',', ".").parse().ok()?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "B" => 1,
+        "KB" => 1 << 10,
+        "MB" => 1 << 20,
+        "GB" => 1 << 30,
+        "TB" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * multiplier as f64).round() as u64)
+}
+
+/// Normalizes a language code or name to its ISO 639-1 code
+///
+/// The site labels subtitles inconsistently (`cs`, `cze`, `CZE`, `eng1`,
+/// `Čeština`), so this folds case and diacritics, drops a numeric suffix and
+/// maps common ISO 639-2 codes and names: `"CZE"` → `"cs"`, `"eng1"` → `"en"`.
+/// Unknown values are returned folded but otherwise unchanged.
+pub(crate) fn normalize_language(code: &str) -> String {
+    let folded = fold_text(code);
+    let code = folded.trim_end_matches(|c: char| c.is_ascii_digit()).trim();
+
+    let iso = match code {
+        "cs" | "cz" | "cze" | "ces" | "czech" | "cesky" | "cestina" => "cs",
+        "sk" | "slo" | "slk" | "slovak" | "slovensky" | "slovencina" => "sk",
+        "en" | "eng" | "english" | "anglicky" => "en",
+        "de" | "ger" | "deu" | "german" | "nemecky" => "de",
+        "fr" | "fre" | "fra" | "french" => "fr",
+        "es" | "spa" | "spanish" => "es",
+        "it" | "ita" | "italian" => "it",
+        "pl" | "pol" | "polish" => "pl",
+        "hu" | "hun" | "hungarian" => "hu",
+        "ru" | "rus" | "russian" => "ru",
+        other => other,
+    };
+    iso.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_text() {
+        assert_eq!(
+            fold_text("Andělé  dobývají: Manhattan"),
+            "andele dobyvaji manhattan"
+        );
+        assert_eq!(fold_text("Příliš ŽLUŤOUČKÝ kůň"), "prilis zlutoucky kun");
+        assert_eq!(fold_text("Doctor.Who.S07E05"), "doctor who s07e05");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("00:44:20"), Some(Duration::from_secs(2660)));
+        assert_eq!(parse_duration("1:30:00"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("44:20"), Some(Duration::from_secs(2660)));
+        assert_eq!(parse_duration("00:61:00"), None);
+        assert_eq!(parse_duration("HD"), None);
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("18446744073709551615:00"), None);
+        assert_eq!(parse_duration("5124095576030432:00:00"), None);
+    }
+
+    #[test]
+    fn test_parse_duration_to_secs() {
+        assert_eq!(parse_duration_to_secs("01:02:03"), Some(3723));
+        assert_eq!(parse_duration_to_secs("44:20"), Some(2660));
+        assert_eq!(parse_duration_to_secs("00:00:00"), Some(0));
+        assert_eq!(parse_duration_to_secs("1:2:3"), Some(3723));
+        assert_eq!(parse_duration_to_secs("5:07"), Some(307));
+        assert_eq!(parse_duration_to_secs("1:+2:03"), None);
+        assert_eq!(parse_duration_to_secs("1::03"), None);
+        assert_eq!(parse_duration_to_secs("01:02:03:04"), None);
+        assert_eq!(parse_duration_to_secs("12"), None);
+    }
+
+    #[test]
+    fn test_parse_size_to_bytes() {
+        assert_eq!(parse_size_to_bytes("700 MB"), Some(700 << 20));
+        assert_eq!(parse_size_to_bytes("1.5 GB"), Some(3 << 29));
+        assert_eq!(parse_size_to_bytes("1,5 gb"), Some(3 << 29));
+        assert_eq!(parse_size_to_bytes("1,5 GB"), Some(3 << 29));
+        assert_eq!(parse_size_to_bytes("1.7 GB"), Some(1_825_361_101));
+        assert_eq!(parse_size_to_bytes("500 MB"), Some(500 << 20));
+        assert_eq!(parse_size_to_bytes("2GB"), Some(2 << 30));
+        assert_eq!(parse_size_to_bytes("512B"), Some(512));
+        assert_eq!(parse_size_to_bytes("big"), None);
+        assert_eq!(parse_size_to_bytes("12 parsecs"), None);
+        assert_eq!(parse_size_to_bytes("1.2.3 GB"), None);
+        assert_eq!(parse_size_to_bytes("GB"), None);
+    }
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("cs"), "cs");
+        assert_eq!(normalize_language("CZE"), "cs");
+        assert_eq!(normalize_language("Čeština"), "cs");
+        assert_eq!(normalize_language("eng1"), "en");
+        assert_eq!(normalize_language(" slk "), "sk");
+        assert_eq!(normalize_language("tlh"), "tlh");
+    }
+}
@@ -2,17 +2,30 @@
 //!
 //! Contains the main data structures used throughout the library.
 
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+use crate::text::{fold_text, normalize_language};
+use crate::url::build_video_url;
+
 /// Represents a video result from prehraj.to search
 ///
 /// Contains all metadata extracted from video cards in search results.
 /// All fields implement Serialize and Deserialize for Tauri compatibility.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VideoResult {
     /// Video title/name
     pub name: String,
 
+    /// Secondary title shown under the main one (e.g., the original title
+    /// of a dubbed film), if the card has one
+    #[serde(default)]
+    pub original_title: Option<String>,
+
     /// Full URL to the video page
     pub url: String,
 
@@ -28,18 +41,127 @@ pub struct VideoResult {
     /// Video duration in format "HH:MM:SS" (e.g., "00:44:20")
     pub duration: Option<String>,
 
+    /// `duration` in seconds, for time math (e.g., 2660 for "00:44:20")
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+
     /// Video quality indicator (e.g., "HD" or None)
     pub quality: Option<String>,
 
+    /// Resolution height from the card's badge (e.g., 1080 for "1080p",
+    /// 2160 for "4K"), if it shows one
+    #[serde(default)]
+    pub resolution: Option<u32>,
+
     /// File size as string (e.g., "1.7 GB")
     pub file_size: Option<String>,
+
+    /// `file_size` in bytes, for sorting and filtering (binary multiples,
+    /// so "1 GB" is 1073741824)
+    #[serde(default)]
+    pub file_size_bytes: Option<u64>,
+
+    /// Upload date as shown on the card (e.g., "24.12.2023")
+    pub upload_date: Option<String>,
+
+    /// 0-based position of the card on the page it was parsed from
+    #[serde(default)]
+    pub position: usize,
+}
+
+impl VideoResult {
+    /// Key identifying this video, for maps and deduplication
+    ///
+    /// Returns the `video_id`: re-uploads can share a slug, so the slug
+    /// alone never identifies a video.
+    pub fn unique_key(&self) -> &str {
+        &self.video_id
+    }
+
+    /// Get the shareable public page URL of the video
+    ///
+    /// Rebuilt from `video_slug` and `video_id` with [`build_video_url`], so
+    /// it never carries `?do=download` or other query noise, even if `url`
+    /// was altered.
+    pub fn web_url(&self) -> String {
+        build_video_url(&self.video_slug, &self.video_id)
+    }
+
+    /// Check whether two results refer to the same video
+    ///
+    /// The `video_id` is authoritative: when both results have one, they are
+    /// the same video exactly when the IDs match, regardless of name. If
+    /// either ID is missing, falls back to comparing the folded name
+    /// (case, diacritics and punctuation ignored) together with the duration,
+    /// which must be known on both sides.
+    pub fn is_same_as(&self, other: &VideoResult) -> bool {
+        let id = self.video_id.trim();
+        let other_id = other.video_id.trim();
+        if !id.is_empty() && !other_id.is_empty() {
+            return id == other_id;
+        }
+
+        match (&self.duration, &other.duration) {
+            (Some(duration), Some(other_duration)) => {
+                duration == other_duration && fold_text(&self.name) == fold_text(&other.name)
+            }
+            _ => false,
+        }
+    }
+
+    /// Parse the upload date into a structured date
+    ///
+    /// # Returns
+    /// `Some(date)` for the `dd.mm.yyyy` form, `None` if missing or unparseable
+    pub fn uploaded_date(&self) -> Option<NaiveDate> {
+        let raw = self.upload_date.as_deref()?.trim();
+        NaiveDate::parse_from_str(raw, "%d.%m.%Y").ok()
+    }
+
+    /// Check whether the video was uploaded after the given cutoff date
+    ///
+    /// # Returns
+    /// `Some(true)` if uploaded strictly after `cutoff`, `Some(false)` if on
+    /// or before it, `None` if the upload date couldn't be parsed
+    pub fn uploaded_after(&self, cutoff: NaiveDate) -> Option<bool> {
+        self.uploaded_date().map(|date| date > cutoff)
+    }
+}
+
+/// Stable identity of a video, for persisting saved videos compactly
+///
+/// Keeps only what identifies the video; volatile details (duration, size,
+/// quality) can be re-fetched with
+/// [`crate::PrehrajtoScraper::refresh_bookmark`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VideoBookmark {
+    /// Video title when bookmarked
+    pub name: String,
+    /// URL slug of the video
+    pub slug: String,
+    /// ID of the video
+    pub id: String,
+}
+
+impl From<&VideoResult> for VideoBookmark {
+    fn from(video: &VideoResult) -> Self {
+        Self {
+            name: video.name.clone(),
+            slug: video.video_slug.clone(),
+            id: video.video_id.clone(),
+        }
+    }
 }
 
 /// A single video quality source from the player
 ///
 /// Represents one quality variant (e.g., 720p, 1080p) extracted from
 /// the video page's JavaScript player initialization blocks.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Implements `Eq` and `Hash` over all fields, so sources can be
+/// deduplicated with a `HashSet`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VideoSource {
     /// Direct CDN URL for this quality variant
     pub url: String,
@@ -49,15 +171,167 @@ pub struct VideoSource {
     pub resolution: u32,
     /// Whether this is marked as the default quality
     pub is_default: bool,
+    /// Whether this is an adaptive manifest (HLS `.m3u8` / DASH `.mpd`)
+    ///
+    /// Adaptive sources have `resolution` 0, as they contain several qualities.
+    #[serde(default)]
+    pub is_adaptive: bool,
     /// File extension if known (e.g., "mp4", "mkv", "avi")
     pub format: Option<String>,
+    /// Whether the file is a packaged download bundling subtitles
+    #[serde(default)]
+    pub includes_subtitles: bool,
+    /// File size in bytes, if known (filled by a HEAD request)
+    #[serde(default)]
+    pub size_bytes: Option<u64>,
+}
+
+impl VideoSource {
+    /// Estimate how long downloading this source takes, in whole seconds
+    ///
+    /// Computes `size_bytes / bandwidth` rounded up. Returns `None` when the
+    /// size is unknown (see
+    /// [`crate::PrehrajtoScraper::get_video_sources_with_sizes`]) or the
+    /// bandwidth is zero.
+    pub fn estimated_download_secs(&self, bandwidth_bytes_per_sec: u64) -> Option<u64> {
+        if bandwidth_bytes_per_sec == 0 {
+            return None;
+        }
+        self.size_bytes
+            .map(|size| size.div_ceil(bandwidth_bytes_per_sec))
+    }
+
+    /// Get the quality of this source
+    ///
+    /// Returns `None` when the resolution is unknown (0), e.g. for
+    /// adaptive manifests.
+    pub fn quality(&self) -> Option<Quality> {
+        (self.resolution > 0).then(|| Quality::from_resolution(self.resolution))
+    }
+
+    /// Get the raw file extension of this source, lowercased
+    ///
+    /// Unlike [`VideoSource::format`], which only holds known video
+    /// containers, this returns whatever extension the `filename=` query
+    /// parameter or the last URL path segment carries (the parameter wins).
+    /// Returns `None` when neither has one.
+    pub fn file_extension(&self) -> Option<String> {
+        let extension_of = |name: &str| {
+            let (stem, ext) = name.rsplit_once('.')?;
+            let valid = !stem.is_empty()
+                && !ext.is_empty()
+                && ext.chars().all(|c| c.is_ascii_alphanumeric());
+            valid.then(|| ext.to_lowercase())
+        };
+
+        let url = self.url.split('#').next().unwrap_or(&self.url);
+        let (location, query) = match url.split_once('?') {
+            Some((location, query)) => (location, Some(query)),
+            None => (url, None),
+        };
+
+        let filename = query.and_then(|query| {
+            query
+                .split('&')
+                .find_map(|param| param.strip_prefix("filename="))
+        });
+        if let Some(filename) = filename {
+            let filename = urlencoding::decode(filename).unwrap_or_default();
+            if let Some(ext) = extension_of(&filename) {
+                return Some(ext);
+            }
+        }
+
+        // Skip the scheme and host so a bare domain isn't read as "net"
+        let path = location.split_once("://").map_or(location, |(_, rest)| {
+            rest.split_once('/').map_or("", |(_, path)| path)
+        });
+        path.rsplit('/').next().and_then(extension_of)
+    }
+}
+
+/// Video quality by resolution height
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quality {
+    /// 360p
+    P360,
+    /// 480p
+    P480,
+    /// 576p
+    P576,
+    /// 720p
+    P720,
+    /// 1080p
+    P1080,
+    /// 1440p
+    P1440,
+    /// 2160p (4K)
+    P2160,
+    /// Any other height
+    Other(u32),
+}
+
+impl Quality {
+    /// Map a resolution height to a quality
+    pub fn from_resolution(height: u32) -> Self {
+        match height {
+            360 => Self::P360,
+            480 => Self::P480,
+            576 => Self::P576,
+            720 => Self::P720,
+            1080 => Self::P1080,
+            1440 => Self::P1440,
+            2160 => Self::P2160,
+            other => Self::Other(other),
+        }
+    }
+
+    /// Get the resolution height in pixels
+    pub fn height(&self) -> u32 {
+        match self {
+            Self::P360 => 360,
+            Self::P480 => 480,
+            Self::P576 => 576,
+            Self::P720 => 720,
+            Self::P1080 => 1080,
+            Self::P1440 => 1440,
+            Self::P2160 => 2160,
+            Self::Other(height) => *height,
+        }
+    }
+
+    /// Get the tier this quality is grouped under
+    ///
+    /// Heights below 720 are SD, below 2160 HD (so 1440p counts as HD),
+    /// everything from 2160 up UHD.
+    pub fn tier(&self) -> QualityTier {
+        match self.height() {
+            0..720 => QualityTier::Sd,
+            720..2160 => QualityTier::Hd,
+            _ => QualityTier::Uhd,
+        }
+    }
+}
+
+/// Coarse quality group for UI menus, ordered from lowest to highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityTier {
+    /// Standard definition (below 720p)
+    Sd,
+    /// High definition (720p–1080p)
+    Hd,
+    /// Ultra high definition (2160p and up)
+    Uhd,
 }
 
 /// A subtitle track from the video page
 ///
 /// Represents a VTT subtitle file extracted from the video page's
 /// JavaScript player initialization blocks.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SubtitleTrack {
     /// Direct CDN URL for the VTT subtitle file
     pub url: String,
@@ -74,6 +348,7 @@ pub struct SubtitleTrack {
 /// Returned by [`crate::PrehrajtoScraper::get_video_page_data`] to avoid
 /// double-fetching the video page when both sources and subtitles are needed.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VideoPageData {
     /// Available video quality sources
     pub sources: Vec<VideoSource>,
@@ -81,6 +356,509 @@ pub struct VideoPageData {
     pub subtitles: Vec<SubtitleTrack>,
 }
 
+impl VideoPageData {
+    /// Check whether any video source was found
+    pub fn has_sources(&self) -> bool {
+        !self.sources.is_empty()
+    }
+
+    /// Check whether any subtitle track was found
+    pub fn has_subtitles(&self) -> bool {
+        !self.subtitles.is_empty()
+    }
+
+    /// Check whether the page yielded neither sources nor subtitles
+    pub fn is_empty(&self) -> bool {
+        !self.has_sources() && !self.has_subtitles()
+    }
+
+    /// Get the player's default source
+    ///
+    /// Returns the source flagged `is_default`, falling back to the one with
+    /// the highest resolution. `None` if there are no sources.
+    pub fn default_source(&self) -> Option<&VideoSource> {
+        self.sources
+            .iter()
+            .find(|s| s.is_default)
+            .or_else(|| self.sources.iter().max_by_key(|s| s.resolution))
+    }
+
+    /// Get progressive (single-file) sources
+    pub fn progressive_sources(&self) -> Vec<&VideoSource> {
+        self.sources.iter().filter(|s| !s.is_adaptive).collect()
+    }
+
+    /// Get adaptive (HLS/DASH manifest) sources
+    pub fn adaptive_sources(&self) -> Vec<&VideoSource> {
+        self.sources.iter().filter(|s| s.is_adaptive).collect()
+    }
+
+    /// Get all sources (mirrors) for a given resolution
+    ///
+    /// Some videos list several storage servers for the same quality; each
+    /// is kept as a separate source. Returns them in page order.
+    pub fn mirrors_for(&self, resolution: u32) -> Vec<&VideoSource> {
+        self.sources
+            .iter()
+            .filter(|s| s.resolution == resolution)
+            .collect()
+    }
+
+    /// Merge sources and subtitles from another parse of the same video
+    ///
+    /// Useful when combining several fetches (mobile and desktop pages, or a
+    /// refetch after a partial parse). Entries are matched by URL: new ones
+    /// are appended in `other`'s order, and for duplicates the entry with
+    /// more known details (format, resolution, size, language, label) is
+    /// kept, the existing one on a tie.
+    pub fn merge(&mut self, other: VideoPageData) {
+        let source_detail = |source: &VideoSource| {
+            [
+                source.format.is_some(),
+                source.resolution > 0,
+                source.size_bytes.is_some(),
+                !source.label.is_empty(),
+            ]
+            .into_iter()
+            .filter(|known| *known)
+            .count()
+        };
+        let track_detail = |track: &SubtitleTrack| {
+            usize::from(!track.language.is_empty()) + usize::from(!track.label.is_empty())
+        };
+
+        merge_by_url(&mut self.sources, other.sources, |s| &s.url, source_detail);
+        merge_by_url(
+            &mut self.subtitles,
+            other.subtitles,
+            |t| &t.url,
+            track_detail,
+        );
+    }
+
+    /// Pick a subtitle track by language preference
+    ///
+    /// Returns the first track matching the earliest language in `prefs`
+    /// (e.g. `&["cs", "en"]` for "Czech, else English"). Codes and names are
+    /// compared after normalizing to ISO 639-1, so `"cze"` matches a track
+    /// with language `"cs"`. Without a match, falls back to the track
+    /// flagged `is_default`, then the first track. `None` if there are no
+    /// subtitles.
+    pub fn select_subtitle(&self, prefs: &[&str]) -> Option<&SubtitleTrack> {
+        let languages: Vec<String> = self
+            .subtitles
+            .iter()
+            .map(|track| normalize_language(&track.language))
+            .collect();
+
+        prefs
+            .iter()
+            .map(|pref| normalize_language(pref))
+            .find_map(|pref| languages.iter().position(|language| *language == pref))
+            .map(|index| &self.subtitles[index])
+            .or_else(|| self.subtitles.iter().find(|track| track.is_default))
+            .or_else(|| self.subtitles.first())
+    }
+
+    /// Group sources by quality tier
+    ///
+    /// Sources within a tier keep page order. Sources with an unknown
+    /// resolution (such as adaptive manifests) are left out; tiers without
+    /// sources have no entry.
+    pub fn by_tier(&self) -> BTreeMap<QualityTier, Vec<&VideoSource>> {
+        let mut tiers: BTreeMap<QualityTier, Vec<&VideoSource>> = BTreeMap::new();
+        for source in &self.sources {
+            if let Some(quality) = source.quality() {
+                tiers.entry(quality.tier()).or_default().push(source);
+            }
+        }
+        tiers
+    }
+}
+
+/// Adds `theirs` to `ours`, matching entries by URL and keeping the more detailed one
+fn merge_by_url<T>(
+    ours: &mut Vec<T>,
+    theirs: Vec<T>,
+    url: impl Fn(&T) -> &str,
+    detail: impl Fn(&T) -> usize,
+) {
+    for entry in theirs {
+        match ours
+            .iter_mut()
+            .find(|existing| url(existing) == url(&entry))
+        {
+            Some(existing) if detail(&entry) > detail(existing) => *existing = entry,
+            Some(_) => {}
+            None => ours.push(entry),
+        }
+    }
+}
+
+/// Ordering of search results requested from prehraj.to
+///
+/// The values sent for each variant follow
+/// [observed site behaviour](crate::url#site-assumptions).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    /// Site default ordering (no sort parameter is sent)
+    #[default]
+    Relevance,
+    /// Most recently uploaded first
+    Newest,
+    /// Oldest uploads first
+    Oldest,
+    /// Largest files first
+    LargestSize,
+    /// Longest videos first
+    Longest,
+}
+
+impl SearchSort {
+    /// Get the value of the site's `order` query parameter for this sort
+    ///
+    /// [`SearchSort::Relevance`] maps to `"relevance"` for round-tripping,
+    /// but as the site default it is never sent in search URLs.
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Self::Relevance => "relevance",
+            Self::Newest => "newest",
+            Self::Oldest => "oldest",
+            Self::LargestSize => "largest",
+            Self::Longest => "longest",
+        }
+    }
+
+    /// Parse a sort from an `order` parameter value (see [`SearchSort::as_param`])
+    ///
+    /// Matching ignores ASCII case and surrounding whitespace. Returns `None`
+    /// for unknown values.
+    pub fn from_param(param: &str) -> Option<Self> {
+        let param = param.trim();
+        [
+            Self::Relevance,
+            Self::Newest,
+            Self::Oldest,
+            Self::LargestSize,
+            Self::Longest,
+        ]
+        .into_iter()
+        .find(|sort| sort.as_param().eq_ignore_ascii_case(param))
+    }
+}
+
+/// Options controlling how a search is sent to prehraj.to
+///
+/// Build with [`SearchOptions::new`] and the chained setters, then pass to
+/// [`crate::PrehrajtoScraper::search_with`]. [`Default::default`] gives the
+/// plain behaviour of [`crate::PrehrajtoScraper::search`].
+///
+/// # Example
+/// ```
+/// use prehrajto_core::{SearchOptions, SearchSort};
+/// let options = SearchOptions::new("doctor who")
+///     .page(2)
+///     .sort(SearchSort::Newest)
+///     .match_all(true);
+/// assert_eq!(options.page, Some(2));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchOptions {
+    /// Search query string
+    #[serde(default)]
+    pub query: String,
+
+    /// Results page to fetch, 1-based (default: first page)
+    #[serde(default)]
+    pub page: Option<u32>,
+
+    /// Result ordering (default: [`SearchSort::Relevance`])
+    #[serde(default)]
+    pub sort: SearchSort,
+
+    /// Require every search term to match (default: false)
+    ///
+    /// prehraj.to matches space-separated terms loosely. When enabled, the
+    /// terms are joined with `+`, which the site is assumed to treat as AND
+    /// (see [site assumptions](crate::url#site-assumptions)).
+    #[serde(default)]
+    pub match_all: bool,
+
+    /// Client-side filters applied after parsing (default: keep everything)
+    #[serde(default)]
+    pub filter: SearchFilter,
+}
+
+impl SearchOptions {
+    /// Create options for a query with all other settings at their defaults
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the results page to fetch (1-based)
+    pub fn page(mut self, page: u32) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Set the result ordering
+    pub fn sort(mut self, sort: SearchSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Require every search term to match
+    pub fn match_all(mut self, match_all: bool) -> Self {
+        self.match_all = match_all;
+        self
+    }
+
+    /// Set the client-side filters applied to parsed results
+    pub fn filter(mut self, filter: SearchFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// Results of a [`crate::PrehrajtoScraper::search_with`] call
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchResponse {
+    /// The query searched for, trimmed and normalized
+    ///
+    /// Lets responses to concurrent searches be matched to their queries.
+    #[serde(default)]
+    pub query: String,
+
+    /// Results that passed the filters, in page order
+    pub results: Vec<VideoResult>,
+
+    /// Page the results were taken from (1-based)
+    pub page: u32,
+
+    /// Number of results parsed from the page before filtering
+    pub total_parsed: usize,
+}
+
+/// Download options offered by the `?do=download` page
+///
+/// Returned by [`crate::PrehrajtoScraper::get_download_options`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadOptions {
+    /// The original uploaded file
+    pub original: VideoSource,
+    /// Packaged download with subtitles included, if the site offers one
+    pub packaged: Option<VideoSource>,
+}
+
+/// Which kind of file a [`ResolvedVideo`] points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    /// The original uploaded file from the download flow
+    Original,
+    /// A streaming quality variant from the player
+    Streaming,
+}
+
+/// Options for [`crate::PrehrajtoScraper::resolve_best`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolveOptions {
+    /// Try the original file first, falling back to streaming (default: false)
+    #[serde(default)]
+    pub prefer_original: bool,
+    /// Never pick a streaming source without a known resolution (default: false)
+    ///
+    /// Adaptive manifests and label-less files have resolution 0 and are
+    /// normally used only when no other source exists; with this set they
+    /// are never used and such videos fail with `NotFound`.
+    #[serde(default)]
+    pub skip_zero_resolution: bool,
+}
+
+/// Options for batch methods such as
+/// [`crate::PrehrajtoScraper::search_with_direct_urls`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchOptions {
+    /// Treat the first item's fetch as a connectivity canary (default: false)
+    ///
+    /// If it fails with a connection, rate-limit or 403 block error, the
+    /// whole batch is aborted with that error instead of attempting every item.
+    #[serde(default)]
+    pub fail_fast: bool,
+}
+
+/// The best file resolved for a video
+///
+/// Returned by [`crate::PrehrajtoScraper::resolve_best`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedVideo {
+    /// The chosen source
+    pub source: VideoSource,
+    /// Whether the source is the original file or a streaming variant
+    pub kind: SourceKind,
+}
+
+/// Series page info with aggregate episode data
+///
+/// Returned by [`crate::PrehrajtoScraper::get_series_info`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeriesInfo {
+    /// Series title from the page heading
+    pub title: String,
+    /// Episodes listed on the page
+    pub episodes: Vec<VideoResult>,
+    /// Number of episodes listed
+    pub episode_count: usize,
+    /// Sum of the episode durations that could be parsed
+    pub total_duration: Option<Duration>,
+}
+
+/// Metadata stated on a video's own page
+///
+/// Returned by [`crate::PrehrajtoScraper::get_video_metadata`]. Everything is
+/// optional, as pages don't always carry every piece.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VideoMetadata {
+    /// Title from page metadata, without the site-name suffix
+    pub title: Option<String>,
+    /// Duration in format "HH:MM:SS"
+    pub duration: Option<String>,
+    /// Maximum resolution the page text claims (e.g., 1080 for "Full HD 1080p")
+    pub reported_resolution: Option<u32>,
+    /// Last day the video stays available, if the page states one
+    #[serde(default)]
+    pub available_until: Option<NaiveDate>,
+    /// Display name of the uploader, if the page links their profile
+    #[serde(default)]
+    pub uploader: Option<String>,
+    /// Full URL of the uploader's profile page, listing their other uploads
+    /// (see [`crate::PrehrajtoScraper::get_uploader_videos`])
+    #[serde(default)]
+    pub uploader_url: Option<String>,
+}
+
+impl VideoMetadata {
+    /// Cross-check the reported resolution against parsed sources
+    ///
+    /// Returns `Some(true)` when the highest non-adaptive source resolution
+    /// equals the reported one, `Some(false)` when it differs, and `None`
+    /// when either side is unknown.
+    pub fn resolution_matches(&self, sources: &[VideoSource]) -> Option<bool> {
+        let reported = self.reported_resolution?;
+        let best = sources
+            .iter()
+            .filter(|s| !s.is_adaptive)
+            .map(|s| s.resolution)
+            .max()
+            .filter(|&r| r > 0)?;
+        Some(best == reported)
+    }
+}
+
+/// Everything known about a video from a single page fetch
+///
+/// Returned by [`crate::PrehrajtoScraper::get_full_video_info`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FullVideoInfo {
+    /// Metadata stated on the page
+    pub metadata: VideoMetadata,
+    /// Available video quality sources
+    pub sources: Vec<VideoSource>,
+    /// Available subtitle tracks
+    pub subtitles: Vec<SubtitleTrack>,
+    /// Direct CDN URL of the best source, if one was found
+    pub best_direct_url: Option<String>,
+}
+
+/// Video listings from the prehraj.to homepage
+///
+/// Returned by [`crate::PrehrajtoScraper::get_homepage_sections`].
+/// Sections missing from the page are left empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HomepageSections {
+    /// Most recently uploaded videos
+    pub latest: Vec<VideoResult>,
+    /// Most viewed videos
+    pub popular: Vec<VideoResult>,
+}
+
+/// Client-side filters applied to search results
+///
+/// Used by [`crate::PrehrajtoScraper::search_filtered`] and
+/// [`SearchOptions::filter`]. All filters are
+/// optional; the default filter keeps every result.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchFilter {
+    /// Keep only videos uploaded strictly after this date
+    ///
+    /// Results whose upload date can't be parsed are dropped.
+    pub uploaded_after: Option<NaiveDate>,
+}
+
+impl SearchFilter {
+    /// Check whether a video passes all configured filters
+    pub fn matches(&self, video: &VideoResult) -> bool {
+        if let Some(cutoff) = self.uploaded_after
+            && video.uploaded_after(cutoff) != Some(true)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Common Czech/English tokens that say nothing about which video it is
+const DEFAULT_STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "of", "and", "film", "movie", "cz", "sk", "en", "dabing", "titulky", "tit",
+    "cely", "hd", "full", "z", "na", "v", "ve", "se", "do",
+];
+
+/// Configuration for match scoring in [`crate::util::match_score`]
+///
+/// Used by [`crate::PrehrajtoScraper::search_movie_with_scoring`]. The
+/// default stop-word set covers common Czech/English filler tokens such as
+/// `"the"`, `"film"`, `"cz"` or `"dabing"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoringConfig {
+    /// Folded tokens that don't count toward the overlap
+    pub stop_words: HashSet<String>,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self::with_stop_words(DEFAULT_STOP_WORDS.iter().copied())
+    }
+}
+
+impl ScoringConfig {
+    /// Create a config with a custom stop-word set
+    ///
+    /// Words are folded (lowercased, diacritics stripped) so `"Dabing"`
+    /// and `"dabing"` are the same stop-word.
+    pub fn with_stop_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            stop_words: words
+                .into_iter()
+                .map(|word| fold_text(word.as_ref()))
+                .filter(|word| !word.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Check whether a folded token is a stop-word
+    pub fn is_stop_word(&self, token: &str) -> bool {
+        self.stop_words.contains(token)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,13 +867,19 @@ mod tests {
     fn test_video_result_serialization() {
         let video = VideoResult {
             name: "Test Video".to_string(),
+            original_title: None,
             url: "https://prehraj.to/test-video/abc123".to_string(),
             video_id: "abc123".to_string(),
             video_slug: "test-video".to_string(),
             download_url: "https://prehraj.to/test-video/abc123?do=download".to_string(),
             duration: Some("01:30:00".to_string()),
+            duration_secs: Some(5400),
             quality: Some("HD".to_string()),
+            resolution: Some(1080),
             file_size: Some("1.5 GB".to_string()),
+            file_size_bytes: Some(3 << 29),
+            upload_date: Some("24.12.2023".to_string()),
+            position: 3,
         };
 
         let json = serde_json::to_string(&video).expect("Serialization should succeed");
@@ -109,13 +893,19 @@ mod tests {
     fn test_video_result_with_none_fields() {
         let video = VideoResult {
             name: "Minimal Video".to_string(),
+            original_title: None,
             url: "https://prehraj.to/minimal/xyz789".to_string(),
             video_id: "xyz789".to_string(),
             video_slug: "minimal".to_string(),
             download_url: "https://prehraj.to/minimal/xyz789?do=download".to_string(),
             duration: None,
+            duration_secs: None,
             quality: None,
+            resolution: None,
             file_size: None,
+            file_size_bytes: None,
+            upload_date: None,
+            position: 0,
         };
 
         let json = serde_json::to_string(&video).expect("Serialization should succeed");
@@ -124,4 +914,598 @@ mod tests {
 
         assert_eq!(video, deserialized);
     }
+
+    fn video_with_date(upload_date: Option<&str>) -> VideoResult {
+        VideoResult {
+            name: "Dated Video".to_string(),
+            original_title: None,
+            url: "https://prehraj.to/dated/abc123".to_string(),
+            video_id: "abc123".to_string(),
+            video_slug: "dated".to_string(),
+            download_url: "https://prehraj.to/dated/abc123?do=download".to_string(),
+            duration: None,
+            duration_secs: None,
+            quality: None,
+            resolution: None,
+            file_size: None,
+            file_size_bytes: None,
+            upload_date: upload_date.map(str::to_string),
+            position: 0,
+        }
+    }
+
+    #[test]
+    fn test_uploaded_date_parsing() {
+        let video = video_with_date(Some("5.3.2024"));
+        assert_eq!(video.uploaded_date(), NaiveDate::from_ymd_opt(2024, 3, 5));
+
+        assert_eq!(video_with_date(Some("yesterday")).uploaded_date(), None);
+        assert_eq!(video_with_date(None).uploaded_date(), None);
+    }
+
+    #[test]
+    fn test_uploaded_after() {
+        let cutoff = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        let after = |date: &str| video_with_date(Some(date)).uploaded_after(cutoff);
+
+        assert_eq!(after("15.06.2024"), Some(true));
+        assert_eq!(after("31.12.2023"), Some(false));
+        assert_eq!(after("01.01.2024"), Some(false));
+        assert_eq!(after("32.13.2024"), None);
+    }
+
+    #[test]
+    fn test_search_filter_uploaded_after() {
+        let filter = SearchFilter {
+            uploaded_after: NaiveDate::from_ymd_opt(2024, 1, 1),
+        };
+
+        assert!(filter.matches(&video_with_date(Some("15.06.2024"))));
+        assert!(!filter.matches(&video_with_date(Some("31.12.2023"))));
+        assert!(!filter.matches(&video_with_date(None)));
+        assert!(SearchFilter::default().matches(&video_with_date(None)));
+    }
+
+    #[test]
+    fn test_search_options_builder() {
+        let filter = SearchFilter {
+            uploaded_after: NaiveDate::from_ymd_opt(2024, 1, 1),
+        };
+        let options = SearchOptions::new("doctor who")
+            .page(3)
+            .sort(SearchSort::LargestSize)
+            .match_all(true)
+            .filter(filter.clone());
+
+        assert_eq!(options.query, "doctor who");
+        assert_eq!(options.page, Some(3));
+        assert_eq!(options.sort, SearchSort::LargestSize);
+        assert!(options.match_all);
+        assert_eq!(options.filter, filter);
+
+        let defaults = SearchOptions::new("x");
+        assert_eq!(defaults.page, None);
+        assert_eq!(defaults.sort, SearchSort::Relevance);
+        assert!(!defaults.match_all);
+    }
+
+    #[test]
+    fn test_search_options_deserialize_defaults() {
+        let options: SearchOptions = serde_json::from_str(r#"{"query": "doctor who"}"#)
+            .expect("Deserialization should succeed");
+        assert_eq!(options, SearchOptions::new("doctor who"));
+    }
+
+    #[test]
+    fn test_resolve_options_deserialize_defaults() {
+        let options: ResolveOptions =
+            serde_json::from_str("{}").expect("Deserialization should succeed");
+        assert_eq!(options, ResolveOptions::default());
+    }
+
+    #[test]
+    fn test_batch_options_deserialize_defaults() {
+        let options: BatchOptions =
+            serde_json::from_str("{}").expect("Deserialization should succeed");
+        assert_eq!(options, BatchOptions::default());
+    }
+
+    #[test]
+    fn test_search_sort_params() {
+        let expected = [
+            (SearchSort::Relevance, "relevance"),
+            (SearchSort::Newest, "newest"),
+            (SearchSort::Oldest, "oldest"),
+            (SearchSort::LargestSize, "largest"),
+            (SearchSort::Longest, "longest"),
+        ];
+        for (sort, param) in expected {
+            assert_eq!(sort.as_param(), param);
+            assert_eq!(SearchSort::from_param(param), Some(sort));
+        }
+
+        assert_eq!(SearchSort::from_param(" Newest "), Some(SearchSort::Newest));
+        assert_eq!(SearchSort::from_param("largest_size"), None);
+        assert_eq!(SearchSort::from_param(""), None);
+    }
+
+    #[test]
+    fn test_video_source_hash_set_dedup() {
+        use std::collections::HashSet;
+
+        let source = VideoSource {
+            url: "https://cdn.premiumcdn.net/abc/1080p.mp4?token=x".to_string(),
+            label: "1080p".to_string(),
+            resolution: 1080,
+            is_default: true,
+            is_adaptive: false,
+            format: Some("mp4".to_string()),
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+        let other = VideoSource {
+            url: "https://cdn.premiumcdn.net/abc/720p.mp4?token=y".to_string(),
+            label: "720p".to_string(),
+            resolution: 720,
+            is_default: false,
+            is_adaptive: false,
+            format: Some("mp4".to_string()),
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+
+        let set: HashSet<VideoSource> = [source.clone(), other.clone(), source.clone()]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&source));
+        assert!(set.contains(&other));
+    }
+
+    #[test]
+    fn test_subtitle_track_hash_set_dedup() {
+        use std::collections::HashSet;
+
+        let track = SubtitleTrack {
+            url: "https://cdn.premiumcdn.net/subs/eng.vtt".to_string(),
+            language: "eng".to_string(),
+            label: "ENG".to_string(),
+            is_default: false,
+        };
+
+        let set: HashSet<SubtitleTrack> = [track.clone(), track].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_mirrors_for() {
+        let source = |host: &str, resolution: u32| VideoSource {
+            url: format!("https://{}.premiumcdn.net/abc/{}p.mp4", host, resolution),
+            label: format!("{}p", resolution),
+            resolution,
+            is_default: false,
+            is_adaptive: false,
+            format: Some("mp4".to_string()),
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+        let data = VideoPageData {
+            sources: vec![
+                source("pf-storage1", 1080),
+                source("pf-storage1", 720),
+                source("pf-storage2", 1080),
+            ],
+            subtitles: vec![],
+        };
+
+        let mirrors = data.mirrors_for(1080);
+        assert_eq!(mirrors.len(), 2);
+        assert!(mirrors[0].url.contains("pf-storage1"));
+        assert!(mirrors[1].url.contains("pf-storage2"));
+        assert_eq!(data.mirrors_for(720).len(), 1);
+        assert!(data.mirrors_for(2160).is_empty());
+    }
+
+    #[test]
+    fn test_is_same_as_same_id_different_name() {
+        let a = video_with_date(None);
+        let mut b = video_with_date(None);
+        b.name = "Completely Different Name".to_string();
+        assert!(a.is_same_as(&b));
+    }
+
+    #[test]
+    fn test_is_same_as_different_id() {
+        let a = video_with_date(None);
+        let mut b = video_with_date(None);
+        b.video_id = "def456".to_string();
+        assert!(!a.is_same_as(&b));
+    }
+
+    #[test]
+    fn test_web_url_rebuilt_from_slug_and_id() {
+        let mut video = video_with_date(None);
+        video.url = format!("{}?do=download&utm_source=x", video.url);
+        assert_eq!(
+            video.web_url(),
+            build_video_url(&video.video_slug, &video.video_id)
+        );
+        assert!(!video.web_url().contains('?'));
+    }
+
+    #[test]
+    fn test_unique_key_distinguishes_shared_slug() {
+        use std::collections::HashMap;
+
+        let a = video_with_date(None);
+        let mut b = video_with_date(None);
+        b.video_id = "def456".to_string();
+        assert_eq!(a.video_slug, b.video_slug);
+
+        let mut by_key: HashMap<&str, &VideoResult> = HashMap::new();
+        for video in [&a, &b, &a] {
+            by_key.entry(video.unique_key()).or_insert(video);
+        }
+        assert_eq!(by_key.len(), 2);
+        assert_eq!(by_key[a.unique_key()].video_id, a.video_id);
+        assert_eq!(by_key[b.unique_key()].video_id, "def456");
+    }
+
+    #[test]
+    fn test_is_same_as_name_and_duration_fallback() {
+        let mut a = video_with_date(None);
+        a.video_id = String::new();
+        a.name = "Andělé dobývají Manhattan".to_string();
+        a.duration = Some("00:44:20".to_string());
+
+        let mut b = video_with_date(None);
+        b.video_id = "xyz789".to_string();
+        b.name = "andele  DOBYVAJI manhattan".to_string();
+        b.duration = Some("00:44:20".to_string());
+        assert!(a.is_same_as(&b));
+
+        // Different duration → not the same
+        b.duration = Some("00:45:00".to_string());
+        assert!(!a.is_same_as(&b));
+
+        // Unknown duration → can't confirm
+        b.duration = None;
+        assert!(!a.is_same_as(&b));
+    }
+
+    #[test]
+    fn test_quality_tier() {
+        assert_eq!(Quality::from_resolution(480).tier(), QualityTier::Sd);
+        assert_eq!(Quality::from_resolution(576).tier(), QualityTier::Sd);
+        assert_eq!(Quality::from_resolution(720).tier(), QualityTier::Hd);
+        assert_eq!(Quality::from_resolution(1440).tier(), QualityTier::Hd);
+        assert_eq!(Quality::from_resolution(2160).tier(), QualityTier::Uhd);
+        assert_eq!(Quality::from_resolution(4320), Quality::Other(4320));
+        assert_eq!(Quality::Other(4320).tier(), QualityTier::Uhd);
+    }
+
+    #[test]
+    fn test_by_tier_groups_sources() {
+        let source = |resolution: u32, is_adaptive: bool| VideoSource {
+            url: format!("https://cdn.example/{}.mp4", resolution),
+            label: format!("{}p", resolution),
+            resolution,
+            is_default: false,
+            is_adaptive,
+            format: None,
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+        let data = VideoPageData {
+            sources: vec![
+                source(1080, false),
+                source(360, false),
+                source(2160, false),
+                source(720, false),
+                source(480, false),
+                source(0, true),
+            ],
+            subtitles: vec![],
+        };
+
+        let tiers = data.by_tier();
+        let heights =
+            |tier: QualityTier| -> Vec<u32> { tiers[&tier].iter().map(|s| s.resolution).collect() };
+        assert_eq!(
+            tiers.keys().copied().collect::<Vec<_>>(),
+            vec![QualityTier::Sd, QualityTier::Hd, QualityTier::Uhd]
+        );
+        assert_eq!(heights(QualityTier::Sd), vec![360, 480]);
+        assert_eq!(heights(QualityTier::Hd), vec![1080, 720]);
+        assert_eq!(heights(QualityTier::Uhd), vec![2160]);
+    }
+
+    #[test]
+    fn test_progressive_and_adaptive_sources() {
+        let source = |url: &str, resolution: u32, is_adaptive: bool| VideoSource {
+            url: url.to_string(),
+            label: "x".to_string(),
+            resolution,
+            is_default: false,
+            is_adaptive,
+            format: None,
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+        let data = VideoPageData {
+            sources: vec![
+                source("https://cdn.premiumcdn.net/master.m3u8", 0, true),
+                source("https://cdn.premiumcdn.net/720p.mp4", 720, false),
+                source("https://cdn.premiumcdn.net/1080p.mp4", 1080, false),
+            ],
+            subtitles: vec![],
+        };
+
+        assert_eq!(data.progressive_sources().len(), 2);
+        let adaptive = data.adaptive_sources();
+        assert_eq!(adaptive.len(), 1);
+        assert!(adaptive[0].url.ends_with(".m3u8"));
+    }
+
+    #[test]
+    fn test_video_page_data_empty() {
+        let data = VideoPageData {
+            sources: vec![],
+            subtitles: vec![],
+        };
+
+        assert!(!data.has_sources());
+        assert!(!data.has_subtitles());
+        assert!(data.is_empty());
+        assert!(data.default_source().is_none());
+    }
+
+    #[test]
+    fn test_video_page_data_default_source() {
+        let source = |resolution: u32, is_default: bool| VideoSource {
+            url: format!("https://cdn.premiumcdn.net/abc/{}p.mp4", resolution),
+            label: format!("{}p", resolution),
+            resolution,
+            is_default,
+            is_adaptive: false,
+            format: Some("mp4".to_string()),
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+        let mut data = VideoPageData {
+            sources: vec![source(480, false), source(720, true), source(1080, false)],
+            subtitles: vec![SubtitleTrack {
+                url: "https://cdn.premiumcdn.net/subs/eng.vtt".to_string(),
+                language: "eng".to_string(),
+                label: "ENG".to_string(),
+                is_default: false,
+            }],
+        };
+
+        assert!(data.has_sources());
+        assert!(data.has_subtitles());
+        assert!(!data.is_empty());
+        assert_eq!(data.default_source().map(|s| s.resolution), Some(720));
+
+        // No source flagged → highest resolution
+        data.sources[1].is_default = false;
+        assert_eq!(data.default_source().map(|s| s.resolution), Some(1080));
+
+        // Subtitles only → not empty, but no sources
+        data.sources.clear();
+        assert!(!data.has_sources());
+        assert!(!data.is_empty());
+    }
+
+    #[test]
+    fn test_video_bookmark_round_trip() {
+        let video = VideoResult {
+            name: "Doctor Who S07E05".to_string(),
+            original_title: None,
+            url: "https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf".to_string(),
+            video_id: "63aba7f51f6cf".to_string(),
+            video_slug: "doctor-who-s07e05".to_string(),
+            download_url: "https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf?do=download"
+                .to_string(),
+            duration: Some("00:44:20".to_string()),
+            duration_secs: Some(2660),
+            quality: Some("HD".to_string()),
+            resolution: None,
+            file_size: Some("1.7 GB".to_string()),
+            file_size_bytes: Some(1_825_361_101),
+            upload_date: None,
+            position: 3,
+        };
+
+        let bookmark = VideoBookmark::from(&video);
+        let json = serde_json::to_string(&bookmark).unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":"Doctor Who S07E05","slug":"doctor-who-s07e05","id":"63aba7f51f6cf"}"#
+        );
+        let restored: VideoBookmark = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, bookmark);
+    }
+
+    #[test]
+    fn test_video_page_data_merge() {
+        let source = |url: &str, format: Option<&str>| VideoSource {
+            url: url.to_string(),
+            label: "720p".to_string(),
+            resolution: 720,
+            is_default: false,
+            is_adaptive: false,
+            format: format.map(str::to_string),
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+        let track = |url: &str, language: &str| SubtitleTrack {
+            url: url.to_string(),
+            language: language.to_string(),
+            label: "CZE".to_string(),
+            is_default: false,
+        };
+
+        // Desktop page: bare 720p source, Czech subtitles without srclang
+        let mut data = VideoPageData {
+            sources: vec![source("https://cdn/720.mp4", None)],
+            subtitles: vec![track("https://cdn/cze.vtt", "")],
+        };
+        // Mobile page: the same source with its format, plus 1080p and English
+        data.merge(VideoPageData {
+            sources: vec![
+                source("https://cdn/1080.mp4", Some("mp4")),
+                source("https://cdn/720.mp4", Some("mp4")),
+            ],
+            subtitles: vec![
+                track("https://cdn/cze.vtt", "cs"),
+                track("https://cdn/eng.vtt", "en"),
+            ],
+        });
+
+        let urls: Vec<&str> = data.sources.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(urls, ["https://cdn/720.mp4", "https://cdn/1080.mp4"]);
+        assert_eq!(data.sources[0].format.as_deref(), Some("mp4"));
+        let languages: Vec<&str> = data.subtitles.iter().map(|t| t.language.as_str()).collect();
+        assert_eq!(languages, ["cs", "en"]);
+
+        // Poorer duplicates don't replace richer entries
+        data.merge(VideoPageData {
+            sources: vec![source("https://cdn/720.mp4", None)],
+            subtitles: vec![track("https://cdn/eng.vtt", "")],
+        });
+        assert_eq!(data.sources.len(), 2);
+        assert_eq!(data.sources[0].format.as_deref(), Some("mp4"));
+        assert_eq!(data.subtitles[1].language, "en");
+    }
+
+    #[test]
+    fn test_select_subtitle() {
+        let track = |language: &str, is_default: bool| SubtitleTrack {
+            url: format!("https://cdn.premiumcdn.net/subs/{}.vtt", language),
+            language: language.to_string(),
+            label: language.to_uppercase(),
+            is_default,
+        };
+        let mut data = VideoPageData {
+            sources: Vec::new(),
+            subtitles: vec![track("eng", false), track("cze", false), track("ger", true)],
+        };
+        let language = |data: &VideoPageData| {
+            data.select_subtitle(&["cs", "en"])
+                .map(|track| track.language.clone())
+        };
+
+        // Czech present → picked over English
+        assert_eq!(language(&data).as_deref(), Some("cze"));
+
+        // No Czech, English present → English
+        data.subtitles.remove(1);
+        assert_eq!(language(&data).as_deref(), Some("eng"));
+
+        // Neither → default-flagged track, then the first track
+        data.subtitles.remove(0);
+        data.subtitles.push(track("fre", false));
+        assert_eq!(language(&data).as_deref(), Some("ger"));
+        data.subtitles[0].is_default = false;
+        assert_eq!(language(&data).as_deref(), Some("ger"));
+        data.subtitles.swap(0, 1);
+        assert_eq!(language(&data).as_deref(), Some("fre"));
+
+        data.subtitles.clear();
+        assert_eq!(data.select_subtitle(&["cs"]), None);
+    }
+
+    #[test]
+    fn test_video_metadata_resolution_matches() {
+        let source = |resolution: u32| VideoSource {
+            url: format!("https://cdn.premiumcdn.net/abc/{}p.mp4", resolution),
+            label: format!("{}p", resolution),
+            resolution,
+            is_default: false,
+            is_adaptive: false,
+            format: Some("mp4".to_string()),
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+        let metadata = VideoMetadata {
+            reported_resolution: Some(1080),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            metadata.resolution_matches(&[source(720), source(1080)]),
+            Some(true)
+        );
+        assert_eq!(metadata.resolution_matches(&[source(720)]), Some(false));
+        assert_eq!(metadata.resolution_matches(&[]), None);
+        assert_eq!(
+            VideoMetadata::default().resolution_matches(&[source(720)]),
+            None
+        );
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_video_result_json_schema() {
+        let schema = schemars::schema_for!(VideoResult);
+        let object = schema.schema.object.as_ref().unwrap();
+
+        for field in ["name", "url", "video_id", "download_url", "duration"] {
+            assert!(object.properties.contains_key(field), "missing {}", field);
+        }
+        assert!(object.required.contains("name"));
+        assert!(!object.required.contains("duration"));
+    }
+
+    #[test]
+    fn test_estimated_download_secs() {
+        let mut source = VideoSource {
+            url: "https://cdn.premiumcdn.net/abc/1080p.mp4".to_string(),
+            label: "1080p".to_string(),
+            resolution: 1080,
+            is_default: true,
+            is_adaptive: false,
+            format: Some("mp4".to_string()),
+            includes_subtitles: false,
+            size_bytes: Some(1_000_000_000),
+        };
+
+        // 1 GB at 10 MB/s
+        assert_eq!(source.estimated_download_secs(10_000_000), Some(100));
+        // Partial seconds round up
+        assert_eq!(source.estimated_download_secs(300_000_000), Some(4));
+        assert_eq!(source.estimated_download_secs(0), None);
+
+        source.size_bytes = None;
+        assert_eq!(source.estimated_download_secs(10_000_000), None);
+    }
+
+    #[test]
+    fn test_file_extension() {
+        let mut source = VideoSource {
+            url: "https://cdn.premiumcdn.net/abc/Movie.MP4?token=x".to_string(),
+            label: "1080p".to_string(),
+            resolution: 1080,
+            is_default: true,
+            is_adaptive: false,
+            format: None,
+            includes_subtitles: false,
+            size_bytes: None,
+        };
+        assert_eq!(source.file_extension().as_deref(), Some("mp4"));
+
+        source.url = "https://cdn.premiumcdn.net/123/abc?filename=Movie+2160p.mkv&token=x".into();
+        assert_eq!(source.file_extension().as_deref(), Some("mkv"));
+
+        // Not limited to known video containers
+        source.url = "https://cdn.premiumcdn.net/123/subs.srt".into();
+        assert_eq!(source.file_extension().as_deref(), Some("srt"));
+
+        source.url = "https://cdn.premiumcdn.net/123/abc?token=x".into();
+        assert_eq!(source.file_extension(), None);
+        source.url = "https://cdn.premiumcdn.net".into();
+        assert_eq!(source.file_extension(), None);
+    }
 }
@@ -1,11 +1,36 @@
 //! URL helper functions for prehraj.to
 //!
 //! Provides functions for building video, download, and search URLs.
+//!
+//! # Site assumptions
+//!
+//! prehraj.to does not document its search syntax or query parameters. The
+//! `+` term separator, the `vp-page` and `order` parameters and the XHR
+//! listing used by the search builders follow observed site behaviour and
+//! may change without notice.
+
+use crate::text::decode_html_entities;
+use crate::types::{SearchOptions, SearchSort};
 
 const BASE_URL: &str = "https://prehraj.to";
 
+/// Query parameter selecting the search results page
+const PAGE_PARAM: &str = "vp-page";
+
+/// Query parameter selecting the search result ordering
+const SORT_PARAM: &str = "order";
+
+/// Slug used in place of an empty one, so paths stay well-formed
+const PLACEHOLDER_SLUG: &str = "video";
+
+/// Query parameters required by the CDN to serve a file
+const CDN_REQUIRED_PARAMS: &[&str] = &["token", "expires", "filename"];
+
 /// Builds the full video page URL from slug and ID
 ///
+/// An empty or whitespace-only slug is replaced by `video`, since the site
+/// resolves videos by ID and `//{id}` isn't a valid page path.
+///
 /// # Arguments
 /// * `slug` - URL-friendly video slug (e.g., "doctor-who-s07e05-andele-dobyvaji-manhattan")
 /// * `id` - Unique video ID (e.g., "63aba7f51f6cf")
@@ -20,6 +45,11 @@ const BASE_URL: &str = "https://prehraj.to";
 /// assert_eq!(url, "https://prehraj.to/test-video/abc123");
 /// ```
 pub fn build_video_url(slug: &str, id: &str) -> String {
+    let slug = if slug.trim().is_empty() {
+        PLACEHOLDER_SLUG
+    } else {
+        slug
+    };
     format!("{}/{}/{}", BASE_URL, slug, id)
 }
 
@@ -46,7 +76,8 @@ pub fn build_download_url(slug: &str, id: &str) -> String {
 
 /// Builds the search URL for a given query
 ///
-/// URL encodes the query and constructs the search URL.
+/// Normalizes the query with [`normalize_search_query`] and URL encodes it,
+/// so text copied from HTML or an existing URL isn't double-encoded.
 ///
 /// # Arguments
 /// * `query` - Search query string
@@ -61,13 +92,168 @@ pub fn build_download_url(slug: &str, id: &str) -> String {
 /// assert_eq!(url, "https://prehraj.to/hledej/doctor%20who");
 /// ```
 pub fn build_search_url(query: &str) -> String {
-    let encoded = urlencoding::encode(query);
+    let normalized = normalize_search_query(query);
+    let encoded = urlencoding::encode(&normalized);
     format!("{}/hledej/{}", BASE_URL, encoded)
 }
 
+/// Normalizes a raw search query before encoding
+///
+/// Rules, applied in order:
+/// 1. HTML entities are decoded (`&amp;` → `&`, `&quot;` → `"`, …)
+/// 2. If the query contains `%XX` escapes and decodes to valid UTF-8, it is
+///    treated as already percent-encoded and decoded once
+/// 3. Everything else is left as typed (a lone `%` or `100%` is kept)
+///
+/// `+` is never treated as an encoded space, as it may be part of the query.
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::normalize_search_query;
+/// assert_eq!(normalize_search_query("Tom &amp; Jerry"), "Tom & Jerry");
+/// assert_eq!(normalize_search_query("doctor%20who"), "doctor who");
+/// assert_eq!(normalize_search_query("100% pure"), "100% pure");
+/// ```
+pub fn normalize_search_query(query: &str) -> String {
+    let decoded = decode_html_entities(query);
+
+    if has_percent_escape(&decoded)
+        && let Ok(unescaped) = urlencoding::decode(&decoded)
+    {
+        return unescaped.into_owned();
+    }
+
+    decoded
+}
+
+/// Checks if text contains at least one `%XX` escape sequence
+fn has_percent_escape(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes
+        .windows(3)
+        .any(|w| w[0] == b'%' && w[1].is_ascii_hexdigit() && w[2].is_ascii_hexdigit())
+}
+
+/// Builds the search URL for a given query with search options applied
+///
+/// With `match_all` enabled, the query is split on whitespace, each term is
+/// URL encoded separately and the terms are joined with a literal `+`.
+/// A page other than the first and a non-default sort are appended as query
+/// parameters (`vp-page` and `order`, see [site assumptions](self#site-assumptions)).
+/// The `query` field of `options` is ignored in favour of the argument, and
+/// client-side filters don't affect the URL.
+/// Without options this is identical to [`build_search_url`].
+///
+/// # Arguments
+/// * `query` - Search query string
+/// * `options` - Search options to apply
+///
+/// # Returns
+/// Full search URL with encoded query
+///
+/// # Example
+/// ```
+/// use prehrajto_core::{SearchOptions, SearchSort};
+/// use prehrajto_core::url::build_search_url_with_options;
+/// let options = SearchOptions::default().match_all(true);
+/// let url = build_search_url_with_options("doctor who", &options);
+/// assert_eq!(url, "https://prehraj.to/hledej/doctor+who");
+///
+/// let options = SearchOptions::default().page(2).sort(SearchSort::Newest);
+/// let url = build_search_url_with_options("doctor who", &options);
+/// assert_eq!(url, "https://prehraj.to/hledej/doctor%20who?vp-page=2&order=newest");
+/// ```
+pub fn build_search_url_with_options(query: &str, options: &SearchOptions) -> String {
+    let base = if options.match_all {
+        let normalized = normalize_search_query(query);
+        let terms: Vec<String> = normalized
+            .split_whitespace()
+            .map(|term| urlencoding::encode(term).into_owned())
+            .collect();
+        format!("{}/hledej/{}", BASE_URL, terms.join("+"))
+    } else {
+        build_search_url(query)
+    };
+
+    let mut params: Vec<String> = Vec::new();
+    if let Some(page) = options.page.filter(|&p| p > 1) {
+        params.push(format!("{}={}", PAGE_PARAM, page));
+    }
+    // Relevance is the site default, so no parameter is sent for it
+    if options.sort != SearchSort::Relevance {
+        params.push(format!("{}={}", SORT_PARAM, options.sort.as_param()));
+    }
+
+    if params.is_empty() {
+        base
+    } else {
+        format!("{}?{}", base, params.join("&"))
+    }
+}
+
+/// Builds the search URL for a given results page
+///
+/// Pages after the first add the `vp-page` parameter; page 1 (and 0) give
+/// exactly the URL of [`build_search_url`].
+///
+/// # Arguments
+/// * `query` - Search query string
+/// * `page` - Results page, 1-based
+///
+/// # Returns
+/// Full search URL with encoded query
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::build_search_url_paged;
+/// let url = build_search_url_paged("doctor who", 2);
+/// assert_eq!(url, "https://prehraj.to/hledej/doctor%20who?vp-page=2");
+/// assert_eq!(build_search_url_paged("doctor who", 1), "https://prehraj.to/hledej/doctor%20who");
+/// ```
+pub fn build_search_url_paged(query: &str, page: u32) -> String {
+    build_search_url_with_options(query, &SearchOptions::default().page(page))
+}
+
+/// Converts a full prehraj.to URL or a path into a site-relative path
+///
+/// Strips the base URL if present and ensures a leading slash.
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::site_path;
+/// assert_eq!(site_path("https://prehraj.to/serialy/doctor-who"), "/serialy/doctor-who");
+/// assert_eq!(site_path("serialy/doctor-who"), "/serialy/doctor-who");
+/// ```
+pub fn site_path(url: &str) -> String {
+    let path = url.trim().strip_prefix(BASE_URL).unwrap_or(url.trim());
+    format!("/{}", path.trim_start_matches('/'))
+}
+
+/// Converts a site-relative path into a full prehraj.to URL
+///
+/// The inverse of [`site_path`]; full URLs (on any host) are returned trimmed
+/// but otherwise unchanged.
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::site_url;
+/// assert_eq!(site_url("/profil/uploader/123"), "https://prehraj.to/profil/uploader/123");
+/// assert_eq!(site_url("https://prehraj.to/profil/uploader/123"), "https://prehraj.to/profil/uploader/123");
+/// ```
+pub fn site_url(path: &str) -> String {
+    let path = path.trim();
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else {
+        format!("{}{}", BASE_URL, site_path(path))
+    }
+}
+
 /// Extracts video slug and ID from a URL path
 ///
 /// Parses URLs in format `/{slug}/{id}` and returns both components.
+/// Everything from the first `?` or `#` on is ignored, so query strings
+/// (even ones containing `/`) and fragments don't leak into the ID.
 ///
 /// # Arguments
 /// * `url` - URL string or path (e.g., "/test-video/abc123" or "https://prehraj.to/test-video/abc123")
@@ -87,36 +273,380 @@ pub fn extract_video_info(url: &str) -> Option<(String, String)> {
         .strip_prefix(BASE_URL)
         .unwrap_or(url);
     
-    // Remove leading slash and any query parameters
+    // Remove leading slash, any query parameters and fragment
     let path = path.trim_start_matches('/');
-    let path = path.split('?').next().unwrap_or(path);
+    let path = path.split(['?', '#']).next().unwrap_or(path);
     
     // Split by '/' and get slug and id
-    let parts: Vec<&str> = path.split('/').collect();
+    let mut parts = path.split('/');
+    let slug = parts.next()?;
+    let id = parts.next()?;
     
-    if parts.len() >= 2 {
-        let slug = parts[0];
-        let id = parts[1];
-        
-        // Validate that both are non-empty
-        if !slug.is_empty() && !id.is_empty() {
-            return Some((slug.to_string(), id.to_string()));
+    // Validate that both are non-empty
+    if slug.is_empty() || id.is_empty() {
+        return None;
+    }
+    Some((slug.to_string(), id.to_string()))
+}
+
+/// Detects a pasted prehraj.to video URL or path and extracts slug and ID
+///
+/// Unlike [`extract_video_info`], only input that is clearly a link is
+/// accepted: a URL on the prehraj.to host (with or without scheme or
+/// `www.`) or a path starting with `/`. Plain text returns `None`.
+///
+/// # Arguments
+/// * `input` - User input, e.g. a search box value
+///
+/// # Returns
+/// `Some((slug, id))` if the input is a video link, `None` otherwise
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::parse_video_link;
+/// let info = parse_video_link("https://www.prehraj.to/doctor-who/63aba7f51f6cf");
+/// assert_eq!(info, Some(("doctor-who".to_string(), "63aba7f51f6cf".to_string())));
+/// assert_eq!(parse_video_link("doctor who"), None);
+/// ```
+pub fn parse_video_link(input: &str) -> Option<(String, String)> {
+    let trimmed = input.trim();
+    let without_scheme = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+        .unwrap_or(trimmed);
+    let without_www = without_scheme
+        .strip_prefix("www.")
+        .unwrap_or(without_scheme);
+
+    let path = if let Some(path) = without_www.strip_prefix("prehraj.to") {
+        if !path.starts_with('/') {
+            return None;
         }
+        path
+    } else if trimmed.starts_with('/') {
+        trimmed
+    } else {
+        return None;
+    };
+
+    extract_video_info(path)
+}
+
+/// Strips tracking/analytics query parameters from a CDN URL
+///
+/// Keeps only the parameters the CDN needs (`token`, `expires`, `filename`),
+/// preserving their original order and encoding. A `#fragment` is kept as
+/// is. URLs without a query string are returned unchanged.
+///
+/// # Arguments
+/// * `url` - CDN URL (e.g., from [`crate::PrehrajtoScraper::get_direct_url`])
+///
+/// # Returns
+/// The URL with only the essential query parameters
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::strip_cdn_tracking;
+/// let url = strip_cdn_tracking("https://cdn.premiumcdn.net/a.mp4?token=x&utm_source=y&expires=1");
+/// assert_eq!(url, "https://cdn.premiumcdn.net/a.mp4?token=x&expires=1");
+/// ```
+pub fn strip_cdn_tracking(url: &str) -> String {
+    let (url, fragment) = match url.find('#') {
+        Some(index) => url.split_at(index),
+        None => (url, ""),
+    };
+    let Some((base, query)) = url.split_once('?') else {
+        return format!("{}{}", url, fragment);
+    };
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|param| {
+            let key = param.split('=').next().unwrap_or(param);
+            CDN_REQUIRED_PARAMS.contains(&key)
+        })
+        .collect();
+
+    if kept.is_empty() {
+        format!("{}{}", base, fragment)
+    } else {
+        format!("{}?{}{}", base, kept.join("&"), fragment)
     }
-    
-    None
+}
+
+/// Builds a stable key identifying the file behind a CDN URL
+///
+/// Every resolution of a video yields a new `token` and `expires`, but the
+/// host, path and `filename` stay the same. The key is the lowercased host
+/// and the path, plus the `filename` parameter if present; all other query
+/// parameters and the fragment are ignored.
+///
+/// # Arguments
+/// * `url` - Absolute CDN URL
+///
+/// # Returns
+/// The key, or `None` if `url` isn't an absolute http(s) URL
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::cdn_file_key;
+/// let a = cdn_file_key("https://cdn.premiumcdn.net/123/abc?filename=a.mkv&token=x&expires=1");
+/// let b = cdn_file_key("https://cdn.premiumcdn.net/123/abc?token=y&expires=2&filename=a.mkv");
+/// assert_eq!(a, b);
+/// assert_eq!(a.as_deref(), Some("cdn.premiumcdn.net/123/abc?filename=a.mkv"));
+/// ```
+pub fn cdn_file_key(url: &str) -> Option<String> {
+    let trimmed = url.trim();
+    let rest = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))?;
+    let rest = rest.split('#').next().unwrap_or(rest);
+    let (location, query) = match rest.split_once('?') {
+        Some((location, query)) => (location, Some(query)),
+        None => (rest, None),
+    };
+
+    let (host, path) = match location.split_once('/') {
+        Some((host, path)) => (host, path),
+        None => (location, ""),
+    };
+    if host.is_empty() {
+        return None;
+    }
+
+    let mut key = format!("{}/{}", host.to_lowercase(), path);
+    let filename = query.and_then(|query| {
+        query
+            .split('&')
+            .find_map(|param| param.strip_prefix("filename="))
+    });
+    if let Some(filename) = filename {
+        key.push_str("?filename=");
+        key.push_str(filename);
+    }
+    Some(key)
+}
+
+/// Reads the expiry time from a CDN URL's `expires` parameter
+///
+/// # Arguments
+/// * `url` - CDN URL (e.g., from [`crate::PrehrajtoScraper::get_direct_url`])
+///
+/// # Returns
+/// The expiry as Unix seconds, or `None` if the URL has no numeric `expires`
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::cdn_url_expires;
+/// let url = "https://cdn.premiumcdn.net/a.mp4?token=x&expires=1700000000";
+/// assert_eq!(cdn_url_expires(url), Some(1700000000));
+/// assert_eq!(cdn_url_expires("https://cdn.premiumcdn.net/a.mp4"), None);
+/// ```
+pub fn cdn_url_expires(url: &str) -> Option<u64> {
+    let (_, query) = url.split_once('?')?;
+    let query = query.split('#').next().unwrap_or(query);
+    query.split('&').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        (key == "expires").then(|| value.parse().ok()).flatten()
+    })
+}
+
+/// Percent-decodes the path of a CDN URL
+///
+/// CDN links sometimes arrive percent-encoded (`https%3A%2F%2F…`, `%20` in
+/// the path) on top of HTML entities. When the scheme itself is encoded the
+/// whole URL was encoded as one string and is decoded once, except for a
+/// query string after a literal `?`, which is kept byte-for-byte so `token`
+/// and `expires` keep their exact encoding. Otherwise only the path is
+/// decoded, and escapes that would change the URL's structure (`%2F`,
+/// `%3F`, `%23`, `%25`) stay encoded so the server sees the same resource
+/// path. Spaces and control characters are never decoded. If decoding
+/// wouldn't yield valid UTF-8 the URL is returned unchanged.
+///
+/// # Arguments
+/// * `url` - CDN URL, already HTML-entity decoded
+///
+/// # Returns
+/// The URL with its path percent-decoded
+///
+/// # Example
+/// ```
+/// use prehrajto_core::url::decode_cdn_path;
+/// let url = decode_cdn_path("https%3A%2F%2Fcdn.premiumcdn.net%2Fa%2Fvideo.mp4?token=a%2Bb");
+/// assert_eq!(url, "https://cdn.premiumcdn.net/a/video.mp4?token=a%2Bb");
+/// ```
+pub fn decode_cdn_path(url: &str) -> String {
+    let fully_encoded = ["https%3A%2F%2F", "http%3A%2F%2F"].iter().any(|prefix| {
+        url.get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+    });
+
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (url, None),
+    };
+
+    let bytes = base.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = bytes.get(i + 1..i + 3).and_then(decode_hex_pair)
+            && (fully_encoded || !matches!(byte, b'/' | b'?' | b'#' | b'%'))
+            && byte != b' '
+            && !byte.is_ascii_control()
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    let Ok(base) = String::from_utf8(decoded) else {
+        return url.to_string();
+    };
+    match query {
+        Some(query) => format!("{}?{}", base, query),
+        None => base,
+    }
+}
+
+/// Decodes two ASCII hex digits into a byte
+fn decode_hex_pair(pair: &[u8]) -> Option<u8> {
+    let text = std::str::from_utf8(pair).ok()?;
+    if !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u8::from_str_radix(text, 16).ok()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_cdn_file_key_ignores_token_and_expires() {
+        let first = cdn_file_key(
+            "https://pf-storage3.premiumcdn.net/165065360/abc?filename=Movie.mkv&token=a&expires=1",
+        );
+        let second = cdn_file_key(
+            "https://PF-STORAGE3.premiumcdn.net/165065360/abc?token=b&expires=2&filename=Movie.mkv#t=5",
+        );
+        assert!(first.is_some());
+        assert_eq!(first, second);
+
+        let plain = cdn_file_key("https://cdn.premiumcdn.net/a/720p.mp4?token=a&expires=1");
+        assert_eq!(plain.as_deref(), Some("cdn.premiumcdn.net/a/720p.mp4"));
+    }
+
+    #[test]
+    fn test_cdn_file_key_differs_for_other_files() {
+        let base = cdn_file_key("https://cdn.premiumcdn.net/a/720p.mp4?token=a");
+        let other_path = cdn_file_key("https://cdn.premiumcdn.net/a/1080p.mp4?token=a");
+        let other_host = cdn_file_key("https://cdn2.premiumcdn.net/a/720p.mp4?token=a");
+        assert_ne!(base, other_path);
+        assert_ne!(base, other_host);
+        assert_ne!(
+            cdn_file_key("https://cdn.premiumcdn.net/a?filename=one.mkv"),
+            cdn_file_key("https://cdn.premiumcdn.net/a?filename=two.mkv")
+        );
+        assert_eq!(cdn_file_key("/a/720p.mp4"), None);
+        assert_eq!(cdn_file_key("https:///a.mp4"), None);
+    }
+
+    #[test]
+    fn test_cdn_url_expires() {
+        assert_eq!(
+            cdn_url_expires("https://cdn.premiumcdn.net/a.mp4?expires=42&token=x"),
+            Some(42)
+        );
+        assert_eq!(
+            cdn_url_expires("https://cdn.premiumcdn.net/a.mp4?token=x&expires=soon"),
+            None
+        );
+        assert_eq!(
+            cdn_url_expires("https://cdn.premiumcdn.net/a.mp4?not_expires=42"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode_cdn_path_segment() {
+        let url = decode_cdn_path(
+            "https://cdn.premiumcdn.net/files/Doctor%20Who%2FS07E05%C3%A9.mp4?token=abc&expires=1",
+        );
+        assert_eq!(
+            url,
+            "https://cdn.premiumcdn.net/files/Doctor%20Who%2FS07E05é.mp4?token=abc&expires=1"
+        );
+    }
+
+    #[test]
+    fn test_decode_cdn_path_fully_encoded() {
+        assert_eq!(
+            decode_cdn_path("https%3A%2F%2Fcdn.premiumcdn.net%2Fa%2Fb%2520c.mp4%3Ftoken%3Dx"),
+            "https://cdn.premiumcdn.net/a/b%20c.mp4?token=x"
+        );
+    }
+
+    #[test]
+    fn test_decode_cdn_path_keeps_query_intact() {
+        let url = "https://cdn.premiumcdn.net/a.mp4?token=a%2Fb%3D&expires=1%3A2";
+        assert_eq!(decode_cdn_path(url), url);
+    }
+
+    #[test]
+    fn test_decode_cdn_path_keeps_structural_escapes() {
+        let url = "https://cdn.premiumcdn.net/a%2Fb%3Fc%23d%25e.mp4";
+        assert_eq!(decode_cdn_path(url), url);
+    }
+
+    #[test]
+    fn test_decode_cdn_path_utf8_and_invalid() {
+        assert_eq!(
+            decode_cdn_path("https://cdn.premiumcdn.net/Andel%C3%A9.mp4"),
+            "https://cdn.premiumcdn.net/Andelé.mp4"
+        );
+        let invalid = "https://cdn.premiumcdn.net/a%FF.mp4";
+        assert_eq!(decode_cdn_path(invalid), invalid);
+    }
+
     #[test]
     fn test_build_video_url() {
         let url = build_video_url("doctor-who-s07e05", "63aba7f51f6cf");
         assert_eq!(url, "https://prehraj.to/doctor-who-s07e05/63aba7f51f6cf");
     }
 
+    #[test]
+    fn test_build_video_url_empty_slug() {
+        assert_eq!(
+            build_video_url("", "abc123"),
+            "https://prehraj.to/video/abc123"
+        );
+        assert_eq!(
+            build_video_url("  \t", "abc123"),
+            "https://prehraj.to/video/abc123"
+        );
+        assert_eq!(
+            build_download_url("", "abc123"),
+            "https://prehraj.to/video/abc123?do=download"
+        );
+        assert_eq!(
+            build_download_url(" ", "abc123"),
+            "https://prehraj.to/video/abc123?do=download"
+        );
+    }
+
+    #[test]
+    fn test_build_video_url_keeps_slug() {
+        assert_eq!(
+            build_video_url("my-movie", "abc123"),
+            "https://prehraj.to/my-movie/abc123"
+        );
+    }
+
     #[test]
     fn test_build_download_url() {
         let url = build_download_url("doctor-who-s07e05", "63aba7f51f6cf");
@@ -135,6 +665,187 @@ mod tests {
         assert_eq!(url, "https://prehraj.to/hledej/doctor%20who%20s07e05");
     }
 
+    #[test]
+    fn test_build_search_url_raw_query() {
+        let url = build_search_url("Tom & Jerry 100%");
+        assert_eq!(url, "https://prehraj.to/hledej/Tom%20%26%20Jerry%20100%25");
+    }
+
+    #[test]
+    fn test_build_search_url_html_entities() {
+        let url = build_search_url("Tom &amp; Jerry");
+        assert_eq!(url, "https://prehraj.to/hledej/Tom%20%26%20Jerry");
+    }
+
+    #[test]
+    fn test_build_search_url_already_encoded() {
+        let url = build_search_url("doctor%20who%20s07e05");
+        assert_eq!(url, "https://prehraj.to/hledej/doctor%20who%20s07e05");
+
+        // Idempotent for the encoded form of a raw query
+        let raw = "Andělé & démoni";
+        let encoded = urlencoding::encode(raw).into_owned();
+        assert_eq!(build_search_url(&encoded), build_search_url(raw));
+    }
+
+    #[test]
+    fn test_normalize_search_query_invalid_escape() {
+        // %zz isn't an escape; %C4 alone isn't valid UTF-8
+        assert_eq!(normalize_search_query("50%zz"), "50%zz");
+        assert_eq!(normalize_search_query("bad%C4"), "bad%C4");
+    }
+
+    #[test]
+    fn test_build_search_url_with_options_default() {
+        let url = build_search_url_with_options("doctor who", &SearchOptions::default());
+        assert_eq!(url, build_search_url("doctor who"));
+    }
+
+    #[test]
+    fn test_build_search_url_match_all() {
+        let options = SearchOptions::default().match_all(true);
+        let url = build_search_url_with_options("doctor  who s07e05", &options);
+        assert_eq!(url, "https://prehraj.to/hledej/doctor+who+s07e05");
+    }
+
+    #[test]
+    fn test_build_search_url_match_all_encodes_terms() {
+        let options = SearchOptions::default().match_all(true);
+        let url = build_search_url_with_options("andělé c++", &options);
+        assert_eq!(url, "https://prehraj.to/hledej/and%C4%9Bl%C3%A9+c%2B%2B");
+    }
+
+    #[test]
+    fn test_build_search_url_page_and_sort() {
+        let options = SearchOptions::default()
+            .page(3)
+            .sort(SearchSort::Longest)
+            .match_all(true);
+        let url = build_search_url_with_options("doctor who", &options);
+        assert_eq!(
+            url,
+            "https://prehraj.to/hledej/doctor+who?vp-page=3&order=longest"
+        );
+    }
+
+    #[test]
+    fn test_parse_video_link() {
+        let expected = Some(("doctor-who".to_string(), "63aba7f51f6cf".to_string()));
+        for input in [
+            "https://prehraj.to/doctor-who/63aba7f51f6cf",
+            " http://prehraj.to/doctor-who/63aba7f51f6cf?x=1 ",
+            "www.prehraj.to/doctor-who/63aba7f51f6cf#t=10",
+            "/doctor-who/63aba7f51f6cf",
+        ] {
+            assert_eq!(parse_video_link(input), expected, "input: {}", input);
+        }
+
+        for input in [
+            "doctor who",
+            "doctor/who",
+            "https://example.com/doctor-who/63aba7f51f6cf",
+            "prehraj.tov/doctor-who/63aba",
+        ] {
+            assert_eq!(parse_video_link(input), None, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_build_search_url_paged() {
+        assert_eq!(
+            build_search_url_paged("doctor who", 3),
+            "https://prehraj.to/hledej/doctor%20who?vp-page=3"
+        );
+        for page in [0, 1] {
+            let url = build_search_url_paged("doctor who", page);
+            assert_eq!(url, build_search_url("doctor who"));
+        }
+    }
+
+    #[test]
+    fn test_build_search_url_first_page_has_no_param() {
+        let options = SearchOptions::default().page(1);
+        let url = build_search_url_with_options("doctor who", &options);
+        assert_eq!(url, build_search_url("doctor who"));
+
+        let options = SearchOptions::default().sort(SearchSort::Relevance);
+        let url = build_search_url_with_options("doctor who", &options);
+        assert_eq!(url, build_search_url("doctor who"));
+    }
+
+    #[test]
+    fn test_strip_cdn_tracking_keeps_required_params() {
+        let url = "https://pf-storage4.premiumcdn.net/abc/video.mp4?utm_source=web&token=abc%3D&ref=player&expires=1700000000&filename=Movie%20(2020).mp4&sid=42";
+        assert_eq!(
+            strip_cdn_tracking(url),
+            "https://pf-storage4.premiumcdn.net/abc/video.mp4?token=abc%3D&expires=1700000000&filename=Movie%20(2020).mp4"
+        );
+    }
+
+    #[test]
+    fn test_strip_cdn_tracking_without_query() {
+        let url = "https://pf-storage4.premiumcdn.net/abc/video.mp4";
+        assert_eq!(strip_cdn_tracking(url), url);
+    }
+
+    #[test]
+    fn test_strip_cdn_tracking_only_tracking_params() {
+        let url = "https://pf-storage4.premiumcdn.net/abc/video.mp4?utm_source=web";
+        assert_eq!(
+            strip_cdn_tracking(url),
+            "https://pf-storage4.premiumcdn.net/abc/video.mp4"
+        );
+    }
+
+    #[test]
+    fn test_strip_cdn_tracking_keeps_fragment() {
+        let url = "https://pf-storage4.premiumcdn.net/abc/video.mp4?token=abc&utm_source=web#t=60";
+        assert_eq!(
+            strip_cdn_tracking(url),
+            "https://pf-storage4.premiumcdn.net/abc/video.mp4?token=abc#t=60"
+        );
+
+        let url = "https://pf-storage4.premiumcdn.net/abc/video.mp4?utm_source=web#t=60&ref=x";
+        assert_eq!(
+            strip_cdn_tracking(url),
+            "https://pf-storage4.premiumcdn.net/abc/video.mp4#t=60&ref=x"
+        );
+
+        let url = "https://pf-storage4.premiumcdn.net/abc/video.mp4#a?token=x";
+        assert_eq!(strip_cdn_tracking(url), url);
+    }
+
+    #[test]
+    fn test_site_path() {
+        assert_eq!(
+            site_path("https://prehraj.to/serialy/doctor-who"),
+            "/serialy/doctor-who"
+        );
+        assert_eq!(site_path("/serialy/doctor-who"), "/serialy/doctor-who");
+        assert_eq!(site_path("serialy/doctor-who"), "/serialy/doctor-who");
+        assert_eq!(site_path("https://prehraj.to"), "/");
+    }
+
+    #[test]
+    fn test_site_url() {
+        assert_eq!(
+            site_url("/profil/uploader/123"),
+            "https://prehraj.to/profil/uploader/123"
+        );
+        assert_eq!(
+            site_url("profil/uploader"),
+            "https://prehraj.to/profil/uploader"
+        );
+        assert_eq!(
+            site_url(" http://example.com/profil/x "),
+            "http://example.com/profil/x"
+        );
+        assert_eq!(
+            site_url(&site_path("https://prehraj.to/a/b")),
+            "https://prehraj.to/a/b"
+        );
+    }
+
     #[test]
     fn test_extract_video_info_from_path() {
         let info = extract_video_info("/doctor-who/63aba7f51f6cf");
@@ -153,6 +864,31 @@ mod tests {
         assert_eq!(info, Some(("doctor-who".to_string(), "63aba7f51f6cf".to_string())));
     }
 
+    #[test]
+    fn test_extract_video_info_with_fragment() {
+        let expected = Some(("doctor-who".to_string(), "63aba7f51f6cf".to_string()));
+        assert_eq!(
+            extract_video_info("/doctor-who/63aba7f51f6cf#t=30"),
+            expected
+        );
+        assert_eq!(
+            extract_video_info("https://prehraj.to/doctor-who/63aba7f51f6cf?a=1&a=2#t=30"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_extract_video_info_query_with_slash() {
+        let expected = Some(("doctor-who".to_string(), "63aba7f51f6cf".to_string()));
+        assert_eq!(
+            extract_video_info("/doctor-who/63aba7f51f6cf?next=/a/b"),
+            expected
+        );
+        // Query before the ID → not a video URL
+        assert_eq!(extract_video_info("/doctor-who?next=/63aba7f51f6cf"), None);
+        assert_eq!(extract_video_info("/doctor-who#/63aba7f51f6cf"), None);
+    }
+
     #[test]
     fn test_extract_video_info_invalid_single_part() {
         let info = extract_video_info("/only-slug");
@@ -0,0 +1,490 @@
+//! Public utility helpers
+//!
+//! Small helpers useful to callers working with data scraped from prehraj.to.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::error::{PrehrajtoError, Result};
+use crate::text::{fold_text, parse_duration_to_secs, parse_size_to_bytes};
+use crate::types::{ScoringConfig, VideoResult};
+
+/// Site-name suffixes appended to page titles (matched case-insensitively)
+const TITLE_SUFFIXES: &[&str] = &[
+    " | prehraj.to",
+    " - prehraj.to",
+    " – prehraj.to",
+    " — prehraj.to",
+];
+
+/// Strips the site-name suffix from a title and trims it
+///
+/// Titles taken from `<title>` or `og:title` metadata carry a suffix such as
+/// `" | Prehraj.to"` or `" - Prehraj.to"`. Titles without a suffix are
+/// only trimmed.
+///
+/// # Example
+/// ```
+/// use prehrajto_core::util::clean_title;
+/// assert_eq!(clean_title("Doctor Who S07E05 | Prehraj.to"), "Doctor Who S07E05");
+/// assert_eq!(clean_title("  Doctor Who  "), "Doctor Who");
+/// ```
+pub fn clean_title(s: &str) -> String {
+    let trimmed = s.trim();
+    let lower = trimmed.to_lowercase();
+
+    for suffix in TITLE_SUFFIXES {
+        if lower.ends_with(suffix) && trimmed.len() >= suffix.len() {
+            // The matched tail lowercases byte-for-byte, so its length is the same
+            return trimmed[..trimmed.len() - suffix.len()].trim().to_string();
+        }
+    }
+
+    trimmed.to_string()
+}
+
+/// Common Czech words typed without diacritics, with their diacritic form
+/// (all lowercase)
+const DIACRITIC_WORDS: &[(&str, &str)] = &[
+    ("andele", "andělé"),
+    ("cesky", "česky"),
+    ("ceske", "české"),
+    ("dil", "díl"),
+    ("dobyvaji", "dobývají"),
+    ("orisky", "oříšky"),
+    ("pelisky", "pelíšky"),
+    ("pohadka", "pohádka"),
+    ("pohadky", "pohádky"),
+    ("serial", "seriál"),
+    ("serie", "série"),
+    ("tri", "tři"),
+    ("vanoce", "vánoce"),
+];
+
+/// Suggests the diacritic form of a query typed without it
+///
+/// Best-effort "did you mean" helper: each word found in a small built-in
+/// list of common Czech words is replaced by its diacritic form, keeping a
+/// leading capital. Other words are left as typed.
+///
+/// # Arguments
+/// * `query` - Search query as typed by the user
+///
+/// # Returns
+/// The suggested query, or `None` if no word has a known diacritic form
+///
+/// # Example
+/// ```
+/// use prehrajto_core::util::suggest_diacritics;
+/// assert_eq!(
+///     suggest_diacritics("Tri orisky pro Popelku"),
+///     Some("Tři oříšky pro Popelku".to_string())
+/// );
+/// assert_eq!(suggest_diacritics("Matrix"), None);
+/// ```
+pub fn suggest_diacritics(query: &str) -> Option<String> {
+    let mut changed = false;
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|word| {
+            let lower = word.to_lowercase();
+            let accented = DIACRITIC_WORDS
+                .iter()
+                .find(|(plain, _)| *plain == lower)
+                .map(|(_, accented)| *accented);
+            let Some(accented) = accented else {
+                return word.to_string();
+            };
+            changed = true;
+            if word.starts_with(char::is_uppercase) {
+                capitalize(accented)
+            } else {
+                accented.to_string()
+            }
+        })
+        .collect();
+
+    changed.then(|| words.join(" "))
+}
+
+/// Uppercases the first character of a word
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Scores how well a title matches a query by token overlap
+///
+/// Both strings are folded (lowercased, diacritics and punctuation
+/// stripped) and split into words. The score is the number of distinct
+/// query words that also appear in the title; stop-words from `config`
+/// never count.
+///
+/// # Example
+/// ```
+/// use prehrajto_core::ScoringConfig;
+/// use prehrajto_core::util::match_score;
+///
+/// let config = ScoringConfig::default();
+/// assert_eq!(match_score("The Matrix", "Matrix (1999) CZ dabing", &config), 1);
+/// assert_eq!(match_score("The Matrix", "The Batman CZ", &config), 0);
+/// ```
+pub fn match_score(query: &str, title: &str, config: &ScoringConfig) -> usize {
+    let folded_title = fold_text(title);
+    let title_tokens: HashSet<&str> = folded_title.split(' ').collect();
+
+    let folded_query = fold_text(query);
+    let query_tokens: HashSet<&str> = folded_query
+        .split(' ')
+        .filter(|token| !token.is_empty() && !config.is_stop_word(token))
+        .collect();
+
+    query_tokens
+        .iter()
+        .filter(|token| title_tokens.contains(*token))
+        .count()
+}
+
+/// Groups search results that are the same title from different uploads
+///
+/// Titles are compared folded (see [`match_score`]) with episode markers
+/// such as `S07E05` or `7x05` removed. Groups are returned in order of
+/// first appearance, keyed by that folded title; each group is sorted by
+/// file size, largest first, with unknown sizes last.
+///
+/// # Example
+/// ```
+/// use prehrajto_core::util::group_by_title;
+/// # use prehrajto_core::VideoResult;
+/// # fn result(name: &str, id: &str, size: &str) -> VideoResult {
+/// #     VideoResult {
+/// #         name: name.to_string(),
+/// #         original_title: None,
+/// #         url: String::new(),
+/// #         video_id: id.to_string(),
+/// #         video_slug: String::new(),
+/// #         download_url: String::new(),
+/// #         duration: None,
+/// #         duration_secs: None,
+/// #         quality: None,
+/// #         resolution: None,
+/// #         file_size: Some(size.to_string()),
+/// #         file_size_bytes: None,
+/// #         upload_date: None,
+/// #         position: 0,
+/// #     }
+/// # }
+/// let groups = group_by_title(vec![
+///     result("Pelíšky", "a", "700 MB"),
+///     result("PELISKY", "b", "1.4 GB"),
+/// ]);
+/// assert_eq!(groups.len(), 1);
+/// assert_eq!(groups[0].0, "pelisky");
+/// assert_eq!(groups[0].1[0].video_id, "b");
+/// ```
+pub fn group_by_title(results: Vec<VideoResult>) -> Vec<(String, Vec<VideoResult>)> {
+    let mut groups: Vec<(String, Vec<VideoResult>)> = Vec::new();
+
+    for result in results {
+        let key = title_key(&result.name);
+        match groups.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, members)) => members.push(result),
+            None => groups.push((key, vec![result])),
+        }
+    }
+
+    for (_, members) in &mut groups {
+        sort_by_size_desc(members);
+    }
+    groups
+}
+
+/// Sorts results by resolution badge, highest first
+///
+/// Results without a [`VideoResult::resolution`] go last; the sort is
+/// stable, so ties keep their page order.
+pub fn sort_by_resolution_desc(results: &mut [VideoResult]) {
+    // `None` sorts below every value, so reversing puts it last
+    results.sort_by_key(|result| Reverse(result.resolution));
+}
+
+/// Sorts results by file size, largest first
+///
+/// Uses [`VideoResult::file_size_bytes`], parsing `file_size` when it is
+/// unset (e.g. results deserialized from before the field existed).
+/// Unknown sizes go last; ties keep their page order.
+pub fn sort_by_size_desc(results: &mut [VideoResult]) {
+    results.sort_by_key(|result| {
+        Reverse(
+            result
+                .file_size_bytes
+                .or_else(|| result.file_size.as_deref().and_then(parse_size_to_bytes)),
+        )
+    });
+}
+
+/// Sorts results by duration, longest first
+///
+/// Uses [`VideoResult::duration_secs`], parsing `duration` when it is
+/// unset. Unknown durations go last; ties keep their page order.
+pub fn sort_by_duration_desc(results: &mut [VideoResult]) {
+    results.sort_by_key(|result| {
+        Reverse(
+            result
+                .duration_secs
+                .or_else(|| result.duration.as_deref().and_then(parse_duration_to_secs)),
+        )
+    });
+}
+
+/// Runs an operation under an overall deadline
+///
+/// Per-request timeouts bound each HTTP call, but a flow of several
+/// requests with retries can take much longer; this bounds the whole thing.
+/// The operation is dropped (cancelling any request in flight) once the
+/// deadline passes.
+///
+/// # Arguments
+/// * `deadline` - Maximum time the whole operation may take
+/// * `operation` - The operation, e.g. a [`crate::PrehrajtoScraper`] method call
+///
+/// # Returns
+/// The operation's own result if it finishes in time
+///
+/// # Errors
+/// Returns `Timeout` if the deadline passes first, otherwise the
+/// operation's own errors
+pub async fn with_deadline<T>(
+    deadline: Duration,
+    operation: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::time::timeout(deadline, operation)
+        .await
+        .unwrap_or_else(|_| Err(PrehrajtoError::Timeout(deadline.as_millis() as u64)))
+}
+
+/// Folds a title and drops episode markers (`s07e05`, `7x05`)
+fn title_key(title: &str) -> String {
+    fold_text(title)
+        .split(' ')
+        .filter(|token| !is_episode_marker(token))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Checks for a folded episode marker token such as `s07e05` or `7x05`
+fn is_episode_marker(token: &str) -> bool {
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    if let Some(rest) = token.strip_prefix('s')
+        && let Some((season, episode)) = rest.split_once('e')
+    {
+        return digits(season) && digits(episode);
+    }
+    token
+        .split_once('x')
+        .is_some_and(|(season, episode)| digits(season) && digits(episode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_deadline() {
+        let quick = with_deadline(Duration::from_millis(200), async { Ok(7) }).await;
+        assert_eq!(quick.unwrap(), 7);
+
+        let slow = with_deadline(Duration::from_millis(20), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(7)
+        })
+        .await;
+        assert!(matches!(slow, Err(PrehrajtoError::Timeout(20))));
+    }
+
+    fn result(name: &str, id: &str, size: Option<&str>) -> VideoResult {
+        VideoResult {
+            name: name.to_string(),
+            original_title: None,
+            url: String::new(),
+            video_id: id.to_string(),
+            video_slug: String::new(),
+            download_url: String::new(),
+            duration: None,
+            duration_secs: None,
+            quality: None,
+            resolution: None,
+            file_size: size.map(str::to_string),
+            file_size_bytes: size.and_then(parse_size_to_bytes),
+            upload_date: None,
+            position: 0,
+        }
+    }
+
+    #[test]
+    fn test_group_by_title() {
+        let groups = group_by_title(vec![
+            result("Doctor Who S07E05", "dw1", Some("700 MB")),
+            result("Matrix", "mx1", None),
+            result("doctor who 7x05", "dw2", Some("1.7 GB")),
+            result("Matrix", "mx2", Some("4 GB")),
+            result("Doctor.Who", "dw3", Some("350 MB")),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, "doctor who");
+        let ids: Vec<&str> = groups[0].1.iter().map(|r| r.video_id.as_str()).collect();
+        assert_eq!(ids, ["dw2", "dw1", "dw3"]);
+
+        assert_eq!(groups[1].0, "matrix");
+        let ids: Vec<&str> = groups[1].1.iter().map(|r| r.video_id.as_str()).collect();
+        assert_eq!(ids, ["mx2", "mx1"]);
+    }
+
+    /// Mixed set: known and unknown values, ties, and a size only set as text
+    fn mixed_results() -> Vec<VideoResult> {
+        let mut results = vec![
+            result("a", "a", Some("700 MB")),
+            result("b", "b", None),
+            result("c", "c", Some("4 GB")),
+            result("d", "d", Some("700 MB")),
+        ];
+        results[0].resolution = Some(720);
+        results[1].resolution = Some(2160);
+        results[3].resolution = Some(1080);
+        results[0].duration_secs = Some(2660);
+        results[2].duration_secs = Some(7200);
+        results[3].duration = Some("01:00:00".to_string());
+        results[2].file_size_bytes = None;
+        results
+    }
+
+    fn ids(results: &[VideoResult]) -> Vec<&str> {
+        results.iter().map(|r| r.video_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_by_resolution_desc() {
+        let mut results = mixed_results();
+        sort_by_resolution_desc(&mut results);
+        assert_eq!(ids(&results), ["b", "d", "a", "c"]);
+    }
+
+    #[test]
+    fn test_sort_by_size_desc() {
+        let mut results = mixed_results();
+        sort_by_size_desc(&mut results);
+        // "c" only has the text size; "a" and "d" tie and keep their order
+        assert_eq!(ids(&results), ["c", "a", "d", "b"]);
+    }
+
+    #[test]
+    fn test_sort_by_duration_desc() {
+        let mut results = mixed_results();
+        sort_by_duration_desc(&mut results);
+        assert_eq!(ids(&results), ["c", "d", "a", "b"]);
+    }
+
+    #[test]
+    fn test_is_episode_marker() {
+        assert!(is_episode_marker("s07e05"));
+        assert!(is_episode_marker("7x05"));
+        assert!(!is_episode_marker("sex"));
+        assert!(!is_episode_marker("x"));
+        assert!(!is_episode_marker("1080p"));
+    }
+
+    #[test]
+    fn test_suggest_diacritics_known_words() {
+        assert_eq!(suggest_diacritics("pelisky"), Some("pelíšky".to_string()));
+        assert_eq!(
+            suggest_diacritics("Andele  dobyvaji Manhattan"),
+            Some("Andělé dobývají Manhattan".to_string())
+        );
+    }
+
+    #[test]
+    fn test_suggest_diacritics_unknown_or_already_accented() {
+        assert_eq!(suggest_diacritics("Doctor Who"), None);
+        assert_eq!(suggest_diacritics("Pelíšky"), None);
+        assert_eq!(suggest_diacritics(""), None);
+    }
+
+    #[test]
+    fn test_match_score_counts_overlap() {
+        let config = ScoringConfig::default();
+        assert_eq!(
+            match_score(
+                "Andělé dobývají Manhattan",
+                "Andele dobyvaji Manhattan",
+                &config
+            ),
+            3
+        );
+        assert_eq!(match_score("Matrix", "Shrek", &config), 0);
+    }
+
+    #[test]
+    fn test_match_score_ignores_stop_words() {
+        let config = ScoringConfig::default();
+        assert_eq!(
+            match_score("The Film CZ dabing", "The Film CZ dabing", &config),
+            0
+        );
+    }
+
+    #[test]
+    fn test_match_score_correct_match_wins_over_filler() {
+        let config = ScoringConfig::default();
+        let query = "The Matrix film CZ dabing";
+        let correct = match_score(query, "Matrix 1999", &config);
+        let filler = match_score(query, "The Batman film CZ dabing", &config);
+        assert!(correct > filler);
+
+        // Without stop-words the filler title would have won
+        let none = ScoringConfig::with_stop_words(Vec::<String>::new());
+        assert!(
+            match_score(query, "Matrix 1999", &none)
+                < match_score(query, "The Batman film CZ dabing", &none)
+        );
+    }
+
+    #[test]
+    fn test_scoring_config_folds_stop_words() {
+        let config = ScoringConfig::with_stop_words(["Titulky"]);
+        assert!(config.is_stop_word("titulky"));
+        assert_eq!(match_score("Matrix titulky", "Matrix Titulky", &config), 1);
+    }
+
+    #[test]
+    fn test_clean_title_pipe_suffix() {
+        assert_eq!(clean_title("Doctor Who | Prehraj.to"), "Doctor Who");
+    }
+
+    #[test]
+    fn test_clean_title_dash_suffixes() {
+        assert_eq!(clean_title("Doctor Who - Prehraj.to"), "Doctor Who");
+        assert_eq!(clean_title("Doctor Who – Prehraj.to"), "Doctor Who");
+        assert_eq!(clean_title("Doctor Who — prehraj.to "), "Doctor Who");
+    }
+
+    #[test]
+    fn test_clean_title_case_insensitive() {
+        assert_eq!(clean_title("Andělé | PREHRAJ.TO"), "Andělé");
+    }
+
+    #[test]
+    fn test_clean_title_without_suffix() {
+        assert_eq!(
+            clean_title("Doctor Who - 7. série"),
+            "Doctor Who - 7. série"
+        );
+        assert_eq!(clean_title("  Prehraj.to  "), "Prehraj.to");
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! This module contains all Tauri command implementations.
 
-use prehrajto_core::VideoResult;
+use prehrajto_core::{FullVideoInfo, VideoResult};
 use tauri::State;
 
 use crate::ScraperState;
@@ -83,3 +83,31 @@ pub async fn search_movie(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Get metadata, sources, subtitles and the best direct URL for a video
+///
+/// Everything comes from a single fetch of the video page, replacing
+/// separate metadata, sources and direct URL invokes.
+///
+/// # Arguments
+/// * `state` - Managed ScraperState from Tauri
+/// * `video_slug` - URL-friendly video slug
+/// * `video_id` - Unique video ID
+///
+/// # Returns
+/// Combined video info; `best_direct_url` is null if no CDN URL was found
+///
+/// # Errors
+/// Returns error message as String if fetching the page fails
+#[tauri::command]
+pub async fn get_full_video_info(
+    state: State<'_, ScraperState>,
+    video_slug: String,
+    video_id: String,
+) -> Result<FullVideoInfo, String> {
+    let scraper = state.scraper.lock().await;
+    scraper
+        .get_full_video_info(&video_slug, &video_id)
+        .await
+        .map_err(|e| e.to_string())
+}
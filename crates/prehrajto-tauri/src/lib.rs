@@ -28,6 +28,12 @@
 //!   videoSlug: 'doctor-who-s07e05',
 //!   videoId: '63aba7f51f6cf'
 //! });
+//!
+//! // Get metadata, sources, subtitles and the best direct URL at once
+//! const info = await invoke('plugin:prehrajto|get_full_video_info', {
+//!   videoSlug: 'doctor-who-s07e05',
+//!   videoId: '63aba7f51f6cf'
+//! });
 //! ```
 
 use std::sync::Arc;
@@ -93,7 +99,8 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
         .invoke_handler(tauri::generate_handler![
             commands::search_videos,
             commands::get_download_url,
-            commands::search_movie
+            commands::search_movie,
+            commands::get_full_video_info
         ])
         .setup(|app, _api| {
             let state = ScraperState::new().map_err(Box::<dyn std::error::Error>::from)?;
@@ -121,4 +128,74 @@ mod tests {
         let state = ScraperState::default();
         assert!(state.scraper.try_lock().is_ok());
     }
+
+    /// Mock app context whose ACL lets local webviews call `command`
+    ///
+    /// Tauri rejects plugin commands that no capability allows, and the
+    /// plain mock context grants none.
+    fn mock_context_allowing(command: &str) -> tauri::Context<tauri::test::MockRuntime> {
+        use tauri::scope::fs::Pattern;
+        use tauri::utils::acl::resolved::{Resolved, ResolvedCommand};
+
+        let mut resolved = Resolved::default();
+        resolved.allowed_commands.insert(
+            command.to_string(),
+            vec![ResolvedCommand {
+                webviews: vec![Pattern::new("*").expect("valid pattern")],
+                ..Default::default()
+            }],
+        );
+
+        let mut context = tauri::test::mock_context(tauri::test::noop_assets());
+        *context.runtime_authority_mut() =
+            tauri::ipc::RuntimeAuthority::new(Default::default(), resolved);
+        context
+    }
+
+    #[test]
+    fn test_plugin_registers_with_app() {
+        const COMMAND: &str = "plugin:prehrajto|get_full_video_info";
+
+        // Building the app runs the plugin setup; invoking a command through
+        // IPC checks it is routed to the plugin's handler
+        let app = tauri::test::mock_builder()
+            .plugin(init())
+            .build(mock_context_allowing(COMMAND))
+            .expect("app with plugin should build");
+        assert!(app.try_state::<ScraperState>().is_some());
+
+        let webview = tauri::WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .expect("mock webview should build");
+        let response = tauri::test::get_ipc_response(
+            &webview,
+            tauri::webview::InvokeRequest {
+                cmd: COMMAND.into(),
+                callback: tauri::ipc::CallbackFn(0),
+                error: tauri::ipc::CallbackFn(1),
+                url: "tauri://localhost".parse().unwrap(),
+                body: tauri::ipc::InvokeBody::Json(serde_json::json!({
+                    "videoSlug": "doctor-who",
+                    "videoId": ""
+                })),
+                headers: Default::default(),
+                invoke_key: tauri::test::INVOKE_KEY.to_string(),
+            },
+        );
+
+        // An empty ID is rejected before any network access
+        let expected = prehrajto_core::PrehrajtoError::InvalidId("Video ID cannot be empty".into());
+        assert_eq!(
+            response.map(|body| body.deserialize::<serde_json::Value>().unwrap()),
+            Err(serde_json::Value::String(expected.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_full_video_info_serializes() {
+        let info = prehrajto_core::FullVideoInfo::default();
+        let json = serde_json::to_value(&info).expect("Serialization should succeed");
+        assert!(json.get("metadata").is_some());
+        assert!(json.get("best_direct_url").is_some());
+    }
 }